@@ -89,4 +89,20 @@ class RustIssClient
     {
         return $this->getJson('trend', $params);
     }
-}
\ No newline at end of file
+
+    /**
+     * Получает счетчики околоземных объектов (NeoWs) для дашборда.
+     */
+    public function getNeoCount(): array
+    {
+        return $this->getJson('/neo/count');
+    }
+
+    /**
+     * Получает активные тревоги по сближениям NEO (для баннера на дашборде).
+     */
+    public function getNeoAlerts(array $params = []): array
+    {
+        return $this->getJson('/neo/alerts', $params);
+    }
+}
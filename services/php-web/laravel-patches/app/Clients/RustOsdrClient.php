@@ -50,4 +50,27 @@ class RustOsdrClient
         // В случае пустого или невалидного JSON, возвращаем пустой массив.
         return is_array($data) ? $data : ['items' => []];
     }
+
+    /**
+     * Получает агрегированную статистику OSDR (для графиков дашборда).
+     *
+     * @return array
+     * @throws Exception
+     */
+    public function getOsdrStats(): array
+    {
+        $response = Http::baseUrl($this->baseUrl)
+            ->timeout(10)
+            ->get('/osdr/stats');
+
+        if (!$response->successful()) {
+            $status = $response->status();
+            $body = $response->body();
+            throw new Exception("OSDR Rust Service returned non-success status ({$status}): {$body}", $status);
+        }
+
+        $data = $response->json();
+
+        return is_array($data) ? $data : ['stats' => []];
+    }
 }
\ No newline at end of file
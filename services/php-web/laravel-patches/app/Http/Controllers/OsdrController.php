@@ -57,6 +57,7 @@ class OsdrController extends Controller
                 'page' => $page,
                 'totalPages' => ceil($total / $limit),
                 'limit' => $limit,
+                'stats' => $this->osdrService->getOsdrStats(),
                 'src' => (getenv('RUST_BASE') ?: 'http://rust_iss:3000') . '/osdr/list?limit=' . $limit,
             ]);
 
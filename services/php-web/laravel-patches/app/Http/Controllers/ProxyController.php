@@ -49,4 +49,12 @@ class ProxyController extends Controller
         // 3. Возвращаем ответ.
         return response()->json($result);
     }
+
+    /**
+     * Проксирует карточку околоземного объекта /neo/{id}.
+     */
+    public function neo(string $id): JsonResponse
+    {
+        return response()->json($this->proxyService->getNeoDetail($id));
+    }
 }
\ No newline at end of file
@@ -48,15 +48,19 @@ class DashboardService
                 ];
             }
 
+            $neo = $this->issClient->getNeoCount();
+            $neoAlerts = $this->issClient->getNeoAlerts(['severity' => 'warning']);
+
             // Маппинг и трансформация данных (ViewModel для Blade)
             return [
                 'iss' => $iss,
                 'trend' => [], // Фронт заберет сам
                 'jw_gallery' => [], // Фронт заберет сам через jwstFeed
+                'neo_alerts' => $neoAlerts['items'] ?? [],
                 'metrics' => [
                     'iss_speed' => $iss['velocity'] ?? null,
                     'iss_alt' => $iss['altitude'] ?? null,
-                    'neo_total' => 0, // Это значение должно быть получено из другого клиента/репозитория
+                    'neo_total' => $neo['neo_total'] ?? 0,
                 ],
             ];
         });
@@ -54,6 +54,24 @@ class OsdrService
         });
     }
 
+    /**
+     * Агрегированная статистика OSDR (живые датасеты) для блока сводки.
+     * Сводка необязательна: при сбое Rust-сервиса страница строится без нее.
+     *
+     * @return array|null
+     */
+    public function getOsdrStats(): ?array
+    {
+        return Cache::remember('osdr_stats', 300, function () {
+            try {
+                $data = $this->client->getOsdrStats();
+                return $data['stats'] ?? null;
+            } catch (Exception $e) {
+                return null;
+            }
+        });
+    }
+
     /**
      * Преобразует данные вида {"OSD-1": {...}, "OSD-2": {...}} в плоский список (ключевая логика).
      * @param array $items
@@ -40,4 +40,14 @@ class ProxyService
         // мы пока не добавляем кэширование.
         return $this->client->get('/iss/trend', $qs);
     }
+
+    /**
+     * Проксирует карточку околоземного объекта (орбита и все сближения).
+     * @param string $id Идентификатор объекта NeoWs (detail_url в ленте /neo/upcoming)
+     * @return array
+     */
+    public function getNeoDetail(string $id): array
+    {
+        return $this->client->get('/neo/' . rawurlencode($id));
+    }
 }
\ No newline at end of file
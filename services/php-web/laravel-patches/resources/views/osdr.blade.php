@@ -5,6 +5,30 @@
   <h3 class="mb-3">NASA OSDR</h3>
   <div class="small text-muted mb-2">Источник {{ $src }}</div>
 
+  @if(!empty($stats))
+    <div class="row g-2 mb-3">
+      <div class="col-md-3">
+        <div class="border rounded p-2 h-100">
+          <div class="small text-muted">Датасетов</div>
+          <div class="fs-4">{{ $stats['total'] ?? 0 }}</div>
+          <div class="small text-muted">отозвано: {{ $stats['withdrawn'] ?? 0 }}</div>
+        </div>
+      </div>
+      @foreach(['by_organism' => 'Организмы', 'by_mission' => 'Миссии', 'by_assay_type' => 'Типы ассеев'] as $key => $label)
+        <div class="col-md-3">
+          <div class="border rounded p-2 h-100">
+            <div class="small text-muted mb-1">{{ $label }}</div>
+            @foreach(array_slice($stats[$key] ?? [], 0, 5) as $b)
+              <div class="d-flex justify-content-between small">
+                <span class="text-truncate me-2">{{ $b['key'] }}</span><span>{{ $b['count'] }}</span>
+              </div>
+            @endforeach
+          </div>
+        </div>
+      @endforeach
+    </div>
+  @endif
+
   <div class="table-responsive">
     <input id="search" class="form-control mb-2" placeholder="Поиск по title / dataset_id">
 
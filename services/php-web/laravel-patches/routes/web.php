@@ -11,6 +11,7 @@ Route::get('/osdr',      [\App\Http\Controllers\OsdrController::class,      'ind
 // Прокси к rust_iss
 Route::get('/api/iss/last',  [\App\Http\Controllers\ProxyController::class, 'last']);
 Route::get('/api/iss/trend', [\App\Http\Controllers\ProxyController::class, 'trend']);
+Route::get('/api/neo/{id}',  [\App\Http\Controllers\ProxyController::class, 'neo']);
 Route::get('/iss', [\App\Http\Controllers\DashboardController::class, 'issPage']);
 
 
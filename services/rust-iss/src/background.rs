@@ -12,14 +12,39 @@ async fn osdr_task(state: AppState) {
     let interval = state.config.every_osdr;
     let duration = Duration::from_secs(interval);
     info!("Starting OSDR background task with interval: {}s.", interval);
+    // Заполняем типизированные поля у строк, сохраненных до текущей версии экстрактора
+    match state.osdr_repo.backfill_datasets().await {
+        Ok(0) => {}
+        Ok(n) => info!("Backfilled typed OSDR metadata for {} rows.", n),
+        Err(e) => error!("OSDR metadata backfill failed: {:?}", e),
+    }
     loop {
-        if let Err(e) = state.space_service.fetch_and_save_osdr_data().await { 
+        // В режиме обхода идем по всем страницам с чекпоинтом, иначе берем только первую
+        let result = if state.config.osdr_crawl {
+            state.space_service.crawl_osdr().await.map(|_| ())
+        } else {
+            state.space_service.fetch_and_save_osdr_data().await.map(|_| ())
+        };
+        if let Err(e) = result { 
             error!("OSDR task failed: {:?}", e); 
         }
         sleep(duration).await;
     }
 }
 
+async fn osdr_snapshot_task(state: AppState) {
+    let interval = state.config.every_osdr_snapshot;
+    let duration = Duration::from_secs(interval);
+    info!("Starting OSDR snapshot task with interval: {}s.", interval);
+    loop {
+        // Первый снимок — после первого цикла синхронизации
+        sleep(duration).await;
+        if let Err(e) = state.space_service.create_osdr_snapshot().await {
+            error!("OSDR snapshot task failed: {:?}", e);
+        }
+    }
+}
+
 async fn iss_task(state: AppState) {
     let interval = state.config.every_iss;
     let duration = Duration::from_secs(interval);
@@ -52,6 +77,12 @@ async fn neo_task(state: AppState) {
         if let Err(e) = state.space_service.fetch_and_cache_neo().await { 
             error!("NeoWs task failed: {:?}", e); 
         }
+        // Карточки объектов, открывавшиеся через /neo/:id
+        match state.space_service.refresh_neo_lookups().await {
+            Ok(n) if n > 0 => info!("NeoWs lookups refreshed: {}", n),
+            Ok(_) => {}
+            Err(e) => error!("NeoWs lookup refresh failed: {:?}", e),
+        }
         sleep(duration).await;
     }
 }
@@ -68,6 +99,18 @@ async fn donki_task(state: AppState) {
     }
 }
 
+async fn noaa_task(state: AppState) {
+    let interval = state.config.every_noaa;
+    let duration = Duration::from_secs(interval);
+    info!("Starting NOAA SWPC background task with interval: {}s.", interval);
+    loop {
+        if let Err(e) = state.space_service.fetch_and_cache_noaa().await {
+            error!("NOAA SWPC task failed: {:?}", e);
+        }
+        sleep(duration).await;
+    }
+}
+
 async fn spacex_task(state: AppState) {
     let interval = state.config.every_spacex;
     let duration = Duration::from_secs(interval);
@@ -80,6 +123,45 @@ async fn spacex_task(state: AppState) {
     }
 }
 
+async fn ll2_task(state: AppState) {
+    let interval = state.config.every_ll2;
+    let duration = Duration::from_secs(interval);
+    info!("Starting Launch Library 2 background task with interval: {}s.", interval);
+    loop {
+        if let Err(e) = state.space_service.fetch_and_cache_ll2().await {
+            error!("Launch Library 2 task failed: {:?}", e);
+        }
+        sleep(duration).await;
+    }
+}
+
+async fn iss_elements_task(state: AppState) {
+    let interval = state.config.every_iss_elements;
+    let duration = Duration::from_secs(interval);
+    info!("Starting ISS elements background task with interval: {}s.", interval);
+    loop {
+        if let Err(e) = state.space_service.fetch_and_store_iss_elements().await {
+            error!("ISS elements task failed: {:?}", e);
+        }
+        if let Err(e) = state.space_service.prune_iss_passes().await {
+            error!("ISS passes cleanup failed: {:?}", e);
+        }
+        sleep(duration).await;
+    }
+}
+
+async fn space_cache_task(state: AppState) {
+    let interval = state.config.every_space_cache_maintenance;
+    let duration = Duration::from_secs(interval);
+    info!("Starting space_cache maintenance task with interval: {}s.", interval);
+    loop {
+        if let Err(e) = state.space_service.prune_space_cache().await {
+            error!("space_cache maintenance failed: {:?}", e);
+        }
+        sleep(duration).await;
+    }
+}
+
 // =========================================================================================
 // 2. ЗАПУСК ВСЕХ ЗАДАЧ
 // =========================================================================================
@@ -90,9 +172,14 @@ pub fn spawn_background_tasks(state: AppState) {
     
     // Клонируем стейт для каждой задачи
     tokio::spawn(osdr_task(state.clone()));
+    tokio::spawn(osdr_snapshot_task(state.clone()));
     tokio::spawn(iss_task(state.clone()));
     tokio::spawn(apod_task(state.clone()));
     tokio::spawn(neo_task(state.clone()));
     tokio::spawn(donki_task(state.clone()));
+    tokio::spawn(noaa_task(state.clone()));
     tokio::spawn(spacex_task(state.clone()));
+    tokio::spawn(ll2_task(state.clone()));
+    tokio::spawn(iss_elements_task(state.clone()));
+    tokio::spawn(space_cache_task(state.clone()));
 }
\ No newline at end of file
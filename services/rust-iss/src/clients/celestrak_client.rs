@@ -0,0 +1,54 @@
+use async_trait::async_trait;
+use serde_json::Value;
+use axum::http::StatusCode;
+use reqwest_middleware::ClientWithMiddleware;
+use tracing::{info, instrument};
+
+use crate::domain::contracts::CelestrakClientContract;
+use crate::domain::errors::AppError;
+
+/// Элементы орбиты МКС (NORAD 25544) в формате OMM (путь относительно базового URL)
+const ISS_ELEMENTS_PATH: &str = "/NORAD/elements/gp.php?CATNR=25544&FORMAT=json";
+
+// =========================================================================================
+// 1. СТРУКТУРА CELESTRAK КЛИЕНТА
+// =========================================================================================
+
+/// Клиент CelesTrak GP (celestrak.org)
+pub struct CelestrakClient {
+    client: ClientWithMiddleware,
+    base_url: String,
+}
+
+pub fn new_celestrak_client(client: ClientWithMiddleware, base_url: String) -> CelestrakClient {
+    CelestrakClient { client, base_url: base_url.trim_end_matches('/').to_string() }
+}
+
+// =========================================================================================
+// 2. РЕАЛИЗАЦИЯ КОНТРАКТА
+// =========================================================================================
+
+#[async_trait]
+impl CelestrakClientContract for CelestrakClient {
+
+    #[instrument(skip(self), level = "info")]
+    async fn fetch_iss_elements(&self) -> Result<Value, AppError> {
+        let url = format!("{}{}", self.base_url, ISS_ELEMENTS_PATH);
+        info!("Fetching ISS orbital elements from: {}", url);
+
+        let response = self.client.get(&url)
+            .send()
+            .await
+            .map_err(|e| AppError::ClientError(e.to_string(), StatusCode::BAD_GATEWAY))?;
+
+        let response = response.error_for_status()
+            .map_err(|e| AppError::ClientError(e.to_string(), StatusCode::BAD_GATEWAY))?;
+
+        let json_body = response.json::<Value>().await
+            .map_err(|e| AppError::SerializationError(e.to_string()))?;
+
+        // GP отдает массив объектов OMM; для одного CATNR — из одного элемента
+        json_body.as_array().and_then(|a| a.first()).cloned()
+            .ok_or_else(|| AppError::ClientError("CelesTrak returned no elements for ISS".to_string(), StatusCode::BAD_GATEWAY))
+    }
+}
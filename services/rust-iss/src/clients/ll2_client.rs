@@ -0,0 +1,63 @@
+use async_trait::async_trait;
+use serde_json::Value;
+use axum::http::StatusCode;
+use reqwest_middleware::ClientWithMiddleware;
+use tracing::{info, instrument};
+
+use crate::domain::contracts::Ll2ClientContract;
+use crate::domain::errors::AppError;
+
+// =========================================================================================
+// 1. СТРУКТУРА КЛИЕНТА LAUNCH LIBRARY 2
+// =========================================================================================
+
+/// Клиент Launch Library 2 (ll.thespacedevs.com): пуски всех провайдеров мира
+pub struct Ll2Client {
+    client: ClientWithMiddleware,
+    base_url: String,
+}
+
+pub fn new_ll2_client(client: ClientWithMiddleware, base_url: String) -> Ll2Client {
+    Ll2Client { client, base_url: base_url.trim_end_matches('/').to_string() }
+}
+
+impl Ll2Client {
+    /// Первая страница списка пусков (results); бесплатный тариф — 15 запросов в час,
+    /// поэтому страница одна, а ее размер задается limit
+    async fn fetch_launch_page(&self, list: &str, limit: u32) -> Result<Vec<Value>, AppError> {
+        let url = format!("{}/launch/{}/", self.base_url, list);
+        info!("Fetching Launch Library 2 {} (limit {})", url, limit);
+
+        let response = self.client.get(&url)
+            .query(&[("limit", limit.to_string().as_str()), ("mode", "detailed")])
+            .send()
+            .await
+            .map_err(|e| AppError::ClientError(e.to_string(), StatusCode::BAD_GATEWAY))?;
+
+        let response = response.error_for_status()
+            .map_err(|e| AppError::ClientError(e.to_string(), StatusCode::BAD_GATEWAY))?;
+
+        let body = response.json::<Value>().await
+            .map_err(|e| AppError::SerializationError(e.to_string()))?;
+
+        Ok(body["results"].as_array().cloned().unwrap_or_default())
+    }
+}
+
+// =========================================================================================
+// 2. РЕАЛИЗАЦИЯ КОНТРАКТА
+// =========================================================================================
+
+#[async_trait]
+impl Ll2ClientContract for Ll2Client {
+
+    #[instrument(skip(self), level = "info")]
+    async fn fetch_upcoming_launches(&self, limit: u32) -> Result<Vec<Value>, AppError> {
+        self.fetch_launch_page("upcoming", limit).await
+    }
+
+    #[instrument(skip(self), level = "info")]
+    async fn fetch_previous_launches(&self, limit: u32) -> Result<Vec<Value>, AppError> {
+        self.fetch_launch_page("previous", limit).await
+    }
+}
@@ -1,5 +1,9 @@
+pub mod celestrak_client;
 pub mod iss_client;
+pub mod ll2_client;
 pub mod nasa_client;
+pub mod noaa_client;
+pub mod spacex_client;
 pub mod legacy_pascal_client;
 
 pub use iss_client::*;
@@ -4,12 +4,15 @@ use serde_json::Value;
 use axum::http::StatusCode;
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
 use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
-use reqwest::header::CONTENT_TYPE;
-use tracing::{info, instrument, warn};
+use reqwest::header::{HeaderName, CONTENT_TYPE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, IF_RANGE, LAST_MODIFIED, RANGE};
+use std::path::Path;
+use tokio::io::AsyncWriteExt;
+use tracing::{info, instrument};
 
 use crate::domain::contracts::NasaClientContract;
 use crate::domain::errors::AppError;
-use crate::domain::models::IssPosition; // Используем IssPosition
+use crate::domain::models::{DownloadOutcome, HttpValidators, IssPosition, OsdrPage, OsdrRemoteFile}; // Используем IssPosition
+use crate::utils::json_tools;
 
 // =========================================================================================
 // 1. КОНСТРУКТОР HTTP КЛИЕНТА (С RETRIES, БЕЗ CIRCUIT BREAKER)
@@ -27,6 +30,15 @@ pub fn create_api_client() -> ClientWithMiddleware { // FIX: Имя функци
         .build() 
 }
 
+/// Эндпоинт Astronomy Picture of the Day
+const APOD_URL: &str = "https://api.nasa.gov/planetary/apod";
+/// Лента сближений NeoWs (не более 7 дней за запрос)
+const NEO_FEED_URL: &str = "https://api.nasa.gov/neo/rest/v1/feed";
+/// Карточка объекта NeoWs: {NEO_LOOKUP_URL}/{id}
+const NEO_LOOKUP_URL: &str = "https://api.nasa.gov/neo/rest/v1/neo";
+/// База DONKI: {DONKI_URL}/FLR, {DONKI_URL}/CME, ...
+const DONKI_URL: &str = "https://api.nasa.gov/DONKI";
+
 // =========================================================================================
 // 2. СТРУКТУРА NASA КЛИЕНТА
 // =========================================================================================
@@ -40,6 +52,110 @@ pub fn new_nasa_client(client: ClientWithMiddleware, api_key: String) -> NasaCli
     NasaClient { client, api_key }
 }
 
+impl NasaClient {
+    /// Добавляет api_key к URL, если ключ задан и еще не присутствует в запросе.
+    fn with_api_key(&self, url: &str) -> String {
+        if self.api_key.is_empty() || url.contains("api_key=") {
+            return url.to_string();
+        }
+        let sep = if url.contains('?') { '&' } else { '?' };
+        format!("{}{}api_key={}", url, sep, self.api_key)
+    }
+
+    /// Список событий DONKI ({DONKI_URL}/{endpoint}) за диапазон дат.
+    /// Когда событий нет, DONKI отвечает пустым телом — это пустой массив.
+    async fn fetch_donki(&self, endpoint: &str, start_date: &str, end_date: &str, api_key: &str) -> Result<Value, AppError> {
+        info!("Fetching DONKI {} {}..{}", endpoint, start_date, end_date);
+
+        let response = self.client.get(format!("{}/{}", DONKI_URL, endpoint))
+            .query(&[("startDate", start_date), ("endDate", end_date), ("api_key", api_key)])
+            .send()
+            .await
+            .map_err(|e| AppError::ClientError(e.to_string(), StatusCode::BAD_GATEWAY))?;
+
+        let response = response.error_for_status()
+            .map_err(|e| AppError::ClientError(e.to_string(), StatusCode::BAD_GATEWAY))?;
+
+        let body = response.text().await
+            .map_err(|e| AppError::ClientError(e.to_string(), StatusCode::BAD_GATEWAY))?;
+        if body.trim().is_empty() {
+            return Ok(Value::Array(vec![]));
+        }
+        serde_json::from_str(&body).map_err(|e| AppError::SerializationError(e.to_string()))
+    }
+}
+
+/// Собирает файлы из листинга OSDR: любой объект с URL-ключом считается файлом,
+/// имя берется из полей объекта или из ключа, под которым он лежит.
+fn collect_remote_files(base_url: &str, v: &Value, key: Option<&str>, out: &mut Vec<OsdrRemoteFile>) {
+    match v {
+        Value::Object(map) => {
+            let url = json_tools::s_pick(v, &["URL", "url", "remote_url", "download_url", "downloadUrl"])
+                .and_then(|u| reqwest::Url::parse(base_url).ok()?.join(&u).ok());
+            if let Some(url) = url {
+                let name = json_tools::s_pick(v, &["file_name", "filename", "name"])
+                    .or_else(|| key.map(|k| k.to_string()))
+                    .or_else(|| url.path_segments()?.next_back().map(|s| s.to_string()))
+                    .unwrap_or_default();
+                let size = ["size", "file_size", "fileSize", "bytes"].iter()
+                    .find_map(|k| v.get(*k).and_then(json_tools::num))
+                    .map(|n| n as i64);
+                if !name.is_empty() {
+                    out.push(OsdrRemoteFile { name, url: url.to_string(), size });
+                }
+                return;
+            }
+            for (k, child) in map {
+                collect_remote_files(base_url, child, Some(k), out);
+            }
+        }
+        Value::Array(arr) => {
+            for child in arr {
+                collect_remote_files(base_url, child, None, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Файл рядом с .part, где хранится валидатор (ETag/Last-Modified) скачиваемой версии
+fn part_validator_path(part_path: &Path) -> std::path::PathBuf {
+    let mut name = part_path.as_os_str().to_owned();
+    name.push(".validator");
+    name.into()
+}
+
+/// Определяет URL следующей страницы: ссылка `next` (в т.ч. `links.next`,
+/// `_links.next.href`) либо номер страницы `page` при известном `pages`/`total_pages`.
+fn next_page_url(current_url: &str, body: &Value) -> Option<String> {
+    let link = body.get("next").and_then(|v| v.as_str())
+        .or_else(|| body.pointer("/links/next").and_then(|v| v.as_str()))
+        .or_else(|| body.pointer("/_links/next/href").and_then(|v| v.as_str()))
+        .filter(|s| !s.is_empty());
+
+    if let Some(link) = link {
+        let base = reqwest::Url::parse(current_url).ok()?;
+        return base.join(link).ok().map(|u| u.to_string());
+    }
+
+    let page = body.get("page").and_then(|v| v.as_i64())?;
+    let pages = body.get("pages").or_else(|| body.get("total_pages")).and_then(|v| v.as_i64())?;
+    if page >= pages {
+        return None;
+    }
+
+    let mut url = reqwest::Url::parse(current_url).ok()?;
+    let pairs: Vec<(String, String)> = url.query_pairs()
+        .filter(|(k, _)| k != "page")
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    url.query_pairs_mut()
+        .clear()
+        .extend_pairs(pairs)
+        .append_pair("page", &(page + 1).to_string());
+    Some(url.to_string())
+}
+
 // =========================================================================================
 // 3. РЕАЛИЗАЦИЯ КОНТРАКТА
 // =========================================================================================
@@ -78,52 +194,274 @@ impl NasaClientContract for NasaClient {
     }
 
     #[instrument(skip(self), level = "info")]
-    async fn fetch_osdr_list(&self, url: &str) -> Result<Vec<Value>, AppError> {
-        info!("Fetching OSDR list from: {}", url);
-        
-        let request_url = format!("{}&api_key={}", url, self.api_key);
+    async fn fetch_osdr_page(&self, url: &str, validators: &HttpValidators) -> Result<OsdrPage, AppError> {
+        info!("Fetching OSDR page from: {}", url);
 
-        let response = self.client.get(&request_url)
-            .header(CONTENT_TYPE, "application/json")
+        let request_url = self.with_api_key(url);
+
+        let mut request = self.client.get(&request_url)
+            .header(CONTENT_TYPE, "application/json");
+        if let Some(etag) = &validators.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &validators.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+
+        let response = request
             .send()
             .await
             .map_err(|e| AppError::ClientError(e.to_string(), StatusCode::BAD_GATEWAY))?;
+
+        // 304: страница не изменилась с прошлого запроса
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            info!("OSDR page not modified: {}", url);
+            return Ok(OsdrPage {
+                items: vec![],
+                next_url: None,
+                total: None,
+                not_modified: true,
+                validators: validators.clone(),
+            });
+        }
         
         // Обработка статуса ошибки
         let response = response.error_for_status()
             .map_err(|e| AppError::ClientError(e.to_string(), StatusCode::BAD_GATEWAY))?;
 
-        // Десериализация (мы ожидаем массив)
+        let header = |name: HeaderName| response.headers().get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string());
+        let new_validators = HttpValidators {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        };
+
         let json_body = response.json::<Value>().await
             .map_err(|e| AppError::SerializationError(e.to_string()))?;
 
         // Поиск массива данных по ключу "results" (типично для NASA APIs)
-        let items = json_body["results"].as_array().cloned().unwrap_or_default();
+        let items = match json_body.as_array() {
+            Some(arr) => arr.clone(),
+            None => json_body["results"].as_array().cloned().unwrap_or_default(),
+        };
+        let next_url = next_page_url(url, &json_body);
+        let total = ["total", "count", "total_count", "totalCount"].iter()
+            .find_map(|k| json_body.get(*k).and_then(|v| v.as_i64()));
 
-        Ok(items)
+        Ok(OsdrPage {
+            items,
+            next_url,
+            total,
+            not_modified: false,
+            validators: new_validators,
+        })
     }
 
     #[instrument(skip(self), level = "info")]
-    async fn fetch_apod(&self, _api_key: &str) -> Result<Value, AppError> { 
-        warn!("NASA APOD client not implemented yet.");
-        Ok(Value::Null)
+    async fn fetch_osdr_detail(&self, rest_url: &str) -> Result<Value, AppError> {
+        info!("Fetching OSDR dataset detail from: {}", rest_url);
+
+        let response = self.client.get(rest_url)
+            .header(CONTENT_TYPE, "application/json")
+            .send()
+            .await
+            .map_err(|e| AppError::ClientError(e.to_string(), StatusCode::BAD_GATEWAY))?;
+
+        let response = response.error_for_status()
+            .map_err(|e| AppError::ClientError(e.to_string(), StatusCode::BAD_GATEWAY))?;
+
+        let json_body = response.json::<Value>().await
+            .map_err(|e| AppError::SerializationError(e.to_string()))?;
+
+        Ok(json_body)
     }
 
     #[instrument(skip(self), level = "info")]
-    async fn fetch_neo_feed(&self, _start_date: &str, _end_date: &str, _api_key: &str) -> Result<Value, AppError> {
-        warn!("NASA NEO Feed client not implemented yet.");
-        Ok(Value::Null)
+    async fn fetch_osdr_file_list(&self, url: &str) -> Result<Vec<OsdrRemoteFile>, AppError> {
+        info!("Fetching OSDR file listing from: {}", url);
+
+        let response = self.client.get(url)
+            .header(CONTENT_TYPE, "application/json")
+            .send()
+            .await
+            .map_err(|e| AppError::ClientError(e.to_string(), StatusCode::BAD_GATEWAY))?;
+
+        let response = response.error_for_status()
+            .map_err(|e| AppError::ClientError(e.to_string(), StatusCode::BAD_GATEWAY))?;
+
+        let json_body = response.json::<Value>().await
+            .map_err(|e| AppError::SerializationError(e.to_string()))?;
+
+        let mut files = Vec::new();
+        collect_remote_files(url, &json_body, None, &mut files);
+        Ok(files)
     }
 
     #[instrument(skip(self), level = "info")]
-    async fn fetch_donki_flr(&self, _start_date: &str, _end_date: &str, _api_key: &str) -> Result<Value, AppError> {
-        warn!("NASA DONKI FLR client not implemented yet.");
-        Ok(Value::Null)
+    async fn download_file(&self, url: &str, part_path: &Path, max_bytes: u64) -> Result<DownloadOutcome, AppError> {
+        // Докачка: продолжаем с длины уже скачанного .part, но только с If-Range по валидатору,
+        // сохраненному при начале скачивания — иначе изменившийся upstream файл склеится со старыми байтами
+        let validator_path = part_validator_path(part_path);
+        let validator = tokio::fs::read_to_string(&validator_path).await.ok().filter(|v| !v.is_empty());
+        let offset = match &validator {
+            Some(_) => tokio::fs::metadata(part_path).await.map(|m| m.len()).unwrap_or(0),
+            None => 0,
+        };
+
+        let mut request = self.client.get(url);
+        if let (true, Some(validator)) = (offset > 0, &validator) {
+            info!("Resuming download of {} from byte {}", url, offset);
+            request = request
+                .header(RANGE, format!("bytes={}-", offset))
+                .header(IF_RANGE, validator.as_str());
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| AppError::ClientError(e.to_string(), StatusCode::BAD_GATEWAY))?;
+
+        // 416 на докачке: .part уже содержит файл целиком
+        if offset > 0 && response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+            return Ok(DownloadOutcome::Complete(offset, None));
+        }
+
+        let mut response = response.error_for_status()
+            .map_err(|e| AppError::ClientError(e.to_string(), StatusCode::BAD_GATEWAY))?;
+
+        // Сервер может проигнорировать Range или If-Range не совпал (файл изменился) — 200, пишем заново
+        let resumed = offset > 0 && response.status() == reqwest::StatusCode::PARTIAL_CONTENT;
+        let mut written = if resumed { offset } else { 0 };
+        let content_type = response.headers().get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string());
+        if let Some(len) = response.content_length() {
+            if written + len > max_bytes {
+                let _ = tokio::fs::remove_file(&validator_path).await;
+                return Ok(DownloadOutcome::TooLarge(written + len));
+            }
+        }
+
+        let mut file = if resumed {
+            tokio::fs::OpenOptions::new().append(true).open(part_path).await?
+        } else {
+            // Валидатор новой версии: сильный ETag, иначе Last-Modified; без него докачки не будет
+            let header = |name: HeaderName| response.headers().get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|s| s.to_string());
+            let new_validator = header(ETAG).filter(|e| !e.starts_with("W/")).or_else(|| header(LAST_MODIFIED));
+            match new_validator {
+                Some(v) => tokio::fs::write(&validator_path, v).await?,
+                None => { let _ = tokio::fs::remove_file(&validator_path).await; }
+            }
+            tokio::fs::File::create(part_path).await?
+        };
+
+        while let Some(chunk) = response.chunk().await
+            .map_err(|e| AppError::ClientError(e.to_string(), StatusCode::BAD_GATEWAY))? {
+            written += chunk.len() as u64;
+            if written > max_bytes {
+                let _ = tokio::fs::remove_file(&validator_path).await;
+                return Ok(DownloadOutcome::TooLarge(written));
+            }
+            file.write_all(&chunk).await?;
+        }
+        file.flush().await?;
+        let _ = tokio::fs::remove_file(&validator_path).await;
+
+        Ok(DownloadOutcome::Complete(written, content_type))
     }
 
-    #[instrument(skip(self), level = "info")]
-    async fn fetch_donki_cme(&self, _start_date: &str, _end_date: &str, _api_key: &str) -> Result<Value, AppError> {
-        warn!("NASA DONKI CME client not implemented yet.");
-        Ok(Value::Null)
+    #[instrument(skip(self, api_key), level = "info")]
+    async fn fetch_apod(&self, start_date: &str, end_date: Option<&str>, api_key: &str) -> Result<Vec<Value>, AppError> {
+        info!("Fetching APOD {}..{}", start_date, end_date.unwrap_or("today"));
+
+        // Одна дата — параметр date (ответ-объект), диапазон — start_date/end_date (ответ-массив)
+        let mut query = vec![("api_key", api_key), ("thumbs", "true")];
+        match end_date {
+            Some(end) if end == start_date => query.push(("date", start_date)),
+            Some(end) => query.extend([("start_date", start_date), ("end_date", end)]),
+            None => query.push(("start_date", start_date)),
+        }
+
+        let response = self.client.get(APOD_URL)
+            .query(&query)
+            .send()
+            .await
+            .map_err(|e| AppError::ClientError(e.to_string(), StatusCode::BAD_GATEWAY))?;
+
+        // APOD отдает причину ошибки в теле (msg), ее и пробрасываем
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.json::<Value>().await.unwrap_or(Value::Null);
+            let msg = json_tools::s_pick_any(&body, &["msg", "/error/message"]).unwrap_or_else(|| status.to_string());
+            return Err(AppError::ClientError(format!("APOD {}: {}", status, msg), StatusCode::BAD_GATEWAY));
+        }
+
+        let json_body = response.json::<Value>().await
+            .map_err(|e| AppError::SerializationError(e.to_string()))?;
+
+        Ok(match json_body {
+            Value::Array(items) => items,
+            Value::Null => vec![],
+            item => vec![item],
+        })
+    }
+
+    #[instrument(skip(self, api_key), level = "info")]
+    async fn fetch_neo_feed(&self, start_date: &str, end_date: &str, api_key: &str) -> Result<Value, AppError> {
+        info!("Fetching NeoWs feed {}..{}", start_date, end_date);
+
+        let response = self.client.get(NEO_FEED_URL)
+            .query(&[("start_date", start_date), ("end_date", end_date), ("api_key", api_key)])
+            .send()
+            .await
+            .map_err(|e| AppError::ClientError(e.to_string(), StatusCode::BAD_GATEWAY))?;
+
+        let response = response.error_for_status()
+            .map_err(|e| AppError::ClientError(e.to_string(), StatusCode::BAD_GATEWAY))?;
+
+        let json_body = response.json::<Value>().await
+            .map_err(|e| AppError::SerializationError(e.to_string()))?;
+
+        Ok(json_body)
+    }
+
+    #[instrument(skip(self, api_key), level = "info")]
+    async fn fetch_neo_lookup(&self, neo_id: &str, api_key: &str) -> Result<Option<Value>, AppError> {
+        info!("Fetching NeoWs lookup {}", neo_id);
+
+        let response = self.client.get(format!("{}/{}", NEO_LOOKUP_URL, neo_id))
+            .query(&[("api_key", api_key)])
+            .send()
+            .await
+            .map_err(|e| AppError::ClientError(e.to_string(), StatusCode::BAD_GATEWAY))?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let response = response.error_for_status()
+            .map_err(|e| AppError::ClientError(e.to_string(), StatusCode::BAD_GATEWAY))?;
+
+        let json_body = response.json::<Value>().await
+            .map_err(|e| AppError::SerializationError(e.to_string()))?;
+
+        Ok(Some(json_body))
+    }
+
+    #[instrument(skip(self, api_key), level = "info")]
+    async fn fetch_donki_flr(&self, start_date: &str, end_date: &str, api_key: &str) -> Result<Value, AppError> {
+        self.fetch_donki("FLR", start_date, end_date, api_key).await
+    }
+
+    #[instrument(skip(self, api_key), level = "info")]
+    async fn fetch_donki_cme(&self, start_date: &str, end_date: &str, api_key: &str) -> Result<Value, AppError> {
+        self.fetch_donki("CME", start_date, end_date, api_key).await
+    }
+
+    #[instrument(skip(self, api_key), level = "info")]
+    async fn fetch_donki_events(&self, endpoint: &str, start_date: &str, end_date: &str, api_key: &str) -> Result<Value, AppError> {
+        self.fetch_donki(endpoint, start_date, end_date, api_key).await
     }
 }
\ No newline at end of file
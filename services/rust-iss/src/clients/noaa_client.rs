@@ -0,0 +1,93 @@
+use async_trait::async_trait;
+use serde_json::{Map, Value};
+use axum::http::StatusCode;
+use reqwest_middleware::ClientWithMiddleware;
+use tracing::{info, instrument};
+
+use crate::domain::contracts::NoaaClientContract;
+use crate::domain::errors::AppError;
+
+/// Продукты NOAA SWPC (пути относительно базового URL)
+const KP_INDEX_PATH: &str = "/products/noaa-planetary-k-index.json";
+const KP_FORECAST_PATH: &str = "/products/noaa-planetary-k-index-forecast.json";
+const SOLAR_WIND_PLASMA_PATH: &str = "/products/solar-wind/plasma-1-day.json";
+const SOLAR_WIND_MAG_PATH: &str = "/products/solar-wind/mag-1-day.json";
+
+// =========================================================================================
+// 1. СТРУКТУРА NOAA КЛИЕНТА
+// =========================================================================================
+
+pub struct NoaaClient {
+    client: ClientWithMiddleware,
+    base_url: String,
+}
+
+pub fn new_noaa_client(client: ClientWithMiddleware, base_url: String) -> NoaaClient {
+    NoaaClient { client, base_url: base_url.trim_end_matches('/').to_string() }
+}
+
+impl NoaaClient {
+    /// JSON-продукт SWPC как список объектов
+    async fn fetch_product(&self, path: &str) -> Result<Vec<Value>, AppError> {
+        let url = format!("{}{}", self.base_url, path);
+        info!("Fetching NOAA SWPC {}", url);
+
+        let response = self.client.get(&url)
+            .send()
+            .await
+            .map_err(|e| AppError::ClientError(e.to_string(), StatusCode::BAD_GATEWAY))?;
+
+        let response = response.error_for_status()
+            .map_err(|e| AppError::ClientError(e.to_string(), StatusCode::BAD_GATEWAY))?;
+
+        let body = response.json::<Value>().await
+            .map_err(|e| AppError::SerializationError(e.to_string()))?;
+
+        Ok(table_rows(body))
+    }
+}
+
+/// Продукты SWPC бывают двух видов: массив объектов или таблица,
+/// где первая строка — заголовок ("time_tag", "Kp", ...). Таблица приводится к объектам.
+fn table_rows(body: Value) -> Vec<Value> {
+    let Value::Array(rows) = body else { return vec![] };
+    let header: Option<Vec<String>> = rows.first()
+        .and_then(|r| r.as_array())
+        .filter(|r| r.iter().all(|c| c.is_string()))
+        .map(|r| r.iter().filter_map(|c| c.as_str().map(|s| s.to_string())).collect());
+
+    let Some(header) = header else { return rows.into_iter().filter(|r| r.is_object()).collect() };
+    rows.into_iter().skip(1).filter_map(|row| {
+        let cells = row.as_array()?;
+        let object: Map<String, Value> = header.iter().cloned().zip(cells.iter().cloned()).collect();
+        Some(Value::Object(object))
+    }).collect()
+}
+
+// =========================================================================================
+// 2. РЕАЛИЗАЦИЯ КОНТРАКТА
+// =========================================================================================
+
+#[async_trait]
+impl NoaaClientContract for NoaaClient {
+
+    #[instrument(skip(self), level = "info")]
+    async fn fetch_kp_index(&self) -> Result<Vec<Value>, AppError> {
+        self.fetch_product(KP_INDEX_PATH).await
+    }
+
+    #[instrument(skip(self), level = "info")]
+    async fn fetch_kp_forecast(&self) -> Result<Vec<Value>, AppError> {
+        self.fetch_product(KP_FORECAST_PATH).await
+    }
+
+    #[instrument(skip(self), level = "info")]
+    async fn fetch_solar_wind_plasma(&self) -> Result<Vec<Value>, AppError> {
+        self.fetch_product(SOLAR_WIND_PLASMA_PATH).await
+    }
+
+    #[instrument(skip(self), level = "info")]
+    async fn fetch_solar_wind_mag(&self) -> Result<Vec<Value>, AppError> {
+        self.fetch_product(SOLAR_WIND_MAG_PATH).await
+    }
+}
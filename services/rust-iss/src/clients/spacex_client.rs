@@ -0,0 +1,69 @@
+use async_trait::async_trait;
+use serde_json::Value;
+use axum::http::StatusCode;
+use reqwest_middleware::ClientWithMiddleware;
+use tracing::{info, instrument};
+
+use crate::domain::contracts::SpacexClientContract;
+use crate::domain::errors::AppError;
+
+// =========================================================================================
+// 1. СТРУКТУРА SPACEX КЛИЕНТА
+// =========================================================================================
+
+/// Клиент SpaceX API v4 (api.spacexdata.com/v4)
+pub struct SpacexClient {
+    client: ClientWithMiddleware,
+    base_url: String,
+}
+
+pub fn new_spacex_client(client: ClientWithMiddleware, base_url: String) -> SpacexClient {
+    SpacexClient { client, base_url: base_url.trim_end_matches('/').to_string() }
+}
+
+impl SpacexClient {
+    /// Полная коллекция ресурса v4 (launches, rockets, launchpads) — массив объектов
+    async fn fetch_collection(&self, resource: &str) -> Result<Vec<Value>, AppError> {
+        let url = format!("{}/{}", self.base_url, resource);
+        info!("Fetching SpaceX {}", url);
+
+        let response = self.client.get(&url)
+            .send()
+            .await
+            .map_err(|e| AppError::ClientError(e.to_string(), StatusCode::BAD_GATEWAY))?;
+
+        let response = response.error_for_status()
+            .map_err(|e| AppError::ClientError(e.to_string(), StatusCode::BAD_GATEWAY))?;
+
+        let body = response.json::<Value>().await
+            .map_err(|e| AppError::SerializationError(e.to_string()))?;
+
+        match body {
+            Value::Array(items) => Ok(items),
+            _ => Err(AppError::SerializationError(format!("SpaceX {}: expected array", resource))),
+        }
+    }
+}
+
+// =========================================================================================
+// 2. РЕАЛИЗАЦИЯ КОНТРАКТА
+// =========================================================================================
+
+#[async_trait]
+impl SpacexClientContract for SpacexClient {
+
+    #[instrument(skip(self), level = "info")]
+    async fn fetch_launches(&self) -> Result<Vec<Value>, AppError> {
+        self.fetch_collection("launches").await
+    }
+
+    #[instrument(skip(self), level = "info")]
+    async fn fetch_rockets(&self) -> Result<Vec<Value>, AppError> {
+        self.fetch_collection("rockets").await
+    }
+
+    #[instrument(skip(self), level = "info")]
+    async fn fetch_launchpads(&self) -> Result<Vec<Value>, AppError> {
+        self.fetch_collection("launchpads").await
+    }
+}
@@ -1,10 +1,33 @@
-use crate::domain::models::AppConfig;
+use std::collections::HashMap;
+
+use tracing::warn;
+
+use crate::domain::models::{AppConfig, CacheRetention, NeoAlertRules};
 use crate::domain::errors::AppError;
 
 fn env_u64(k: &str, d: u64) -> u64 {
     std::env::var(k).ok().and_then(|s| s.parse().ok()).unwrap_or(d)
 }
 
+fn env_f64(k: &str, d: f64) -> f64 {
+    std::env::var(k).ok().and_then(|s| s.parse().ok()).unwrap_or(d)
+}
+
+/// Правила хранения space_cache: "источник=правило" через запятую, "*" — для остальных источников
+fn env_cache_retention(k: &str, d: &str) -> HashMap<String, CacheRetention> {
+    let spec = std::env::var(k).unwrap_or_else(|_| d.to_string());
+    let mut rules = HashMap::new();
+    for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        match entry.split_once('=').and_then(|(src, rule)| Some((src.trim().to_lowercase(), CacheRetention::parse(rule)?))) {
+            Some((src, rule)) => {
+                rules.insert(src, rule);
+            }
+            None => warn!("Ignoring invalid {} entry '{}'", k, entry),
+        }
+    }
+    rules
+}
+
 pub fn load_config() -> Result<AppConfig, AppError> {
     dotenvy::dotenv().ok();
 
@@ -24,6 +47,42 @@ pub fn load_config() -> Result<AppConfig, AppError> {
     let every_neo = env_u64("NEO_EVERY_SECONDS", 7200);
     let every_donki = env_u64("DONKI_EVERY_SECONDS", 3600);
     let every_spacex = env_u64("SPACEX_EVERY_SECONDS", 3600);
+    let every_noaa = env_u64("NOAA_EVERY_SECONDS", 900);
+    let every_ll2 = env_u64("LL2_EVERY_SECONDS", 3600);
+    let every_iss_elements = env_u64("ISS_ELEMENTS_EVERY_SECONDS", 21600);
+    let noaa_swpc_url = std::env::var("NOAA_SWPC_URL")
+        .unwrap_or_else(|_| "https://services.swpc.noaa.gov".to_string());
+    let spacex_api_url = std::env::var("SPACEX_API_URL")
+        .unwrap_or_else(|_| "https://api.spacexdata.com/v4".to_string());
+    let ll2_api_url = std::env::var("LL2_API_URL")
+        .unwrap_or_else(|_| "https://ll.thespacedevs.com/2.2.0".to_string());
+    let ll2_page_limit = env_u64("LL2_PAGE_LIMIT", 100) as u32;
+    let celestrak_api_url = std::env::var("CELESTRAK_API_URL")
+        .unwrap_or_else(|_| "https://celestrak.org".to_string());
+    let osdr_detail_ttl = env_u64("OSDR_DETAIL_TTL_SECONDS", 86400);
+    let osdr_crawl = std::env::var("OSDR_CRAWL")
+        .map(|s| matches!(s.to_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false);
+    let osdr_crawl_page_budget = env_u64("OSDR_CRAWL_PAGE_BUDGET", 20) as u32;
+    let osdr_stats_ttl = env_u64("OSDR_STATS_TTL_SECONDS", 300);
+    let osdr_files_url = std::env::var("OSDR_FILES_URL")
+        .unwrap_or_else(|_| "https://visualization.osdr.nasa.gov/biodata/api/v2/dataset/{dataset_id}/files/?format=json".to_string());
+    let osdr_mirror_dir = std::env::var("OSDR_MIRROR_DIR").unwrap_or_else(|_| "/data/osdr".to_string());
+    let osdr_mirror_max_file_bytes = env_u64("OSDR_MIRROR_MAX_FILE_MB", 512) * 1024 * 1024;
+    let osdr_snapshot_dir = std::env::var("OSDR_SNAPSHOT_DIR").unwrap_or_else(|_| format!("{}/_snapshots", osdr_mirror_dir));
+    let every_osdr_snapshot = env_u64("OSDR_SNAPSHOT_EVERY_SECONDS", 86400);
+    let apod_media_dir = std::env::var("APOD_MEDIA_DIR").unwrap_or_else(|_| "/data/apod".to_string());
+    let apod_media_max_bytes = env_u64("APOD_MEDIA_MAX_MB", 25) * 1024 * 1024;
+    let neo_alert_rules = NeoAlertRules {
+        max_lunar: env_f64("NEO_ALERT_MAX_LUNAR", 5.0),
+        min_diameter_m: env_f64("NEO_ALERT_MIN_DIAMETER_M", 140.0),
+        hazardous: std::env::var("NEO_ALERT_HAZARDOUS")
+            .map(|s| matches!(s.to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(true),
+        horizon_days: env_u64("NEO_ALERT_HORIZON_DAYS", 7) as i64,
+    };
+    let space_cache_retention = env_cache_retention("SPACE_CACHE_RETENTION", "*=30d");
+    let every_space_cache_maintenance = env_u64("SPACE_CACHE_MAINTENANCE_EVERY_SECONDS", 3600);
 
     Ok(AppConfig {
         nasa_url,
@@ -36,5 +95,27 @@ pub fn load_config() -> Result<AppConfig, AppError> {
         every_neo,
         every_donki,
         every_spacex,
+        every_noaa,
+        every_ll2,
+        every_iss_elements,
+        noaa_swpc_url,
+        spacex_api_url,
+        ll2_api_url,
+        ll2_page_limit,
+        celestrak_api_url,
+        osdr_detail_ttl,
+        osdr_crawl,
+        osdr_crawl_page_budget,
+        osdr_stats_ttl,
+        osdr_files_url,
+        osdr_mirror_dir,
+        osdr_mirror_max_file_bytes,
+        osdr_snapshot_dir,
+        every_osdr_snapshot,
+        apod_media_dir,
+        apod_media_max_bytes,
+        neo_alert_rules,
+        space_cache_retention,
+        every_space_cache_maintenance,
     })
 }
\ No newline at end of file
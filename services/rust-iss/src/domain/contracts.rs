@@ -1,34 +1,114 @@
 // src/domain/contracts.rs
 
+use std::collections::HashMap;
+use std::path::Path;
+
 use async_trait::async_trait;
+use futures_util::stream::BoxStream;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde_json::Value;
 
 use super::errors::AppError;
-use crate::domain::models::{IssPosition, OsdrItem};
+use crate::domain::models::{
+    ApodEntry, ApodImage, ApodMedia, ApodSearchFilter, ApodSearchHit, CachePruneReport, DonkiChain, DonkiChainNode, DonkiCme, DonkiEvent, DonkiLink, DonkiFlare, DonkiNotification, KpReading, Launch, LaunchSyncStats, NoaaKp, SolarWindSample, SpaceWeatherMonthly, SpaceWeatherStats, SpaceWeatherStatus, StatBucket, NeoAlert, NeoApproachView, NeoCounts, NeoDetail, DownloadOutcome, HttpValidators, IsaArchive, IssElements, IssPass, IssPassForecast, IssPosition, MirrorReport, OsdrCrawlState, OsdrDetail, OsdrDetailCache, OsdrHttpCache, OsdrItem, OsdrPage,
+    OsdrFile, OsdrGraph, OsdrMirrorFilter, OsdrRelated, OsdrRemoteFile, OsdrSnapshot, OsdrSnapshotDiff, OsdrStats, OsdrSyncRun, OsdrSyncStats,
+};
 
 // ------------------------------------------------------------
 // Контракт для внешних API (Adapter Pattern)
 // ------------------------------------------------------------
 #[async_trait]
 pub trait NasaClientContract: Send + Sync {
-    /// Запрашивает список данных OSDR
-    async fn fetch_osdr_list(&self, url: &str) -> Result<Vec<Value>, AppError>;
+    /// Запрашивает одну страницу OSDR вместе со ссылкой на следующую.
+    /// Непустые validators отправляются как If-None-Match / If-Modified-Since.
+    async fn fetch_osdr_page(&self, url: &str, validators: &HttpValidators) -> Result<OsdrPage, AppError>;
+
+    /// Запрашивает детальные метаданные датасета по его REST_URL
+    async fn fetch_osdr_detail(&self, rest_url: &str) -> Result<Value, AppError>;
+
+    /// Запрашивает листинг файлов датасета OSDR
+    async fn fetch_osdr_file_list(&self, url: &str) -> Result<Vec<OsdrRemoteFile>, AppError>;
+
+    /// Скачивает файл в part_path с докачкой (Range) от текущей длины part_path
+    async fn download_file(&self, url: &str, part_path: &Path, max_bytes: u64) -> Result<DownloadOutcome, AppError>;
 
     /// Запрашивает APOD
-    async fn fetch_apod(&self, api_key: &str) -> Result<Value, AppError>;
+    /// Записи APOD с start_date по end_date (без end_date — по сегодняшнюю дату upstream)
+    async fn fetch_apod(&self, start_date: &str, end_date: Option<&str>, api_key: &str) -> Result<Vec<Value>, AppError>;
     
     async fn get_iss_position(&self) -> Result<IssPosition, AppError>;
     
-    /// Запрашивает NEO feed - ДОБАВЬТЕ ЕСЛИ ИСПОЛЬЗУЕТЕ
+    /// Лента NeoWs за диапазон дат (не длиннее 7 дней)
     async fn fetch_neo_feed(&self, start_date: &str, end_date: &str, api_key: &str) -> Result<Value, AppError>;
+
+    /// Запись NeoWs lookup по id объекта (орбита и все сближения); None — объект неизвестен NeoWs
+    async fn fetch_neo_lookup(&self, neo_id: &str, api_key: &str) -> Result<Option<Value>, AppError>;
     
-    /// Запрашивает DONKI FLR - ДОБАВЬТЕ ЕСЛИ ИСПОЛЬЗУЕТЕ
+    /// Солнечные вспышки DONKI FLR за диапазон дат (массив событий)
     async fn fetch_donki_flr(&self, start_date: &str, end_date: &str, api_key: &str) -> Result<Value, AppError>;
     
-    /// Запрашивает DONKI CME - ДОБАВЬТЕ ЕСЛИ ИСПОЛЬЗУЕТЕ
-    async fn fetch_donki_cme(&self, start_date: &str, end_date: &str, api_key: &str) -> Result<Value, AppError>;}
+    /// Корональные выбросы массы DONKI CME за диапазон дат (массив событий с анализами)
+    async fn fetch_donki_cme(&self, start_date: &str, end_date: &str, api_key: &str) -> Result<Value, AppError>;
+
+    /// Прочие списки DONKI по эндпоинту (GST, SEP, IPS, HSS, RBE, notifications) за диапазон дат
+    async fn fetch_donki_events(&self, endpoint: &str, start_date: &str, end_date: &str, api_key: &str) -> Result<Value, AppError>;
+}
 
 
+// ------------------------------------------------------------
+// Контракт для клиента NOAA SWPC (строки продуктов как объекты)
+// ------------------------------------------------------------
+#[async_trait]
+pub trait NoaaClientContract: Send + Sync {
+    /// Планетарный Kp (3-часовые значения за последние дни)
+    async fn fetch_kp_index(&self) -> Result<Vec<Value>, AppError>;
+
+    /// Kp: наблюдения, оценки и прогноз на 3 дня (поле observed)
+    async fn fetch_kp_forecast(&self) -> Result<Vec<Value>, AppError>;
+
+    /// Плазма солнечного ветра за сутки: плотность, скорость, температура
+    async fn fetch_solar_wind_plasma(&self) -> Result<Vec<Value>, AppError>;
+
+    /// Межпланетное магнитное поле за сутки (GSM)
+    async fn fetch_solar_wind_mag(&self) -> Result<Vec<Value>, AppError>;
+}
+
+// ------------------------------------------------------------
+// Контракт для клиента SpaceX API v4 (полные коллекции ресурсов)
+// ------------------------------------------------------------
+#[async_trait]
+pub trait SpacexClientContract: Send + Sync {
+    /// Все пуски (прошедшие и предстоящие)
+    async fn fetch_launches(&self) -> Result<Vec<Value>, AppError>;
+
+    /// Ракеты-носители
+    async fn fetch_rockets(&self) -> Result<Vec<Value>, AppError>;
+
+    /// Стартовые площадки
+    async fn fetch_launchpads(&self) -> Result<Vec<Value>, AppError>;
+}
+
+// ------------------------------------------------------------
+// Контракт для клиента Launch Library 2 (пуски всех провайдеров)
+// ------------------------------------------------------------
+#[async_trait]
+pub trait Ll2ClientContract: Send + Sync {
+    /// Ближайшие предстоящие пуски (не больше limit)
+    async fn fetch_upcoming_launches(&self, limit: u32) -> Result<Vec<Value>, AppError>;
+
+    /// Последние состоявшиеся пуски (не больше limit)
+    async fn fetch_previous_launches(&self, limit: u32) -> Result<Vec<Value>, AppError>;
+}
+
+// ------------------------------------------------------------
+// Контракт для клиента CelesTrak GP (элементы орбит)
+// ------------------------------------------------------------
+#[async_trait]
+pub trait CelestrakClientContract: Send + Sync {
+    /// Текущие элементы орбиты МКС (объект OMM)
+    async fn fetch_iss_elements(&self) -> Result<Value, AppError>;
+}
+
 // ------------------------------------------------------------
 // Контракт для Репозиториев (Repository Pattern)
 // ------------------------------------------------------------
@@ -36,37 +116,502 @@ pub trait NasaClientContract: Send + Sync {
 pub trait OsdrRepoContract: Send + Sync {
     /// Сохраняет или обновляет список данных в таблице osdr_items
     async fn upsert_osdr_items(&self, items: &[Value]) -> Result<(), AppError>;
+
+    /// Переизвлекает типизированные поля OsdrDataset у устаревших строк, возвращает их число
+    async fn backfill_datasets(&self) -> Result<u64, AppError>;
+
+    /// Хэши содержимого уже сохраненных записей (dataset_id -> content_hash)
+    async fn get_content_hashes(&self, dataset_ids: &[String]) -> Result<HashMap<String, String>, AppError>;
+
+    /// Отмечает записи увиденными в фиде (снимает withdrawn_at), возвращает число восстановленных
+    async fn mark_seen(&self, dataset_ids: &[String]) -> Result<i64, AppError>;
+
+    /// Помечает отозванными записи, не увиденные с момента seen_since
+    async fn mark_withdrawn(&self, seen_since: DateTime<Utc>) -> Result<i64, AppError>;
+
+    /// Валидаторы HTTP, сохраненные для URL страницы
+    async fn get_http_cache(&self, url: &str) -> Result<Option<OsdrHttpCache>, AppError>;
+
+    /// Сохраняет валидаторы HTTP, ссылку на следующую страницу и dataset_id страницы
+    async fn save_http_cache(
+        &self,
+        url: &str,
+        validators: &HttpValidators,
+        next_url: Option<&str>,
+        dataset_ids: &[String],
+    ) -> Result<(), AppError>;
+
+    /// Пишет прогон синхронизации в журнал
+    async fn record_sync(&self, mode: &str, started_at: DateTime<Utc>, stats: &OsdrSyncStats) -> Result<(), AppError>;
+
+    /// Последние прогоны синхронизации
+    async fn get_sync_log(&self, limit: i64) -> Result<Vec<OsdrSyncRun>, AppError>;
     
-    async fn get_list(&self, limit: i64) -> Result<Vec<OsdrItem>, AppError>;
+    async fn get_list(&self, limit: i64, include_withdrawn: bool) -> Result<Vec<OsdrItem>, AppError>;
+
+    /// Получает одну запись по dataset_id
+    async fn get_by_dataset_id(&self, dataset_id: &str) -> Result<Option<OsdrItem>, AppError>;
+
+    /// Читает закэшированные детальные метаданные датасета
+    async fn get_detail_cache(&self, dataset_id: &str) -> Result<Option<OsdrDetailCache>, AppError>;
+
+    /// Сохраняет (перезаписывает) детальные метаданные датасета
+    async fn save_detail_cache(&self, dataset_id: &str, payload: &Value) -> Result<(), AppError>;
+
+    /// Читает чекпоинт обхода OSDR
+    async fn get_crawl_state(&self, crawl: &str) -> Result<Option<OsdrCrawlState>, AppError>;
+
+    /// Сохраняет чекпоинт обхода OSDR
+    async fn save_crawl_state(&self, state: &OsdrCrawlState) -> Result<(), AppError>;
     
+    /// dataset_id живых записей под фильтр зеркалирования
+    async fn find_dataset_ids(&self, filter: &OsdrMirrorFilter) -> Result<Vec<String>, AppError>;
+
+    /// Регистрирует файлы из листинга (новые — в состоянии pending)
+    async fn upsert_file_listing(&self, dataset_id: &str, files: &[OsdrRemoteFile]) -> Result<(), AppError>;
+
+    /// Файлы зеркала датасета
+    async fn get_files(&self, dataset_id: &str) -> Result<Vec<OsdrFile>, AppError>;
+
+    /// Один файл зеркала
+    async fn get_file(&self, dataset_id: &str, file_name: &str) -> Result<Option<OsdrFile>, AppError>;
+
+    /// Сохраняет состояние файла зеркала
+    async fn update_file(&self, file: &OsdrFile) -> Result<(), AppError>;
+
+    /// Заменяет ISA-Tab метаданные датасета (одной транзакцией)
+    async fn replace_isa(&self, dataset_id: &str, archive: &IsaArchive) -> Result<(), AppError>;
+
+    /// ISA-Tab метаданные датасета, если импортированы
+    async fn get_isa(&self, dataset_id: &str) -> Result<Option<IsaArchive>, AppError>;
+
+    /// Живые датасеты с общими атрибутами, по убыванию веса
+    async fn get_related(&self, dataset_id: &str, limit: i64) -> Result<Vec<OsdrRelated>, AppError>;
+
+    /// Граф живых датасетов: ребра с весом не ниже min_weight
+    async fn get_graph(&self, min_weight: i32) -> Result<OsdrGraph, AppError>;
+
+    /// Регистрирует записанный снимок каталога
+    async fn save_snapshot(&self, snapshot: &OsdrSnapshot) -> Result<(), AppError>;
+
+    /// Снимки каталога, новые первыми
+    async fn get_snapshots(&self, limit: i64) -> Result<Vec<OsdrSnapshot>, AppError>;
+
+    async fn get_snapshot(&self, id: &str) -> Result<Option<OsdrSnapshot>, AppError>;
+
+    /// Агрегаты по osdr_items (SQL): статусы, организмы, миссии, типы ассеев, проекты, помесячно
+    async fn get_stats(&self) -> Result<OsdrStats, AppError>;
+
     /// Получает количество записей - ДОБАВЬТЕ ЭТОТ МЕТОД
     async fn get_count(&self) -> Result<i64, AppError>;
     
-    /// Получает все записи - ДОБАВЬТЕ ЕСЛИ ИСПОЛЬЗУЕТЕ
-    async fn get_all(&self) -> Result<Vec<OsdrItem>, AppError>;
+    /// Потоково читает живые (не отозванные) записи, по dataset_id
+    fn stream_live(&self) -> BoxStream<'_, Result<OsdrItem, AppError>>;
     
     /// Сохраняет одну запись - ДОБАВЬТЕ ЕСЛИ ИСПОЛЬЗУЕТЕ
     async fn save(&self, osdr: &OsdrItem) -> Result<(), AppError>;}
 
+// ------------------------------------------------------------
+// Контракт для хранилища событий космической погоды DONKI
+// ------------------------------------------------------------
+#[async_trait]
+pub trait DonkiRepoContract: Send + Sync {
+    /// UPSERT вспышек FLR по flrID, возвращает число сохраненных
+    async fn upsert_flares(&self, items: &[Value]) -> Result<u64, AppError>;
+
+    /// UPSERT CME по activityID вместе с анализами, возвращает число сохраненных
+    async fn upsert_cmes(&self, items: &[Value]) -> Result<u64, AppError>;
+
+    /// Вспышки с началом в [from, to], новые первыми; class_prefix — класс ("X", "M1")
+    async fn get_flares(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        class_prefix: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<DonkiFlare>, AppError>;
+
+    /// CME с началом в [from, to] и анализами, новые первыми
+    async fn get_cmes(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        min_speed: Option<f64>,
+        limit: i64,
+    ) -> Result<Vec<DonkiCme>, AppError>;
+
+    /// UPSERT событий GST/SEP/IPS/HSS/RBE одного типа, возвращает число сохраненных
+    async fn upsert_events(&self, kind: &str, items: &[Value]) -> Result<u64, AppError>;
+
+    /// UPSERT уведомлений прогнозистов по messageID
+    async fn upsert_notifications(&self, items: &[Value]) -> Result<u64, AppError>;
+
+    /// События типа kind со временем в [from, to], новые первыми
+    async fn get_events(
+        &self,
+        kind: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<DonkiEvent>, AppError>;
+
+    /// Уведомления за [from, to], message_type — фильтр по типу сообщения
+    async fn get_notifications(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        message_type: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<DonkiNotification>, AppError>;
+
+    /// Все события, связанные с event_id через linkedEvents (транзитивно), и ребра между ними
+    async fn get_chain(&self, event_id: &str) -> Result<(Vec<DonkiChainNode>, Vec<DonkiLink>), AppError>;
+
+    /// Число вспышек по классу (A, B, C, M, X) за [from, to]
+    async fn get_flare_class_counts(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<StatBucket>, AppError>;
+
+    /// CME, направленные к Земле, с началом в [from, to] (самые быстрые первыми)
+    async fn get_earth_directed_cmes(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<DonkiCme>, AppError>;
+
+    /// Ряд Kp из геомагнитных бурь DONKI за [from, to]
+    async fn get_kp_readings(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<KpReading>, AppError>;
+
+    /// Вспышки по месяцам и классу начиная с from
+    async fn get_flare_monthly(&self, from: DateTime<Utc>) -> Result<Vec<SpaceWeatherMonthly>, AppError>;
+
+    /// CME по месяцам и диапазону скорости начиная с from
+    async fn get_cme_monthly(&self, from: DateTime<Utc>) -> Result<Vec<SpaceWeatherMonthly>, AppError>;
+}
+
+// ------------------------------------------------------------
+// Контракт для хранилища пусков (launches, ракеты, площадки, журнал изменений)
+// ------------------------------------------------------------
+#[async_trait]
+pub trait LaunchRepoContract: Send + Sync {
+    /// UPSERT ракет SpaceX v4, возвращает число сохраненных
+    async fn upsert_spacex_rockets(&self, items: &[Value]) -> Result<u64, AppError>;
+
+    /// UPSERT площадок SpaceX v4, возвращает число сохраненных
+    async fn upsert_spacex_pads(&self, items: &[Value]) -> Result<u64, AppError>;
+
+    /// UPSERT пусков SpaceX v4 с записью изменений статуса и даты
+    async fn upsert_spacex_launches(&self, items: &[Value]) -> Result<LaunchSyncStats, AppError>;
+
+    /// UPSERT пусков Launch Library 2 (с ракетами и площадками) с записью изменений
+    async fn upsert_ll2_launches(&self, items: &[Value]) -> Result<LaunchSyncStats, AppError>;
+
+    /// Поиск новых дубликатов SpaceX -> Launch Library 2, возвращает число слитых за проход пусков
+    async fn merge_launch_duplicates(&self) -> Result<u64, AppError>;
+
+    /// Предстоящие (upcoming = true) или прошедшие пуски без дубликатов
+    async fn get_launches(&self, upcoming: bool, limit: i64) -> Result<Vec<Launch>, AppError>;
+
+    /// Пуск (по id любого провайдера) с журналом изменений
+    async fn get_launch(&self, launch_id: &str) -> Result<Option<Launch>, AppError>;
+}
+
+// ------------------------------------------------------------
+// Контракт для хранилища космоданных (APOD, NeoWs, space_cache)
+// ------------------------------------------------------------
+#[async_trait]
+pub trait SpaceRepoContract: Send + Sync {
+    /// UPSERT сырых записей APOD по дате, возвращает число сохраненных
+    async fn upsert_apod(&self, items: &[Value]) -> Result<u64, AppError>;
+
+    async fn get_apod(&self, date: NaiveDate) -> Result<Option<ApodEntry>, AppError>;
+
+    /// Записи APOD за диапазон дат (включительно), по возрастанию даты
+    async fn get_apod_range(&self, start: NaiveDate, end: NaiveDate) -> Result<Vec<ApodEntry>, AppError>;
+
+    /// Число известных дат APOD в диапазоне: сохраненные записи и известные пропуски архива
+    async fn count_apod(&self, start: NaiveDate, end: NaiveDate) -> Result<i64, AppError>;
+
+    /// Отмечает даты диапазона без записи как пропуски архива
+    async fn mark_apod_gaps(&self, start: NaiveDate, end: NaiveDate) -> Result<u64, AppError>;
+
+    /// Полнотекстовый поиск по title/explanation, по убыванию релевантности
+    async fn search_apod(&self, filter: &ApodSearchFilter) -> Result<Vec<ApodSearchHit>, AppError>;
+
+    async fn get_apod_media(&self, date: NaiveDate) -> Result<Option<ApodMedia>, AppError>;
+
+    /// UPSERT состояния локальной копии изображения APOD
+    async fn save_apod_media(&self, media: &ApodMedia) -> Result<(), AppError>;
+
+    /// UPSERT объектов NeoWs и их сближений, возвращает число объектов
+    async fn upsert_neo(&self, objects: &[Value]) -> Result<u64, AppError>;
+
+    /// Сближения с Землей в интервале, по времени сближения
+    async fn get_neo_approaches(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        hazardous_only: bool,
+        limit: i64,
+    ) -> Result<Vec<NeoApproachView>, AppError>;
+
+    /// Счетчики объектов и ближайших сближений для дашборда
+    async fn get_neo_counts(&self) -> Result<NeoCounts, AppError>;
+
+    /// Сохраняет запись NeoWs lookup: объект, все сближения и орбиту
+    async fn save_neo_lookup(&self, raw: &Value) -> Result<(), AppError>;
+
+    /// Карточка объекта со всеми сохраненными сближениями
+    async fn get_neo_detail(&self, neo_id: &str) -> Result<Option<NeoDetail>, AppError>;
+
+    /// Отмечает попытку lookup без данных (404 в NeoWs)
+    async fn touch_neo_lookup(&self, neo_id: &str) -> Result<(), AppError>;
+
+    /// Объекты, чей lookup загружался раньше older_than (самые старые первыми)
+    async fn get_stale_neo_lookups(&self, older_than: DateTime<Utc>, limit: i64) -> Result<Vec<String>, AppError>;
+
+    /// Пересчет тревог в окне сближений [from, to], возвращает число новых
+    async fn replace_neo_alerts(&self, from: DateTime<Utc>, to: DateTime<Utc>, alerts: &[NeoAlert]) -> Result<u64, AppError>;
+
+    /// Тревоги с важностью не ниже min_level (1 — info, 2 — warning, 3 — critical)
+    async fn get_neo_alerts(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        min_level: i32,
+        limit: i64,
+    ) -> Result<Vec<NeoAlert>, AppError>;
+
+    /// UPSERT значений Kp NOAA по time_tag; наблюдение не перезаписывается оценкой или прогнозом
+    async fn upsert_noaa_kp(&self, rows: &[Value]) -> Result<u64, AppError>;
+
+    /// Kp NOAA за [from, to] по времени
+    async fn get_noaa_kp(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<NoaaKp>, AppError>;
+
+    /// UPSERT точек солнечного ветра (плазма или поле) — обновляются только пришедшие колонки
+    async fn upsert_solar_wind(&self, rows: &[Value]) -> Result<u64, AppError>;
+
+    /// Солнечный ветер за [from, to]: последние limit точек по времени
+    async fn get_solar_wind(&self, from: DateTime<Utc>, to: DateTime<Utc>, limit: i64) -> Result<Vec<SolarWindSample>, AppError>;
+
+    /// Сохраняет элементы орбиты МКС (по эпохе; повтор эпохи обновляет строку)
+    async fn save_iss_elements(&self, elements: &IssElements, raw: &Value) -> Result<(), AppError>;
+
+    /// Самые свежие по эпохе элементы орбиты МКС и время их загрузки
+    async fn get_iss_elements(&self) -> Result<Option<(IssElements, DateTime<Utc>)>, AppError>;
+
+    /// Последний расчет пролетов для точки: (эпоха элементов, конец горизонта, время расчета)
+    async fn get_iss_pass_run(
+        &self,
+        lat_key: i32,
+        lon_key: i32,
+    ) -> Result<Option<(DateTime<Utc>, DateTime<Utc>, DateTime<Utc>)>, AppError>;
+
+    /// Заменяет будущие пролеты точки расчетом по эпохе elements_epoch (одной транзакцией);
+    /// пролеты совпадают по витку, revision растет при смене эпохи
+    async fn replace_iss_passes(
+        &self,
+        lat_key: i32,
+        lon_key: i32,
+        elements_epoch: DateTime<Utc>,
+        from: DateTime<Utc>,
+        horizon_end: DateTime<Utc>,
+        passes: &[IssPass],
+    ) -> Result<u64, AppError>;
+
+    /// Пролеты над точкой, начинающиеся в [from, to]
+    async fn get_iss_passes(&self, lat_key: i32, lon_key: i32, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<IssPass>, AppError>;
+
+    /// Удаляет пролеты, закончившиеся до past_before, и точки (с их пролетами), не пересчитывавшиеся
+    /// с idle_before. Возвращает (удалено прошедших пролетов, удалено точек)
+    async fn prune_iss_passes(&self, past_before: DateTime<Utc>, idle_before: DateTime<Utc>) -> Result<(u64, u64), AppError>;
+
+    /// Запись в универсальный space_cache (витрина /space/:src/latest); неизменный payload только отмечает проверку
+    async fn write_cache(&self, source: &str, payload: &Value) -> Result<(), AppError>;
+
+    /// Источники, у которых есть записи в space_cache
+    async fn get_cache_sources(&self) -> Result<Vec<String>, AppError>;
+
+    /// Чистит space_cache источника по возрасту и числу строк: (удалено по возрасту, по числу, осталось)
+    async fn prune_cache(
+        &self,
+        source: &str,
+        older_than: Option<DateTime<Utc>>,
+        keep_rows: Option<i64>,
+    ) -> Result<(u64, u64, i64), AppError>;
+}
+
 // ------------------------------------------------------------
 // Контракт для Бизнес-Логики (Service Layer)
 // ------------------------------------------------------------
 #[async_trait]
 pub trait SpaceServiceContract: Send + Sync {
     /// Выполняет полный цикл: запрашивает данные у NASA и сохраняет в БД
-    async fn fetch_and_save_osdr_data(&self) -> Result<(), AppError>;
+    async fn fetch_and_save_osdr_data(&self) -> Result<OsdrSyncStats, AppError>;
     
     async fn fetch_and_store_iss(&self) -> Result<(), AppError>;
     
     async fn get_iss_position(&self) -> Result<IssPosition, AppError>;
     
     /// Получает список OSDR - ДОБАВЬТЕ ЭТОТ МЕТОД
-    async fn get_osdr_list(&self) -> Result<Vec<OsdrItem>, AppError>;
+    async fn get_osdr_list(&self, include_withdrawn: bool) -> Result<Vec<OsdrItem>, AppError>;
+
+    /// Детальная карточка OSDR: запись из БД + метаданные REST_URL (кэш с TTL)
+    async fn get_osdr_detail(&self, dataset_id: &str) -> Result<OsdrDetail, AppError>;
+
+    /// Полный обход пагинированного OSDR с чекпоинтом после каждой страницы
+    async fn crawl_osdr(&self) -> Result<OsdrCrawlState, AppError>;
+
+    /// Текущий прогресс обхода OSDR
+    async fn get_osdr_crawl_state(&self) -> Result<Option<OsdrCrawlState>, AppError>;
+
+    /// Журнал синхронизаций OSDR
+    async fn get_osdr_sync_log(&self, limit: i64) -> Result<Vec<OsdrSyncRun>, AppError>;
+
+    /// Статистика OSDR (кэшируется на osdr_stats_ttl)
+    async fn get_osdr_stats(&self) -> Result<OsdrStats, AppError>;
+
+    /// Зеркалирует файлы одного датасета в osdr_mirror_dir
+    async fn mirror_osdr_dataset(&self, dataset_id: &str) -> Result<MirrorReport, AppError>;
+
+    /// Зеркалирует файлы датасетов под фильтр
+    async fn mirror_osdr(&self, filter: &OsdrMirrorFilter) -> Result<MirrorReport, AppError>;
+
+    /// Состояние файлов зеркала датасета
+    async fn get_osdr_files(&self, dataset_id: &str) -> Result<Vec<OsdrFile>, AppError>;
+
+    /// Полностью скачанный файл зеркала
+    async fn get_osdr_file(&self, dataset_id: &str, file_name: &str) -> Result<OsdrFile, AppError>;
+
+    /// Разбирает ISA-Tab из зеркала датасета и сохраняет в нормализованные таблицы
+    async fn import_osdr_isa(&self, dataset_id: &str) -> Result<IsaArchive, AppError>;
+
+    /// Импортированные ISA-Tab метаданные датасета
+    async fn get_osdr_isa(&self, dataset_id: &str) -> Result<IsaArchive, AppError>;
+
+    /// Связанные датасеты (общие миссия, организм, тип ассея, публикация, факторы)
+    async fn get_osdr_related(&self, dataset_id: &str, limit: i64) -> Result<Vec<OsdrRelated>, AppError>;
+
+    /// Граф связей датасетов для визуализации (кэшируется на osdr_stats_ttl)
+    async fn get_osdr_graph(&self, min_weight: i32) -> Result<OsdrGraph, AppError>;
+
+    /// Пишет снимок всего каталога osdr_items (NDJSON, gzip) и manifest
+    async fn create_osdr_snapshot(&self) -> Result<OsdrSnapshot, AppError>;
+
+    async fn get_osdr_snapshots(&self) -> Result<Vec<OsdrSnapshot>, AppError>;
+
+    async fn get_osdr_snapshot(&self, id: &str) -> Result<OsdrSnapshot, AppError>;
+
+    /// Разница двух снимков: добавленные, удаленные и измененные dataset_id
+    async fn diff_osdr_snapshots(&self, from: &str, to: &str) -> Result<OsdrSnapshotDiff, AppError>;
+
+    /// Запись APOD за дату (при отсутствии — запрос к upstream)
+    async fn get_apod(&self, date: NaiveDate) -> Result<ApodEntry, AppError>;
+
+    /// Записи APOD за диапазон; недостающие даты догружаются из upstream
+    async fn get_apod_range(&self, start: NaiveDate, end: NaiveDate) -> Result<Vec<ApodEntry>, AppError>;
+
+    /// Догружает архив APOD за диапазон окнами, пропуская уже сохраненные окна
+    async fn backfill_apod(&self, start: NaiveDate, end: NaiveDate) -> Result<u64, AppError>;
+
+    /// Поиск по архиву APOD с фильтрами по датам и типу медиа
+    async fn search_apod(&self, filter: &ApodSearchFilter) -> Result<Vec<ApodSearchHit>, AppError>;
+
+    /// Скачивает изображение APOD за дату и строит thumb/medium версии
+    async fn cache_apod_media(&self, date: NaiveDate) -> Result<ApodMedia, AppError>;
+
+    /// Изображение APOD нужного размера (thumb | medium | original); для видео — ссылка на оригинал
+    async fn get_apod_image(&self, date: NaiveDate, size: &str) -> Result<ApodImage, AppError>;
+
+    /// Загружает ленту NeoWs за диапазон (не больше года) окнами по 7 дней, возвращает число объектов
+    async fn fetch_neo_range(&self, start: NaiveDate, end: NaiveDate) -> Result<u64, AppError>;
+
+    /// Сближения с Землей в ближайшие days дней (только опасные — hazardous_only)
+    async fn get_neo_upcoming(&self, days: i64, hazardous_only: bool, limit: i64) -> Result<Vec<NeoApproachView>, AppError>;
+
+    async fn get_neo_counts(&self) -> Result<NeoCounts, AppError>;
+
+    /// Карточка объекта; lookup загружается, если его нет или он старше NEO_LOOKUP_MAX_AGE_HOURS
+    async fn get_neo_detail(&self, neo_id: &str) -> Result<NeoDetail, AppError>;
+
+    /// Проверяет ближайшие сближения по правилам тревог, возвращает число новых тревог
+    async fn evaluate_neo_alerts(&self) -> Result<u64, AppError>;
+
+    /// Тревоги по сближениям в ближайшие days дней, не ниже min_severity (info | warning | critical)
+    async fn get_neo_alerts(&self, days: i64, min_severity: &str, limit: i64) -> Result<Vec<NeoAlert>, AppError>;
+
+    /// Плановое обновление устаревших lookup, возвращает число обновленных объектов
+    async fn refresh_neo_lookups(&self) -> Result<u64, AppError>;
+
+    /// Обновляет один источник DONKI (flr | cme | gst | sep | ips | hss | rbe | notifications) за последние дни и пишет сводку в space_cache
+    async fn fetch_and_cache_donki_source(&self, kind: &str) -> Result<(), AppError>;
+
+    /// Загружает события DONKI типа kind за диапазон окнами, возвращает число событий
+    async fn fetch_donki_range(&self, kind: &str, start: NaiveDate, end: NaiveDate) -> Result<u64, AppError>;
+
+    /// Вспышки за диапазон дат (по началу), class_prefix — фильтр по классу ("X", "M5")
+    async fn get_donki_flares(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        class_prefix: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<DonkiFlare>, AppError>;
+
+    /// CME за диапазон дат (по началу) со всеми анализами
+    async fn get_donki_cmes(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        min_speed: Option<f64>,
+        limit: i64,
+    ) -> Result<Vec<DonkiCme>, AppError>;
+
+    /// События GST/SEP/IPS/HSS/RBE за диапазон дат
+    async fn get_donki_events(&self, kind: &str, start: NaiveDate, end: NaiveDate, limit: i64) -> Result<Vec<DonkiEvent>, AppError>;
+
+    /// Уведомления прогнозистов за диапазон дат (message_type — FLR, CME, Report, ...)
+    async fn get_donki_notifications(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        message_type: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<DonkiNotification>, AppError>;
+
+    /// Причинная цепочка события (вспышка -> CME -> буря) по времени, с интервалами между этапами
+    async fn get_donki_chain(&self, activity_id: &str) -> Result<DonkiChain, AppError>;
+
+    /// Текущий уровень активности по вспышкам, CME к Земле и Kp, с объяснением
+    async fn get_space_weather_status(&self) -> Result<SpaceWeatherStatus, AppError>;
+
+    /// Месячные ряды: вспышки по классу, CME по скорости
+    async fn get_space_weather_stats(&self, months: i64) -> Result<SpaceWeatherStats, AppError>;
+
+    /// Планетарный Kp NOAA за [from, to]: наблюдения, оценки и прогноз
+    async fn get_noaa_kp(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<NoaaKp>, AppError>;
+
+    /// Солнечный ветер NOAA (плазма + поле) за [from, to], не больше limit последних точек
+    async fn get_solar_wind(&self, from: DateTime<Utc>, to: DateTime<Utc>, limit: i64) -> Result<Vec<SolarWindSample>, AppError>;
+
+    /// Предстоящие или прошедшие пуски с ракетой и площадкой
+    async fn get_launches(&self, upcoming: bool, limit: i64) -> Result<Vec<Launch>, AppError>;
+
+    /// Пуск по id с журналом изменений (скрабы, переносы, смена статуса)
+    async fn get_launch(&self, launch_id: &str) -> Result<Launch, AppError>;
+
+    /// Загружает и сохраняет текущие элементы орбиты МКС, возвращает их
+    async fn fetch_and_store_iss_elements(&self) -> Result<IssElements, AppError>;
+
+    /// Видимые пролеты МКС над точкой на days суток вперед (расчет кэшируется по точке и эпохе элементов)
+    async fn get_iss_passes(&self, lat: f64, lon: f64, days: i64) -> Result<IssPassForecast, AppError>;
+
+    /// Чистит прошедшие пролеты и давно не запрашиваемые точки: (пролетов, точек)
+    async fn prune_iss_passes(&self) -> Result<(u64, u64), AppError>;
+
+    /// Чистит space_cache по правилам хранения источников, возвращает отчет по каждому источнику
+    async fn prune_space_cache(&self) -> Result<Vec<CachePruneReport>, AppError>;
 
     // Cache/APOD/NEO/DONKI/SPACEX (методы, которые использует background/mod.rs)
     async fn fetch_and_cache_apod(&self) -> Result<(), AppError>;
     async fn fetch_and_cache_neo(&self) -> Result<(), AppError>;
     async fn fetch_and_cache_donki(&self) -> Result<(), AppError>;
+    async fn fetch_and_cache_noaa(&self) -> Result<(), AppError>;
     async fn fetch_and_cache_spacex(&self) -> Result<(), AppError>;
+    async fn fetch_and_cache_ll2(&self) -> Result<(), AppError>;
 
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
+use std::collections::HashMap;
 use std::sync::Arc; // <--- Новый импорт для Arc
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use reqwest_middleware::ClientWithMiddleware; // Оставим пока, если используется где-то еще
@@ -24,6 +25,41 @@ pub struct AppConfig {
     pub every_neo: u64,
     pub every_donki: u64,
     pub every_spacex: u64,
+    pub every_noaa: u64,
+    pub every_ll2: u64,
+    pub every_iss_elements: u64,
+    // Базовый URL NOAA SWPC (services.swpc.noaa.gov)
+    pub noaa_swpc_url: String,
+    // Базовый URL SpaceX API v4
+    pub spacex_api_url: String,
+    // Launch Library 2: базовый URL и размер страницы (пусков за запрос)
+    pub ll2_api_url: String,
+    pub ll2_page_limit: u32,
+    // Базовый URL CelesTrak (элементы орбиты МКС)
+    pub celestrak_api_url: String,
+    // TTL кэша детальных метаданных OSDR (секунды)
+    pub osdr_detail_ttl: u64,
+    // Полный обход пагинированного OSDR API вместо одной страницы
+    pub osdr_crawl: bool,
+    // Лимит страниц за один запуск обхода
+    pub osdr_crawl_page_budget: u32,
+    // TTL кэша статистики OSDR (секунды)
+    pub osdr_stats_ttl: u64,
+    // Зеркало файлов OSDR: шаблон URL списка файлов ({dataset_id}), каталог, лимит размера файла
+    pub osdr_files_url: String,
+    pub osdr_mirror_dir: String,
+    pub osdr_mirror_max_file_bytes: u64,
+    // Снимки каталога OSDR: каталог файлов и интервал (секунды)
+    pub osdr_snapshot_dir: String,
+    pub every_osdr_snapshot: u64,
+    // Локальный кэш изображений APOD: каталог и лимит размера оригинала
+    pub apod_media_dir: String,
+    pub apod_media_max_bytes: u64,
+    // Правила тревог по сближениям NEO
+    pub neo_alert_rules: NeoAlertRules,
+    // Хранение space_cache: правила по источникам ("*" — по умолчанию) и интервал обслуживания (секунды)
+    pub space_cache_retention: HashMap<String, CacheRetention>,
+    pub every_space_cache_maintenance: u64,
     // УБРАНО: pub nasa_client: ClientWithMiddleware, 
 }
 
@@ -100,6 +136,60 @@ pub struct IssLog {
     pub payload: serde_json::Value,
 }
 
+// Типизированные метаданные датасета OSDR, извлеченные из raw
+#[derive(sqlx::FromRow, Deserialize, Serialize, Default, Clone, Debug)]
+pub struct OsdrDataset {
+    pub organism: Option<String>,
+    pub mission: Option<String>,
+    pub project_type: Option<String>,
+    pub assay_technology: Option<String>,
+    pub factors: Vec<String>,
+    pub release_date: Option<NaiveDate>,
+    pub doi: Option<String>,
+}
+
+impl OsdrDataset {
+    /// Версия извлечения: при изменении ключей ниже строки osdr_items переизвлекаются
+    pub const EXTRACTOR_VERSION: i32 = 1;
+
+    /// Терпимое извлечение по известным вариантам ключей (плоские API v1/v2 и ISA-пути)
+    pub fn from_raw(raw: &serde_json::Value) -> Self {
+        use crate::utils::json_tools::{d_pick, list_pick, s_pick_any};
+
+        Self {
+            organism: s_pick_any(raw, &[
+                "organism", "Organism", "organisms", "study.characteristics.organism",
+                "/characteristics/organism", "/study/organism",
+            ]),
+            mission: s_pick_any(raw, &[
+                "mission", "Mission", "mission_name", "missionName", "flight_program", "flightProgram",
+                "investigation.study.comment.mission name", "/mission/name",
+            ]),
+            project_type: s_pick_any(raw, &[
+                "project_type", "projectType", "Project Type", "project",
+                "investigation.study.comment.project type", "/project/type",
+            ]),
+            assay_technology: s_pick_any(raw, &[
+                "assay_technology", "assayTechnology", "assay_technology_type", "assay_type", "assayType",
+                "study.assay technology type", "study assay technology type",
+                "investigation.study assays.study assay technology type",
+            ]),
+            factors: list_pick(raw, &[
+                "factors", "Factors", "study_factors", "studyFactors", "factor_names",
+                "study.factor name", "investigation.study factors.study factor name",
+            ]),
+            release_date: d_pick(raw, &[
+                "release_date", "releaseDate", "public_release_date", "publicReleaseDate",
+                "study public release date", "investigation.study.study public release date",
+            ]),
+            doi: s_pick_any(raw, &[
+                "doi", "DOI", "data_doi", "dataDoi", "study.doi",
+                "investigation.study publications.study publication doi", "/identifiers/doi",
+            ]),
+        }
+    }
+}
+
 // Структура для OSDR (как хранится в DB)
 #[derive(sqlx::FromRow, Deserialize, Serialize)]
 pub struct OsdrItem {
@@ -110,6 +200,1206 @@ pub struct OsdrItem {
     pub updated_at: Option<DateTime<Utc>>,
     pub inserted_at: DateTime<Utc>,
     pub raw: serde_json::Value,
+    pub last_seen_at: Option<DateTime<Utc>>,
+    pub withdrawn_at: Option<DateTime<Utc>>, // датасет исчез из фида upstream
+    #[sqlx(flatten)]
+    pub dataset: OsdrDataset,
+}
+
+// Кэш детальных метаданных OSDR (ответ REST_URL)
+#[derive(sqlx::FromRow, Deserialize, Serialize, Clone)]
+pub struct OsdrDetailCache {
+    pub dataset_id: String,
+    pub fetched_at: DateTime<Utc>,
+    pub payload: serde_json::Value,
+}
+
+// Детальная карточка OSDR: строка osdr_items + метаданные из REST_URL
+#[derive(Serialize)]
+pub struct OsdrDetail {
+    pub item: OsdrItem,
+    pub detail: Option<serde_json::Value>,
+    pub detail_fetched_at: Option<DateTime<Utc>>,
+    pub detail_stale: bool,
+}
+
+// Валидаторы HTTP для условных запросов (If-None-Match / If-Modified-Since)
+#[derive(Clone, Default, Debug)]
+pub struct HttpValidators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+// Одна страница пагинированного OSDR API
+pub struct OsdrPage {
+    pub items: Vec<serde_json::Value>,
+    pub next_url: Option<String>,
+    pub total: Option<i64>,  // общее число записей фида, если upstream его сообщает
+    pub not_modified: bool, // upstream ответил 304
+    pub validators: HttpValidators,
+}
+
+// Сохраненные валидаторы и ссылка на следующую страницу для URL страницы OSDR
+#[derive(sqlx::FromRow, Clone)]
+pub struct OsdrHttpCache {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub next_url: Option<String>,
+    pub dataset_ids: Vec<String>,
+}
+
+// Метрики одного прогона синхронизации OSDR
+#[derive(sqlx::FromRow, Serialize, Default, Clone)]
+pub struct OsdrSyncStats {
+    pub pages_fetched: i32,
+    pub pages_not_modified: i32,
+    pub items_fetched: i64,
+    pub items_unchanged: i64,
+    pub items_written: i64,
+    pub items_withdrawn: i64,
+    pub items_restored: i64,
+}
+
+// Запись журнала синхронизаций OSDR (osdr_sync_log)
+#[derive(sqlx::FromRow, Serialize)]
+pub struct OsdrSyncRun {
+    pub id: i64,
+    pub mode: String, // sync | crawl
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub stats: OsdrSyncStats,
+}
+
+// Чекпоинт полного обхода OSDR (строка osdr_crawl_state)
+#[derive(sqlx::FromRow, Deserialize, Serialize, Clone)]
+pub struct OsdrCrawlState {
+    pub crawl: String,
+    pub status: String, // running | paused | completed | failed
+    pub next_url: Option<String>,
+    pub pages_done: i32,
+    pub items_done: i64,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+// Одна корзина агрегата статистики (значение измерения -> количество)
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct StatBucket {
+    pub key: String,
+    pub count: i64,
+}
+
+// Точка месячного ряда OSDR
+#[derive(sqlx::FromRow, Serialize, Clone)]
+pub struct MonthlyCount {
+    pub month: DateTime<Utc>,
+    pub new_datasets: i64,     // по inserted_at
+    pub updated_datasets: i64, // по updated_at
+}
+
+// Статистика /osdr/stats
+#[derive(Serialize, Clone)]
+pub struct OsdrStats {
+    pub total: i64,     // живые датасеты; срезы и месячный ряд тоже только по ним
+    pub withdrawn: i64,
+    pub by_status: Vec<StatBucket>,
+    pub by_organism: Vec<StatBucket>,
+    pub by_mission: Vec<StatBucket>,
+    pub by_assay_type: Vec<StatBucket>,
+    pub by_project: Vec<StatBucket>,
+    pub monthly: Vec<MonthlyCount>,
+    pub computed_at: DateTime<Utc>,
+}
+
+// Файл датасета из листинга upstream
+#[derive(Clone, Debug)]
+pub struct OsdrRemoteFile {
+    pub name: String,
+    pub url: String,
+    pub size: Option<i64>,
+}
+
+// Результат скачивания файла в .part
+pub enum DownloadOutcome {
+    Complete(u64, Option<String>), // итоговый размер файла и Content-Type ответа (при 416 неизвестен)
+    TooLarge(u64), // превышен лимит размера (байт получено к моменту остановки)
+}
+
+// Состояние файла зеркала (osdr_files)
+#[derive(sqlx::FromRow, Serialize, Clone)]
+pub struct OsdrFile {
+    pub id: i64,
+    pub dataset_id: String,
+    pub file_name: String,
+    pub remote_url: String,
+    #[serde(skip_serializing)]
+    pub local_path: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub downloaded_bytes: i64,
+    pub sha256: Option<String>,
+    pub state: String, // pending | downloading | complete | failed | skipped
+    pub error: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// Фильтр для зеркалирования набора датасетов
+#[derive(Deserialize, Default, Debug)]
+pub struct OsdrMirrorFilter {
+    pub q: Option<String>,      // подстрока в title
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+}
+
+// Итог прогона зеркалирования
+#[derive(Serialize, Default)]
+pub struct MirrorReport {
+    pub datasets: i64,
+    pub files_complete: i64,
+    pub files_skipped: i64,
+    pub files_failed: i64,
+    pub bytes_downloaded: i64,
+}
+
+// ISA-Tab: протокол исследования (i_*.txt, раздел STUDY PROTOCOLS)
+#[derive(sqlx::FromRow, Serialize, Clone, Default, Debug)]
+pub struct IsaProtocol {
+    pub name: String,
+    pub protocol_type: Option<String>,
+    pub description: Option<String>,
+}
+
+// ISA-Tab: Characteristics[...] или Factor Value[...] образца
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct IsaAnnotation {
+    pub category: String,
+    pub value: String,
+    pub unit: Option<String>,
+}
+
+// ISA-Tab: образец из таблицы исследования (s_*.txt)
+#[derive(Serialize, Clone, Default, Debug)]
+pub struct IsaSample {
+    pub sample_name: String,
+    pub source_name: Option<String>,
+    pub study_file: String,
+    pub protocols: Vec<String>,
+    pub characteristics: Vec<IsaAnnotation>,
+    pub factor_values: Vec<IsaAnnotation>,
+}
+
+// ISA-Tab: ассей (раздел STUDY ASSAYS + таблица a_*.txt)
+#[derive(sqlx::FromRow, Serialize, Clone, Default, Debug)]
+pub struct IsaAssay {
+    pub file_name: String,
+    pub measurement_type: Option<String>,
+    pub technology_type: Option<String>,
+    pub technology_platform: Option<String>,
+    pub sample_count: i32,
+}
+
+// Разобранный ISA-Tab архив датасета
+#[derive(Serialize, Default, Debug)]
+pub struct IsaArchive {
+    pub identifier: Option<String>,
+    pub title: Option<String>,
+    pub source: String, // каталог или zip, из которого разобрано
+    pub protocols: Vec<IsaProtocol>,
+    pub samples: Vec<IsaSample>,
+    pub assays: Vec<IsaAssay>,
+}
+
+// Связанный датасет: вес общих атрибутов и их список (mission, organism, ...)
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct OsdrRelated {
+    pub dataset_id: String,
+    pub title: Option<String>,
+    pub score: i32,
+    pub shared: Vec<String>,
+}
+
+// Узел графа датасетов /osdr/graph
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct OsdrGraphNode {
+    pub dataset_id: String,
+    pub title: Option<String>,
+    pub organism: Option<String>,
+    pub mission: Option<String>,
+    pub assay_technology: Option<String>,
+}
+
+// Ребро графа: пара датасетов с общими атрибутами
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct OsdrGraphEdge {
+    pub source: String,
+    pub target: String,
+    pub weight: i32,
+    pub shared: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct OsdrGraph {
+    pub nodes: Vec<OsdrGraphNode>,
+    pub edges: Vec<OsdrGraphEdge>,
+    pub computed_at: DateTime<Utc>,
+}
+
+// Снимок каталога osdr_items (osdr-<id>.ndjson.gz + manifest)
+#[derive(sqlx::FromRow, Serialize, Deserialize, Clone, Debug)]
+pub struct OsdrSnapshot {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub item_count: i64,
+    pub sha256: String, // хэш сжатого файла
+    pub size_bytes: i64,
+    #[serde(skip_serializing, default)]
+    pub file_path: String,
+}
+
+// Разница двух снимков по dataset_id
+#[derive(Serialize, Debug)]
+pub struct OsdrSnapshotDiff {
+    pub from: String,
+    pub to: String,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+// Запись APOD за одну дату (как хранится в apod_entries)
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct ApodEntry {
+    pub date: NaiveDate,
+    pub title: Option<String>,
+    pub explanation: Option<String>,
+    pub media_type: Option<String>, // image | video | other
+    pub url: Option<String>,
+    pub hdurl: Option<String>,
+    pub thumbnail_url: Option<String>,
+    pub copyright: Option<String>,
+    pub fetched_at: DateTime<Utc>,
+}
+
+impl ApodEntry {
+    /// Первая дата архива APOD
+    pub fn first_date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(1995, 6, 16).expect("valid date")
+    }
+
+    /// Последняя возможная дата архива: APOD публикуется по времени US Eastern,
+    /// поэтому вечером по Америке UTC-дата уже "завтрашняя", а записи еще нет
+    pub fn latest_date() -> NaiveDate {
+        us_eastern_date(Utc::now())
+    }
+
+    /// Запись из ответа api.nasa.gov/planetary/apod; без даты запись пропускается
+    pub fn from_raw(raw: &serde_json::Value) -> Option<Self> {
+        use crate::utils::json_tools::{d_pick, s_pick};
+
+        Some(Self {
+            date: d_pick(raw, &["date"])?,
+            title: s_pick(raw, &["title"]),
+            explanation: s_pick(raw, &["explanation"]),
+            media_type: s_pick(raw, &["media_type"]),
+            url: s_pick(raw, &["url"]),
+            hdurl: s_pick(raw, &["hdurl"]),
+            thumbnail_url: s_pick(raw, &["thumbnail_url"]),
+            // В ответе copyright бывает с переводами строк
+            copyright: s_pick(raw, &["copyright"]).map(|c| c.split_whitespace().collect::<Vec<_>>().join(" ")),
+            fetched_at: Utc::now(),
+        })
+    }
+}
+
+/// Дата по US Eastern: EDT (UTC-4) со второго воскресенья марта до первого воскресенья ноября
+/// (переход в 2:00 по местному времени), иначе EST (UTC-5)
+fn us_eastern_date(now: DateTime<Utc>) -> NaiveDate {
+    let nth_sunday = |month: u32, n: i64| {
+        let first = NaiveDate::from_ymd_opt(now.year(), month, 1).expect("valid date");
+        let to_sunday = (7 - first.weekday().num_days_from_sunday() as i64) % 7;
+        first + Duration::days(to_sunday + 7 * (n - 1))
+    };
+    let dst_start = nth_sunday(3, 2).and_hms_opt(7, 0, 0).expect("valid time").and_utc();
+    let dst_end = nth_sunday(11, 1).and_hms_opt(6, 0, 0).expect("valid time").and_utc();
+    let offset = if now >= dst_start && now < dst_end { 4 } else { 5 };
+    (now - Duration::hours(offset)).date_naive()
+}
+
+// Локальная копия изображения APOD и его уменьшенные версии
+#[derive(sqlx::FromRow, Serialize, Clone, Debug, Default)]
+pub struct ApodMedia {
+    pub date: NaiveDate,
+    pub source_url: Option<String>,
+    pub state: String, // complete | video | skipped | failed | pending (качается другим запросом, не сохраняется)
+    #[serde(skip_serializing)]
+    pub original_path: Option<String>,
+    #[serde(skip_serializing)]
+    pub thumb_path: Option<String>,
+    #[serde(skip_serializing)]
+    pub medium_path: Option<String>,
+    pub content_type: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub sha256: Option<String>,
+    pub error: Option<String>,
+    pub retry_after: Option<DateTime<Utc>>, // для skipped/failed: до этого момента upstream не трогаем
+    pub updated_at: DateTime<Utc>,
+}
+
+// Фильтр полнотекстового поиска по архиву APOD
+#[derive(Deserialize, Default, Debug)]
+pub struct ApodSearchFilter {
+    pub q: Option<String>, // обязателен; без него 400 из сервиса, а не ошибка разбора query string
+    pub start: Option<NaiveDate>,
+    pub end: Option<NaiveDate>,
+    pub media_type: Option<String>,
+    pub limit: Option<i64>,
+}
+
+// Результат поиска APOD: ранг и фрагмент объяснения с подсветкой
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct ApodSearchHit {
+    pub date: NaiveDate,
+    pub title: Option<String>,
+    pub media_type: Option<String>,
+    pub url: Option<String>,
+    pub thumbnail_url: Option<String>,
+    pub rank: f32,
+    pub snippet: Option<String>,
+}
+
+// Что отдавать на /space/apod/:date/image
+pub enum ApodImage {
+    File { path: String, content_type: String, etag: String, updated_at: DateTime<Utc> },
+    Redirect(String),
+}
+
+// Околоземный объект NeoWs (как хранится в neo_objects)
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct NeoObject {
+    pub neo_id: String,
+    pub name: Option<String>,
+    pub nasa_jpl_url: Option<String>,
+    pub absolute_magnitude_h: Option<f64>,
+    pub diameter_min_m: Option<f64>,
+    pub diameter_max_m: Option<f64>,
+    pub is_hazardous: bool,
+    pub is_sentry: bool,
+}
+
+impl NeoObject {
+    /// Объект из записи NeoWs (feed или lookup); без id запись пропускается
+    pub fn from_raw(raw: &serde_json::Value) -> Option<Self> {
+        use crate::utils::json_tools::{num, s_pick};
+
+        Some(Self {
+            neo_id: s_pick(raw, &["id", "neo_reference_id"])?,
+            name: s_pick(raw, &["name"]),
+            nasa_jpl_url: s_pick(raw, &["nasa_jpl_url"]),
+            absolute_magnitude_h: num(&raw["absolute_magnitude_h"]),
+            diameter_min_m: num(&raw["estimated_diameter"]["meters"]["estimated_diameter_min"]),
+            diameter_max_m: num(&raw["estimated_diameter"]["meters"]["estimated_diameter_max"]),
+            is_hazardous: raw["is_potentially_hazardous_asteroid"].as_bool().unwrap_or(false),
+            is_sentry: raw["is_sentry_object"].as_bool().unwrap_or(false),
+        })
+    }
+}
+
+// Сближение объекта с телом (как хранится в neo_approaches)
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct NeoApproach {
+    pub approach_at: DateTime<Utc>,
+    pub approach_date: NaiveDate,
+    pub miss_distance_km: Option<f64>,
+    pub miss_distance_lunar: Option<f64>,
+    pub velocity_kms: Option<f64>,
+    pub orbiting_body: String,
+}
+
+impl NeoApproach {
+    /// Сближения из close_approach_data; числа в NeoWs приходят строками
+    pub fn list_from_raw(raw: &serde_json::Value) -> Vec<Self> {
+        use crate::utils::json_tools::{num, s_pick};
+        use chrono::{NaiveDateTime, TimeZone};
+
+        let Some(list) = raw["close_approach_data"].as_array() else { return vec![] };
+        list.iter().filter_map(|a| {
+            let approach_date = s_pick(a, &["close_approach_date"])
+                .and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok())?;
+            // Точное время: epoch в мс, иначе "2024-Jan-05 12:34", иначе полночь даты
+            let approach_at = a["epoch_date_close_approach"].as_i64()
+                .and_then(|ms| Utc.timestamp_millis_opt(ms).single())
+                .or_else(|| s_pick(a, &["close_approach_date_full"])
+                    .and_then(|s| NaiveDateTime::parse_from_str(&s, "%Y-%b-%d %H:%M").ok())
+                    .map(|ndt| Utc.from_utc_datetime(&ndt)))
+                .unwrap_or_else(|| Utc.from_utc_datetime(&approach_date.and_hms_opt(0, 0, 0).expect("valid time")));
+
+            Some(Self {
+                approach_at,
+                approach_date,
+                miss_distance_km: num(&a["miss_distance"]["kilometers"]),
+                miss_distance_lunar: num(&a["miss_distance"]["lunar"]),
+                velocity_kms: num(&a["relative_velocity"]["kilometers_per_second"]),
+                orbiting_body: s_pick(a, &["orbiting_body"]).unwrap_or_else(|| "Earth".to_string()),
+            })
+        }).collect()
+    }
+}
+
+// Сближение вместе с данными объекта (/neo/upcoming, /neo/hazardous)
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct NeoApproachView {
+    pub neo_id: String,
+    pub name: Option<String>,
+    pub is_hazardous: bool,
+    pub diameter_min_m: Option<f64>,
+    pub diameter_max_m: Option<f64>,
+    pub detail_url: String,      // /api/neo/:id — полная карточка объекта через прокси дашборда
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub approach: NeoApproach,
+}
+
+// Орбита объекта из NeoWs lookup (/neo/rest/v1/neo/:id)
+#[derive(sqlx::FromRow, Serialize, Clone, Debug, Default)]
+pub struct NeoOrbit {
+    pub orbit_class: Option<String>,
+    pub orbit_class_description: Option<String>,
+    pub orbital_data: Option<serde_json::Value>, // элементы орбиты без orbit_class
+    pub lookup_at: Option<DateTime<Utc>>,        // когда запись lookup последний раз загружалась
+}
+
+impl NeoOrbit {
+    pub fn from_raw(raw: &serde_json::Value) -> Self {
+        use crate::utils::json_tools::s_pick_any;
+
+        let mut orbital_data = raw["orbital_data"].clone();
+        if let Some(obj) = orbital_data.as_object_mut() {
+            obj.remove("orbit_class");
+        }
+        Self {
+            orbit_class: s_pick_any(raw, &["/orbital_data/orbit_class/orbit_class_type"]),
+            orbit_class_description: s_pick_any(raw, &["/orbital_data/orbit_class/orbit_class_description"]),
+            orbital_data: (!orbital_data.is_null()).then_some(orbital_data),
+            lookup_at: None,
+        }
+    }
+}
+
+// Карточка объекта для /neo/:id: данные объекта, орбита и все известные сближения
+#[derive(Serialize, Clone, Debug)]
+pub struct NeoDetail {
+    #[serde(flatten)]
+    pub object: NeoObject,
+    #[serde(flatten)]
+    pub orbit: NeoOrbit,
+    pub approaches: Vec<NeoApproach>,
+}
+
+// Срок хранения записей space_cache одного источника: по возрасту, по числу строк или по обоим.
+// Последняя запись источника не удаляется никогда — витрина /space/:src/latest не пустеет.
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct CacheRetention {
+    pub max_age_hours: Option<i64>,
+    pub max_rows: Option<i64>,
+}
+
+impl CacheRetention {
+    /// Предельный срок хранения (часы, 10 лет): дальше — явно опечатка, а Duration::hours паникует на переполнении
+    pub const MAX_AGE_HOURS: i64 = 10 * 366 * 24;
+
+    /// Правило вида "30d", "72h", "200" (строк) или "7d+500"
+    pub fn parse(spec: &str) -> Option<Self> {
+        let mut rule = Self::default();
+        let age = |hours: Option<i64>| hours.filter(|h| (1..=Self::MAX_AGE_HOURS).contains(h));
+        for part in spec.split('+').map(str::trim) {
+            if let Some(days) = part.strip_suffix('d') {
+                rule.max_age_hours = Some(age(days.parse::<i64>().ok().and_then(|d| d.checked_mul(24)))?);
+            } else if let Some(hours) = part.strip_suffix('h') {
+                rule.max_age_hours = Some(age(hours.parse::<i64>().ok())?);
+            } else {
+                rule.max_rows = Some(part.parse::<i64>().ok().filter(|n| *n > 0)?);
+            }
+        }
+        Some(rule)
+    }
+}
+
+// Итог обслуживания space_cache по одному источнику
+#[derive(Serialize, Clone, Debug)]
+pub struct CachePruneReport {
+    pub source: String,
+    pub retention: CacheRetention,
+    pub removed_by_age: u64,
+    pub removed_by_count: u64,
+    pub kept: i64,
+}
+
+// Правила тревог по сближениям с Землей; любое сработавшее правило дает тревогу
+#[derive(Clone, Debug)]
+pub struct NeoAlertRules {
+    pub max_lunar: f64,      // промах меньше N лунных расстояний
+    pub min_diameter_m: f64, // оценка диаметра (верхняя) больше X метров
+    pub hazardous: bool,     // флаг потенциально опасного объекта
+    pub horizon_days: i64,   // сколько дней вперед проверять (не дальше окна планового обновления ленты)
+}
+
+impl NeoAlertRules {
+    /// Сработавшие правила и важность: одно правило — info, два — warning, все три — critical.
+    /// Флаг PHA сам по себе — warning: NASA уже учла в нем и размер, и близость орбиты.
+    pub fn evaluate(&self, a: &NeoApproachView) -> Option<(&'static str, Vec<String>)> {
+        let mut reasons = Vec::new();
+        if let Some(lunar) = a.approach.miss_distance_lunar.filter(|d| *d < self.max_lunar) {
+            reasons.push(format!("miss distance {:.2} LD < {} LD", lunar, self.max_lunar));
+        }
+        if let Some(d) = a.diameter_max_m.filter(|d| *d > self.min_diameter_m) {
+            reasons.push(format!("diameter up to {:.0} m > {} m", d, self.min_diameter_m));
+        }
+        let hazardous = self.hazardous && a.is_hazardous;
+        if hazardous {
+            reasons.push("potentially hazardous asteroid".to_string());
+        }
+
+        let severity = match reasons.len() {
+            0 => return None,
+            1 if !hazardous => "info",
+            1 | 2 => "warning",
+            _ => "critical",
+        };
+        Some((severity, reasons))
+    }
+}
+
+// Тревога по сближению (neo_alerts): одна на объект и дату сближения
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct NeoAlert {
+    pub neo_id: String,
+    pub name: Option<String>,
+    pub approach_at: DateTime<Utc>,
+    pub approach_date: NaiveDate,
+    pub severity: String,
+    pub reasons: Vec<String>,
+    pub miss_distance_lunar: Option<f64>,
+    pub diameter_max_m: Option<f64>,
+    pub is_hazardous: bool,
+    pub detail_url: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl NeoAlert {
+    pub fn from_approach(a: &NeoApproachView, severity: &str, reasons: Vec<String>) -> Self {
+        Self {
+            neo_id: a.neo_id.clone(),
+            name: a.name.clone(),
+            approach_at: a.approach.approach_at,
+            approach_date: a.approach.approach_date,
+            severity: severity.to_string(),
+            reasons,
+            miss_distance_lunar: a.approach.miss_distance_lunar,
+            diameter_max_m: a.diameter_max_m,
+            is_hazardous: a.is_hazardous,
+            detail_url: a.detail_url.clone(),
+            created_at: Utc::now(),
+        }
+    }
+}
+
+// Солнечная вспышка DONKI FLR (как хранится в donki_flares)
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct DonkiFlare {
+    pub flr_id: String,
+    pub begin_time: Option<DateTime<Utc>>,
+    pub peak_time: Option<DateTime<Utc>>,
+    pub end_time: Option<DateTime<Utc>>,
+    pub class_type: Option<String>,      // X1.2, M5.0, ...
+    pub source_location: Option<String>, // S15W42
+    pub active_region_num: Option<i32>,
+    pub instruments: Vec<String>,
+    pub link: Option<String>,
+}
+
+impl DonkiFlare {
+    /// Пиковый поток рентгена (Вт/м²) по классу: "M2.5" -> 2.5e-5
+    pub fn class_flux(class_type: &str) -> Option<f64> {
+        let mut chars = class_type.trim().chars();
+        let base = match chars.next()?.to_ascii_uppercase() {
+            'A' => 1e-8,
+            'B' => 1e-7,
+            'C' => 1e-6,
+            'M' => 1e-5,
+            'X' => 1e-4,
+            _ => return None,
+        };
+        Some(base * chars.as_str().parse::<f64>().unwrap_or(1.0))
+    }
+
+    pub fn from_raw(raw: &serde_json::Value) -> Option<Self> {
+        use crate::utils::json_tools::{num, s_pick, t_pick};
+
+        Some(Self {
+            flr_id: s_pick(raw, &["flrID"])?,
+            begin_time: t_pick(raw, &["beginTime"]),
+            peak_time: t_pick(raw, &["peakTime"]),
+            end_time: t_pick(raw, &["endTime"]),
+            class_type: s_pick(raw, &["classType"]),
+            source_location: s_pick(raw, &["sourceLocation"]),
+            active_region_num: num(&raw["activeRegionNum"]).map(|n| n as i32),
+            instruments: raw["instruments"].as_array()
+                .map(|list| list.iter().filter_map(|i| s_pick(i, &["displayName"])).collect())
+                .unwrap_or_default(),
+            link: s_pick(raw, &["link"]),
+        })
+    }
+}
+
+// Анализ CME (как хранится в donki_cme_analyses)
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct DonkiCmeAnalysis {
+    pub time21_5: Option<DateTime<Utc>>, // момент прохождения 21.5 солнечных радиусов
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub half_angle: Option<f64>,
+    pub speed_kms: Option<f64>,
+    pub cme_type: Option<String>,        // S, C, O, R, ER
+    pub is_most_accurate: bool,
+    pub level_of_data: Option<i32>,
+    pub note: Option<String>,
+    pub link: Option<String>,
+}
+
+impl DonkiCmeAnalysis {
+    pub fn list_from_raw(raw: &serde_json::Value) -> Vec<Self> {
+        use crate::utils::json_tools::{num, s_pick, t_pick};
+
+        let Some(list) = raw["cmeAnalyses"].as_array() else { return vec![] };
+        list.iter().map(|a| Self {
+            time21_5: t_pick(a, &["time21_5"]),
+            latitude: num(&a["latitude"]),
+            longitude: num(&a["longitude"]),
+            half_angle: num(&a["halfAngle"]),
+            speed_kms: num(&a["speed"]),
+            cme_type: s_pick(a, &["type"]),
+            is_most_accurate: a["isMostAccurate"].as_bool().unwrap_or(false),
+            level_of_data: num(&a["levelOfData"]).map(|n| n as i32),
+            note: s_pick(a, &["note"]),
+            link: s_pick(a, &["link"]),
+        }).collect()
+    }
+}
+
+// Корональный выброс массы DONKI CME (как хранится в donki_cmes).
+// Скорость, угол и тип — из самого точного анализа (isMostAccurate), иначе из последнего.
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct DonkiCme {
+    pub activity_id: String,
+    pub start_time: Option<DateTime<Utc>>,
+    pub source_location: Option<String>,
+    pub active_region_num: Option<i32>,
+    pub catalog: Option<String>,
+    pub note: Option<String>,
+    pub link: Option<String>,
+    pub speed_kms: Option<f64>,
+    pub half_angle: Option<f64>,
+    pub cme_type: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    #[sqlx(skip)]
+    pub analyses: Vec<DonkiCmeAnalysis>,
+}
+
+impl DonkiCme {
+    pub fn from_raw(raw: &serde_json::Value) -> Option<Self> {
+        use crate::utils::json_tools::{num, s_pick, t_pick};
+
+        let analyses = DonkiCmeAnalysis::list_from_raw(raw);
+        let best = analyses.iter().find(|a| a.is_most_accurate).or(analyses.last()).cloned();
+        Some(Self {
+            activity_id: s_pick(raw, &["activityID"])?,
+            start_time: t_pick(raw, &["startTime"]),
+            source_location: s_pick(raw, &["sourceLocation"]),
+            active_region_num: num(&raw["activeRegionNum"]).map(|n| n as i32),
+            catalog: s_pick(raw, &["catalog"]),
+            note: s_pick(raw, &["note"]),
+            link: s_pick(raw, &["link"]),
+            speed_kms: best.as_ref().and_then(|a| a.speed_kms),
+            half_angle: best.as_ref().and_then(|a| a.half_angle),
+            cme_type: best.as_ref().and_then(|a| a.cme_type.clone()),
+            latitude: best.as_ref().and_then(|a| a.latitude),
+            longitude: best.as_ref().and_then(|a| a.longitude),
+            analyses,
+        })
+    }
+}
+
+// Прочие события DONKI: (тип, эндпоинт DONKI, ключ id, ключ времени события)
+pub const DONKI_EVENT_TYPES: &[(&str, &str, &str, &str)] = &[
+    ("gst", "GST", "gstID", "startTime"),   // геомагнитные бури
+    ("sep", "SEP", "sepID", "eventTime"),   // солнечные энергичные частицы
+    ("ips", "IPS", "activityID", "eventTime"), // межпланетные ударные волны
+    ("hss", "HSS", "hssID", "eventTime"),   // высокоскоростные потоки солнечного ветра
+    ("rbe", "RBE", "rbeID", "eventTime"),   // усиления радиационных поясов
+];
+
+// Событие DONKI GST/SEP/IPS/HSS/RBE (как хранится в donki_events)
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct DonkiEvent {
+    pub event_id: String,
+    pub kind: String,
+    pub event_time: Option<DateTime<Utc>>,
+    pub location: Option<String>,
+    pub catalog: Option<String>,
+    pub instruments: Vec<String>,
+    pub kp_max: Option<f64>,               // только GST: максимум Kp за бурю
+    pub details: Option<serde_json::Value>, // GST: allKpIndex
+    pub link: Option<String>,
+}
+
+impl DonkiEvent {
+    /// Событие из записи DONKI; ключи id и времени зависят от типа (DONKI_EVENT_TYPES)
+    pub fn from_raw(kind: &str, raw: &serde_json::Value) -> Option<Self> {
+        use crate::utils::json_tools::{num, s_pick, t_pick};
+
+        let (_, _, id_key, time_key) = DONKI_EVENT_TYPES.iter().find(|t| t.0 == kind)?;
+        let kp = raw["allKpIndex"].as_array();
+        Some(Self {
+            event_id: s_pick(raw, &[id_key])?,
+            kind: kind.to_string(),
+            event_time: t_pick(raw, &[time_key]),
+            location: s_pick(raw, &["location", "sourceLocation"]),
+            catalog: s_pick(raw, &["catalog"]),
+            instruments: raw["instruments"].as_array()
+                .map(|list| list.iter().filter_map(|i| s_pick(i, &["displayName"])).collect())
+                .unwrap_or_default(),
+            kp_max: kp.and_then(|list| list.iter().filter_map(|k| num(&k["kpIndex"])).reduce(f64::max)),
+            details: kp.map(|list| serde_json::json!({ "allKpIndex": list })),
+            link: s_pick(raw, &["link"]),
+        })
+    }
+}
+
+// Уведомление прогнозистов DONKI (как хранится в donki_notifications)
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct DonkiNotification {
+    pub message_id: String,
+    pub message_type: Option<String>, // FLR, CME, GST, Report, ...
+    pub issue_time: Option<DateTime<Utc>>,
+    pub url: Option<String>,
+    pub body: Option<String>,
+}
+
+impl DonkiNotification {
+    pub fn from_raw(raw: &serde_json::Value) -> Option<Self> {
+        use crate::utils::json_tools::{s_pick, t_pick};
+
+        Some(Self {
+            message_id: s_pick(raw, &["messageID"])?,
+            message_type: s_pick(raw, &["messageType"]),
+            issue_time: t_pick(raw, &["messageIssueTime"]),
+            url: s_pick(raw, &["messageURL"]),
+            body: s_pick(raw, &["messageBody"]),
+        })
+    }
+}
+
+// Ребро графа событий DONKI (linkedEvents)
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct DonkiLink {
+    pub from_id: String,
+    pub to_id: String,
+}
+
+// Узел цепочки событий (donki_event_nodes); kind/event_time пусты, если событие не сохранено
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct DonkiChainNode {
+    pub event_id: String,
+    pub kind: Option<String>,
+    pub event_time: Option<DateTime<Utc>>,
+    pub label: Option<String>,
+    pub link: Option<String>,
+}
+
+impl DonkiChainNode {
+    /// Дополняет тип и время из самого id DONKI: "2024-05-10T06:27:00-FLR-001"
+    pub fn fill_from_id(&mut self) {
+        use chrono::{NaiveDateTime, TimeZone};
+
+        if self.kind.is_none() {
+            self.kind = self.event_id.split('-').rev().nth(1).map(|k| k.to_lowercase());
+        }
+        if self.event_time.is_none() {
+            self.event_time = self.event_id.get(..19)
+                .and_then(|s| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S").ok())
+                .map(|ndt| Utc.from_utc_datetime(&ndt));
+        }
+    }
+}
+
+// Этап цепочки: событие и время от предыдущего этапа / от начала цепочки (часы)
+#[derive(Serialize, Clone, Debug)]
+pub struct DonkiChainStage {
+    #[serde(flatten)]
+    pub node: DonkiChainNode,
+    pub stored: bool, // событие есть в локальных таблицах DONKI
+    pub hours_since_previous: Option<f64>,
+    pub hours_since_start: Option<f64>,
+}
+
+// Причинная цепочка события для /donki/chain/:activity_id
+#[derive(Serialize, Clone, Debug)]
+pub struct DonkiChain {
+    pub activity_id: String,
+    pub stages: Vec<DonkiChainStage>,
+    pub links: Vec<DonkiLink>,
+    pub total_hours: Option<f64>,
+}
+
+// Значение Kp (DONKI GST allKpIndex или NOAA SWPC)
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct KpReading {
+    pub observed_at: DateTime<Utc>,
+    pub kp: f64,
+    pub source: String,
+}
+
+// Значение планетарного Kp NOAA SWPC (как хранится в noaa_kp)
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct NoaaKp {
+    pub time_tag: DateTime<Utc>,
+    pub kp: f64,
+    pub a_running: Option<f64>,
+    pub station_count: Option<i32>,
+    pub status: String,             // observed | estimated | predicted
+    pub noaa_scale: Option<String>, // G1..G5 (только в прогнозе)
+}
+
+impl NoaaKp {
+    /// Строка продукта noaa-planetary-k-index(-forecast); без поля observed — наблюдение
+    pub fn from_raw(raw: &serde_json::Value) -> Option<Self> {
+        use crate::utils::json_tools::{num, s_pick, t_pick};
+
+        Some(Self {
+            time_tag: t_pick(raw, &["time_tag"])?,
+            kp: num(&raw["kp"]).or_else(|| num(&raw["Kp"]))?,
+            a_running: num(&raw["a_running"]),
+            station_count: num(&raw["station_count"]).map(|n| n as i32),
+            status: s_pick(raw, &["observed"]).unwrap_or_else(|| "observed".to_string()),
+            noaa_scale: s_pick(raw, &["noaa_scale"]),
+        })
+    }
+}
+
+// Точка солнечного ветра NOAA SWPC (как хранится в noaa_solar_wind):
+// плазма и магнитное поле приходят разными продуктами и сливаются по time_tag
+#[derive(sqlx::FromRow, Serialize, Clone, Debug, Default)]
+pub struct SolarWindSample {
+    pub time_tag: DateTime<Utc>,
+    pub density: Option<f64>,     // протоны, см^-3
+    pub speed: Option<f64>,       // км/с
+    pub temperature: Option<f64>, // К
+    pub bx_gsm: Option<f64>,      // нТл
+    pub by_gsm: Option<f64>,
+    pub bz_gsm: Option<f64>,
+    pub bt: Option<f64>,
+}
+
+impl SolarWindSample {
+    /// Строка plasma-1-day или mag-1-day: заполняются только присутствующие поля
+    pub fn from_raw(raw: &serde_json::Value) -> Option<Self> {
+        use crate::utils::json_tools::{num, t_pick};
+
+        Some(Self {
+            time_tag: t_pick(raw, &["time_tag"])?,
+            density: num(&raw["density"]),
+            speed: num(&raw["speed"]),
+            temperature: num(&raw["temperature"]),
+            bx_gsm: num(&raw["bx_gsm"]),
+            by_gsm: num(&raw["by_gsm"]),
+            bz_gsm: num(&raw["bz_gsm"]),
+            bt: num(&raw["bt"]),
+        })
+    }
+}
+
+// Уровни активности /space-weather/status (индекс — level_code)
+pub const SPACE_WEATHER_LEVELS: [&str; 5] = ["quiet", "low", "moderate", "high", "severe"];
+
+// Текущая активность космической погоды по данным DONKI (и NOAA, если есть)
+#[derive(Serialize, Clone, Debug)]
+pub struct SpaceWeatherStatus {
+    pub level: String,
+    pub level_code: usize,
+    pub explanation: Vec<String>,
+    pub flares_by_class: Vec<StatBucket>,  // за окно вспышек
+    pub strongest_flare: Option<String>,
+    pub earth_directed_cmes: Vec<DonkiCme>,
+    pub kp_max: Option<f64>,
+    pub kp: Vec<KpReading>,
+    pub computed_at: DateTime<Utc>,
+}
+
+// Точка месячного ряда космической погоды: месяц, корзина (класс вспышки / скорость CME), число
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct SpaceWeatherMonthly {
+    pub month: DateTime<Utc>,
+    pub bucket: String,
+    pub count: i64,
+}
+
+// Статистика /space-weather/stats
+#[derive(Serialize, Clone, Debug)]
+pub struct SpaceWeatherStats {
+    pub months: i64,
+    pub flares_by_class: Vec<SpaceWeatherMonthly>,
+    pub cmes_by_speed: Vec<SpaceWeatherMonthly>,
+    pub computed_at: DateTime<Utc>,
+}
+
+// Ракета-носитель (как хранится в launch_rockets)
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct LaunchRocket {
+    pub rocket_id: String,
+    pub provider: String,
+    pub name: String,
+    pub rocket_type: Option<String>,
+    pub active: Option<bool>,
+    pub success_rate_pct: Option<f64>,
+}
+
+impl LaunchRocket {
+    /// Ракета SpaceX v4 (/rockets)
+    pub fn from_spacex(raw: &serde_json::Value) -> Option<Self> {
+        use crate::utils::json_tools::{num, s_pick};
+
+        Some(Self {
+            rocket_id: s_pick(raw, &["id"])?,
+            provider: "spacex".to_string(),
+            name: s_pick(raw, &["name"])?,
+            rocket_type: s_pick(raw, &["type"]),
+            active: raw["active"].as_bool(),
+            success_rate_pct: num(&raw["success_rate_pct"]),
+        })
+    }
+
+    /// Конфигурация ракеты Launch Library 2 (launch.rocket.configuration); id с префиксом ll2:
+    pub fn from_ll2(raw: &serde_json::Value) -> Option<Self> {
+        use crate::utils::json_tools::s_pick;
+
+        Some(Self {
+            rocket_id: format!("ll2:{}", s_pick(raw, &["id"])?),
+            provider: "ll2".to_string(),
+            name: s_pick(raw, &["full_name", "name"])?,
+            rocket_type: s_pick(raw, &["family"]),
+            active: None,
+            success_rate_pct: None,
+        })
+    }
+}
+
+// Стартовая площадка (как хранится в launch_pads)
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct LaunchPad {
+    pub pad_id: String,
+    pub provider: String,
+    pub name: String,
+    pub full_name: Option<String>,
+    pub locality: Option<String>,
+    pub region: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub status: Option<String>,
+}
+
+impl LaunchPad {
+    /// Площадка SpaceX v4 (/launchpads)
+    pub fn from_spacex(raw: &serde_json::Value) -> Option<Self> {
+        use crate::utils::json_tools::{num, s_pick};
+
+        Some(Self {
+            pad_id: s_pick(raw, &["id"])?,
+            provider: "spacex".to_string(),
+            name: s_pick(raw, &["name"])?,
+            full_name: s_pick(raw, &["full_name"]),
+            locality: s_pick(raw, &["locality"]),
+            region: s_pick(raw, &["region"]),
+            latitude: num(&raw["latitude"]),
+            longitude: num(&raw["longitude"]),
+            status: s_pick(raw, &["status"]),
+        })
+    }
+
+    /// Площадка Launch Library 2 (launch.pad); id с префиксом ll2:, регион — код страны
+    pub fn from_ll2(raw: &serde_json::Value) -> Option<Self> {
+        use crate::utils::json_tools::{num, s_pick, s_pick_any};
+
+        Some(Self {
+            pad_id: format!("ll2:{}", s_pick(raw, &["id"])?),
+            provider: "ll2".to_string(),
+            name: s_pick(raw, &["name"])?,
+            full_name: None,
+            locality: s_pick_any(raw, &["/location/name"]),
+            region: s_pick_any(raw, &["/location/country_code", "country_code"]),
+            latitude: num(&raw["latitude"]),
+            longitude: num(&raw["longitude"]),
+            status: None,
+        })
+    }
+}
+
+// Изменение пуска: status (смена статуса), net_shift (перенос даты), scrub (перенос после наступившей даты)
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct LaunchChange {
+    pub kind: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
+// Пуск (launches) с разрешенными ракетой и площадкой.
+// status: upcoming | tbd | hold | in_flight | success | partial_failure | failure | unknown.
+// sources — провайдеры, из которых собран пуск: [{provider, launch_id, updated_at}]
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct Launch {
+    pub launch_id: String,
+    pub provider: String,
+    pub name: String,
+    pub agency: Option<String>,
+    pub mission_name: Option<String>,
+    pub mission_type: Option<String>,
+    pub orbit: Option<String>,
+    pub flight_number: Option<i32>,
+    pub net: Option<DateTime<Utc>>,     // No Earlier Than
+    pub net_precision: Option<String>,  // hour | day | month | quarter | half | year
+    pub status: String,
+    pub upcoming: bool,
+    pub rocket_id: Option<String>,
+    pub rocket_name: Option<String>,
+    pub pad_id: Option<String>,
+    pub pad_name: Option<String>,
+    pub pad_locality: Option<String>,
+    pub pad_latitude: Option<f64>,
+    pub pad_longitude: Option<f64>,
+    pub details: Option<String>,
+    pub webcast_url: Option<String>,
+    pub patch_url: Option<String>,
+    pub image_url: Option<String>,
+    pub sources: Option<serde_json::Value>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub calendar_uid: Option<String>,   // id, под которым пуск впервые опубликован (UID в календаре)
+    pub revision: i32,                  // растет с каждым изменением пуска и слиянием (SEQUENCE в календаре)
+    #[sqlx(skip)]
+    pub changes: Vec<LaunchChange>,
+}
+
+impl Launch {
+    /// Пуск SpaceX v4 (/launches); ракета и площадка — только id, имена подставляет выборка
+    pub fn from_spacex(raw: &serde_json::Value) -> Option<Self> {
+        use crate::utils::json_tools::{num, s_pick, s_pick_any, t_pick};
+
+        let upcoming = raw["upcoming"].as_bool().unwrap_or(false);
+        let status = match (upcoming, raw["success"].as_bool()) {
+            (true, _) if raw["tbd"].as_bool() == Some(true) => "tbd",
+            (true, _) => "upcoming",
+            (false, Some(true)) => "success",
+            (false, Some(false)) => "failure",
+            (false, None) => "unknown",
+        };
+        Some(Self {
+            launch_id: s_pick(raw, &["id"])?,
+            provider: "spacex".to_string(),
+            name: s_pick(raw, &["name"])?,
+            agency: Some("SpaceX".to_string()),
+            mission_name: None,
+            mission_type: None,
+            orbit: None,
+            flight_number: num(&raw["flight_number"]).map(|n| n as i32),
+            net: t_pick(raw, &["date_utc"]),
+            net_precision: s_pick(raw, &["date_precision"]),
+            status: status.to_string(),
+            upcoming,
+            rocket_id: s_pick(raw, &["rocket"]),
+            rocket_name: None,
+            pad_id: s_pick(raw, &["launchpad"]),
+            pad_name: None,
+            pad_locality: None,
+            pad_latitude: None,
+            pad_longitude: None,
+            details: s_pick(raw, &["details"]),
+            webcast_url: s_pick_any(raw, &["/links/webcast"]),
+            patch_url: s_pick_any(raw, &["/links/patch/small", "/links/patch/large"]),
+            image_url: None,
+            sources: None,
+            updated_at: None,
+            calendar_uid: None,
+            revision: 0,
+            changes: vec![],
+        })
+    }
+
+    /// Пуск Launch Library 2 (/launch/upcoming, /launch/previous); ракета и площадка — id с префиксом ll2:
+    pub fn from_ll2(raw: &serde_json::Value) -> Option<Self> {
+        use crate::utils::json_tools::{s_pick, s_pick_any, t_pick};
+
+        let status = match s_pick_any(raw, &["/status/abbrev"]).as_deref() {
+            Some("Go") => "upcoming",
+            Some("TBD") | Some("TBC") => "tbd",
+            Some("Hold") => "hold",
+            Some("In Flight") => "in_flight",
+            Some("Success") => "success",
+            Some("Partial Failure") => "partial_failure",
+            Some("Failure") => "failure",
+            _ => "unknown",
+        };
+        Some(Self {
+            launch_id: s_pick(raw, &["id"])?,
+            provider: "ll2".to_string(),
+            name: s_pick(raw, &["name"])?,
+            agency: s_pick_any(raw, &["/launch_service_provider/name"]),
+            mission_name: s_pick_any(raw, &["/mission/name"]),
+            mission_type: s_pick_any(raw, &["/mission/type"]),
+            orbit: s_pick_any(raw, &["/mission/orbit/name"]),
+            flight_number: None,
+            net: t_pick(raw, &["net"]),
+            net_precision: s_pick_any(raw, &["/net_precision/name"]).map(|p| p.to_lowercase()),
+            status: status.to_string(),
+            upcoming: matches!(status, "upcoming" | "tbd" | "hold"),
+            rocket_id: s_pick_any(raw, &["/rocket/configuration/id"]).map(|id| format!("ll2:{}", id)),
+            rocket_name: None,
+            pad_id: s_pick_any(raw, &["/pad/id"]).map(|id| format!("ll2:{}", id)),
+            pad_name: None,
+            pad_locality: None,
+            pad_latitude: None,
+            pad_longitude: None,
+            details: s_pick_any(raw, &["/mission/description"]),
+            webcast_url: s_pick_any(raw, &["/vidURLs/0/url"]),
+            patch_url: s_pick_any(raw, &["/mission_patches/0/image_url"]),
+            image_url: s_pick(raw, &["image"]),
+            sources: None,
+            updated_at: None,
+            calendar_uid: None,
+            revision: 0,
+            changes: vec![],
+        })
+    }
+}
+
+// Итог сохранения пусков: записано и обнаружено изменений (статус, перенос, скраб)
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct LaunchSyncStats {
+    pub written: u64,
+    pub changes: u64,
+}
+
+// Счетчики NEO для дашборда
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct NeoCounts {
+    pub objects: i64,
+    pub hazardous: i64,
+    pub upcoming: i64,           // сближения с Землей в ближайшие 7 дней
+    pub hazardous_upcoming: i64,
 }
 
 #[derive(Serialize)]
@@ -129,6 +1419,65 @@ pub trait ToSuccessResponse: Sized + Serialize {
     }
 }
 
+// Средние элементы орбиты МКС (CelesTrak GP, формат OMM), как хранятся в iss_elements
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct IssElements {
+    pub epoch: DateTime<Utc>,
+    pub mean_motion: f64,      // оборотов в сутки
+    pub eccentricity: f64,
+    pub inclination_deg: f64,
+    pub raan_deg: f64,         // долгота восходящего узла
+    pub arg_perigee_deg: f64,
+    pub mean_anomaly_deg: f64,
+    pub mean_motion_dot: f64,  // ṅ/2, оборотов в сутки²
+    pub rev_at_epoch: i64,
+}
+
+impl IssElements {
+    pub fn from_omm(raw: &serde_json::Value) -> Option<Self> {
+        use crate::utils::json_tools::{num, t_pick};
+
+        Some(Self {
+            epoch: t_pick(raw, &["EPOCH"])?,
+            mean_motion: num(&raw["MEAN_MOTION"])?,
+            eccentricity: num(&raw["ECCENTRICITY"])?,
+            inclination_deg: num(&raw["INCLINATION"])?,
+            raan_deg: num(&raw["RA_OF_ASC_NODE"])?,
+            arg_perigee_deg: num(&raw["ARG_OF_PERICENTER"])?,
+            mean_anomaly_deg: num(&raw["MEAN_ANOMALY"])?,
+            mean_motion_dot: num(&raw["MEAN_MOTION_DOT"]).unwrap_or(0.0),
+            rev_at_epoch: num(&raw["REV_AT_EPOCH"]).map(|n| n as i64).unwrap_or(0),
+        })
+    }
+}
+
+// Видимый пролет МКС над точкой наблюдения (как хранится в iss_passes).
+// start/end — видимая часть: МКС выше min_elevation, освещена Солнцем, у наблюдателя сумерки.
+#[derive(sqlx::FromRow, Serialize, Clone, Debug)]
+pub struct IssPass {
+    pub rev: i64,                  // номер витка — вместе с точкой наблюдения дает стабильный UID
+    pub start_at: DateTime<Utc>,
+    pub max_at: DateTime<Utc>,
+    pub end_at: DateTime<Utc>,
+    pub start_elevation: f64,      // градусы
+    pub max_elevation: f64,
+    pub end_elevation: f64,
+    pub start_azimuth: f64,
+    pub max_azimuth: f64,
+    pub end_azimuth: f64,
+    pub revision: i32,             // растет при пересчете по новым элементам орбиты
+}
+
+// Прогноз пролетов для /iss/passes и календаря
+#[derive(Serialize, Clone, Debug)]
+pub struct IssPassForecast {
+    pub lat: f64,
+    pub lon: f64,
+    pub elements_epoch: DateTime<Utc>,
+    pub computed_at: DateTime<Utc>,
+    pub passes: Vec<IssPass>,
+}
+
 // Структура для позиции МКС
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct IssPosition {
@@ -142,4 +1491,95 @@ impl TryFrom<serde_json::Value> for IssPosition {
     fn try_from(value: serde_json::Value) -> Result<Self, Self::Error> {
         serde_json::from_value(value)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules() -> NeoAlertRules {
+        NeoAlertRules { max_lunar: 5.0, min_diameter_m: 140.0, hazardous: true, horizon_days: 7 }
+    }
+
+    fn approach(lunar: Option<f64>, diameter_max_m: Option<f64>, is_hazardous: bool) -> NeoApproachView {
+        let approach_at = Utc::now();
+        NeoApproachView {
+            neo_id: "3542519".to_string(),
+            name: Some("(2010 PK9)".to_string()),
+            is_hazardous,
+            diameter_min_m: diameter_max_m.map(|d| d / 2.0),
+            diameter_max_m,
+            detail_url: "/api/neo/3542519".to_string(),
+            approach: NeoApproach {
+                approach_at,
+                approach_date: approach_at.date_naive(),
+                miss_distance_km: lunar.map(|l| l * 384_400.0),
+                miss_distance_lunar: lunar,
+                velocity_kms: Some(12.3),
+                orbiting_body: "Earth".to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn no_rule_no_alert() {
+        assert!(rules().evaluate(&approach(Some(40.0), Some(50.0), false)).is_none());
+    }
+
+    #[test]
+    fn severity_grows_with_rules() {
+        let (severity, reasons) = rules().evaluate(&approach(Some(2.0), Some(50.0), false)).unwrap();
+        assert_eq!((severity, reasons.len()), ("info", 1));
+        let (severity, reasons) = rules().evaluate(&approach(Some(40.0), Some(300.0), false)).unwrap();
+        assert_eq!((severity, reasons.len()), ("info", 1));
+        let (severity, reasons) = rules().evaluate(&approach(Some(2.0), Some(300.0), false)).unwrap();
+        assert_eq!((severity, reasons.len()), ("warning", 2));
+        let (severity, reasons) = rules().evaluate(&approach(Some(2.0), Some(300.0), true)).unwrap();
+        assert_eq!((severity, reasons.len()), ("critical", 3));
+    }
+
+    #[test]
+    fn lone_pha_flag_is_warning() {
+        let (severity, reasons) = rules().evaluate(&approach(Some(40.0), Some(50.0), true)).unwrap();
+        assert_eq!(severity, "warning");
+        assert_eq!(reasons, vec!["potentially hazardous asteroid".to_string()]);
+
+        // Правило PHA выключено — флаг не учитывается
+        let off = NeoAlertRules { hazardous: false, ..rules() };
+        assert!(off.evaluate(&approach(Some(40.0), Some(50.0), true)).is_none());
+    }
+
+    #[test]
+    fn retention_formats() {
+        let rule = |max_age_hours, max_rows| Some(CacheRetention { max_age_hours, max_rows });
+        assert_eq!(CacheRetention::parse("30d"), rule(Some(720), None));
+        assert_eq!(CacheRetention::parse("72h"), rule(Some(72), None));
+        assert_eq!(CacheRetention::parse("200"), rule(None, Some(200)));
+        assert_eq!(CacheRetention::parse("7d+500"), rule(Some(168), Some(500)));
+        assert_eq!(CacheRetention::parse(" 500 + 48h "), rule(Some(48), Some(500)));
+    }
+
+    #[test]
+    fn retention_rejects_invalid_parts() {
+        for spec in ["", "d", "0d", "-5h", "0", "30x", "7d+", "7d+abc", "1.5d"] {
+            assert_eq!(CacheRetention::parse(spec), None, "{:?}", spec);
+        }
+    }
+
+    #[test]
+    fn retention_rejects_out_of_range_age() {
+        assert_eq!(CacheRetention::parse("999999999999999d"), None);
+        assert_eq!(CacheRetention::parse(&format!("{}d", i64::MAX)), None);
+        assert_eq!(CacheRetention::parse(&format!("{}h", CacheRetention::MAX_AGE_HOURS + 1)), None);
+        let max = CacheRetention::parse(&format!("{}h", CacheRetention::MAX_AGE_HOURS)).unwrap();
+        assert_eq!(max.max_age_hours, Some(CacheRetention::MAX_AGE_HOURS));
+        assert!(chrono::Duration::try_hours(CacheRetention::MAX_AGE_HOURS).is_some());
+    }
+
+    #[test]
+    fn unknown_miss_distance_does_not_trigger() {
+        assert!(rules().evaluate(&approach(None, None, false)).is_none());
+        let (severity, reasons) = rules().evaluate(&approach(None, Some(300.0), false)).unwrap();
+        assert_eq!(severity, "info");
+        assert!(reasons[0].starts_with("diameter"));
+    }
+}
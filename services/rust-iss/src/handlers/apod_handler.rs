@@ -0,0 +1,118 @@
+use axum::{extract::{Path, Query, State}, Json};
+use axum::body::Body;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Redirect, Response};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio_util::io::ReaderStream;
+use tracing::{error, info};
+use crate::domain::models::{ApodEntry, ApodImage, ApodSearchFilter, AppState};
+use crate::domain::errors::AppError;
+
+#[derive(Deserialize)]
+pub struct ApodQuery {
+    pub date: Option<NaiveDate>,
+    pub start: Option<NaiveDate>,
+    pub end: Option<NaiveDate>,
+}
+
+// /space/apod?date= — одна дата, /space/apod?start=&end= — диапазон (end по умолчанию сегодня)
+pub async fn apod(
+    Query(q): Query<ApodQuery>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    match (q.date, q.start) {
+        (Some(date), _) => {
+            let entry = st.space_service.get_apod(date).await?;
+            Ok(Json(json!({ "entry": entry })))
+        }
+        (None, Some(start)) => {
+            let end = q.end.unwrap_or_else(ApodEntry::latest_date);
+            let entries = st.space_service.get_apod_range(start, end).await?;
+            Ok(Json(json!({ "start": start, "end": end, "count": entries.len(), "entries": entries })))
+        }
+        (None, None) => Err(AppError::ClientError(
+            "Either date or start (with optional end) is required".to_string(),
+            StatusCode::BAD_REQUEST,
+        )),
+    }
+}
+
+// Полнотекстовый поиск: ?q=&start=&end=&media_type=&limit=
+pub async fn apod_search(
+    Query(filter): Query<ApodSearchFilter>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let hits = st.space_service.search_apod(&filter).await?;
+    Ok(Json(json!({ "q": filter.q, "count": hits.len(), "results": hits })))
+}
+
+#[derive(Deserialize)]
+pub struct ApodBackfillQuery {
+    pub start: NaiveDate,
+    pub end: Option<NaiveDate>,
+}
+
+// Догрузка архива запускается фоном: большой диапазон — десятки запросов к upstream
+pub async fn apod_backfill(
+    Query(q): Query<ApodBackfillQuery>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let end = q.end.unwrap_or_else(ApodEntry::latest_date);
+    let service = st.space_service.clone();
+    let start = q.start;
+    tokio::spawn(async move {
+        match service.backfill_apod(start, end).await {
+            Ok(n) => info!("APOD backfill {}..{} done: {} entries.", start, end, n),
+            Err(e) => error!("APOD backfill {}..{} failed: {:?}", start, end, e),
+        }
+    });
+    Ok(Json(json!({ "started": true, "start": q.start, "end": end })))
+}
+
+#[derive(Deserialize)]
+pub struct ApodImageQuery {
+    pub size: Option<String>,
+}
+
+// Локальная копия изображения APOD (thumb | medium | original); видео — редирект на оригинал
+pub async fn apod_image(
+    Path(date): Path<NaiveDate>,
+    Query(q): Query<ApodImageQuery>,
+    headers: HeaderMap,
+    State(st): State<AppState>
+) -> Result<Response, AppError> {
+    let size = q.size.as_deref().unwrap_or("medium");
+    let (path, content_type, etag, updated_at) = match st.space_service.get_apod_image(date, size).await? {
+        ApodImage::Redirect(url) => return Ok(Redirect::temporary(&url).into_response()),
+        ApodImage::File { path, content_type, etag, updated_at } => (path, content_type, etag, updated_at),
+    };
+
+    // Файлы за дату неизменны: долгий кэш и ответ 304 по ETag
+    let cache_control = "public, max-age=86400";
+    let last_modified = updated_at.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+    let not_modified = headers.get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.split(',').any(|t| t.trim() == etag));
+    if not_modified {
+        return Ok((
+            StatusCode::NOT_MODIFIED,
+            [(header::ETAG, etag), (header::CACHE_CONTROL, cache_control.to_string())],
+        ).into_response());
+    }
+
+    let file = tokio::fs::File::open(&path).await?;
+    let len = file.metadata().await?.len();
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type),
+            (header::CONTENT_LENGTH, len.to_string()),
+            (header::CACHE_CONTROL, cache_control.to_string()),
+            (header::ETAG, etag),
+            (header::LAST_MODIFIED, last_modified),
+        ],
+        Body::from_stream(ReaderStream::new(file)),
+    ).into_response())
+}
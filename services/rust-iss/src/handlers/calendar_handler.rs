@@ -0,0 +1,178 @@
+use axum::{
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use crate::domain::models::{AppState, IssPass, IssPassForecast, Launch};
+use crate::domain::errors::AppError;
+use crate::utils::ical::{self, IcsEvent, IcsTime};
+use crate::utils::orbit::compass_point;
+
+/// Сколько предстоящих пусков попадает в календарь
+const CALENDAR_LAUNCH_LIMIT: i64 = 200;
+/// Длительность события пуска с точным временем (минуты)
+const LAUNCH_EVENT_MINUTES: i64 = 30;
+/// Напоминания по умолчанию (минуты до начала)
+const LAUNCH_ALARM_MINUTES: i64 = 60;
+const ISS_PASS_ALARM_MINUTES: i64 = 10;
+
+#[derive(Deserialize)]
+pub struct LaunchCalendarQuery {
+    pub alarm: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct IssPassCalendarQuery {
+    pub lat: f64,
+    pub lon: f64,
+    pub days: Option<i64>,
+    pub alarm: Option<i64>,
+}
+
+/// alarm=0 отключает напоминание; не больше суток
+fn alarm_minutes(alarm: Option<i64>, default: i64) -> Result<Option<i64>, AppError> {
+    match alarm.unwrap_or(default) {
+        0 => Ok(None),
+        m @ 1..=1440 => Ok(Some(m)),
+        other => Err(AppError::ClientError(
+            format!("alarm must be within 0..=1440 minutes, got {}", other),
+            StatusCode::BAD_REQUEST,
+        )),
+    }
+}
+
+fn ics_response(file_name: &str, body: String) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, "text/calendar; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, format!("inline; filename=\"{}\"", file_name)),
+        ],
+        body,
+    ).into_response()
+}
+
+// Событие пуска: точное время для NET с точностью до часа, весь день — для точности до дня.
+// Более грубые даты (месяц, квартал) в календарь не попадают.
+fn launch_event(l: &Launch, alarm: Option<i64>) -> Option<IcsEvent> {
+    let net = l.net?;
+    let (start, end, alarm) = match l.net_precision.as_deref() {
+        None | Some("second") | Some("minute") | Some("hour") => {
+            (IcsTime::At(net), IcsTime::At(net + Duration::minutes(LAUNCH_EVENT_MINUTES)), alarm)
+        }
+        Some("day") => {
+            let day = net.date_naive();
+            (IcsTime::Date(day), IcsTime::Date(day + Duration::days(1)), None)
+        }
+        Some(_) => return None,
+    };
+
+    let location = match (&l.pad_name, &l.pad_locality) {
+        (Some(name), Some(locality)) => Some(format!("{}, {}", name, locality)),
+        (Some(name), None) => Some(name.clone()),
+        (None, locality) => locality.clone(),
+    };
+
+    let mut description = Vec::new();
+    if let Some(mission) = &l.mission_name {
+        description.push(format!("Mission: {}", mission));
+    }
+    if let Some(agency) = &l.agency {
+        description.push(format!("Provider: {}", agency));
+    }
+    if let Some(rocket) = &l.rocket_name {
+        description.push(format!("Rocket: {}", rocket));
+    }
+    if let Some(orbit) = &l.orbit {
+        description.push(format!("Orbit: {}", orbit));
+    }
+    description.push(format!("Status: {}", l.status));
+    if let Some(precision) = &l.net_precision {
+        description.push(format!("NET precision: {}", precision));
+    }
+    if let Some(details) = &l.details {
+        description.push(String::new());
+        description.push(details.clone());
+    }
+    if let Some(webcast) = &l.webcast_url {
+        description.push(format!("Webcast: {}", webcast));
+    }
+    if let Some(sources) = l.sources.as_ref().and_then(|s| s.as_array()) {
+        let providers: Vec<&str> = sources.iter().filter_map(|s| s["provider"].as_str()).collect();
+        description.push(format!("Sources: {}", providers.join(", ")));
+    }
+
+    Some(IcsEvent {
+        uid: format!("launch-{}@rust-iss", l.calendar_uid.as_deref().unwrap_or(&l.launch_id)),
+        sequence: l.revision,
+        stamp: l.updated_at.unwrap_or_else(Utc::now),
+        start,
+        end,
+        summary: format!("Launch: {}", l.name),
+        description: description.join("\n"),
+        location,
+        geo: l.pad_latitude.zip(l.pad_longitude),
+        url: l.webcast_url.clone(),
+        tentative: matches!(l.status.as_str(), "tbd" | "hold"),
+        alarm_minutes: alarm,
+    })
+}
+
+fn pass_event(f: &IssPassForecast, p: &IssPass, alarm: Option<i64>) -> IcsEvent {
+    let (lat_key, lon_key) = ((f.lat * 100.0).round() as i32, (f.lon * 100.0).round() as i32);
+    let point = |t: &chrono::DateTime<Utc>, elevation: f64, azimuth: f64| {
+        format!("{}, {:.0}° above {} ({:.0}°)", t.format("%H:%M:%S UTC"), elevation, compass_point(azimuth), azimuth)
+    };
+    let duration = p.end_at - p.start_at;
+    let description = [
+        format!("Appears: {}", point(&p.start_at, p.start_elevation, p.start_azimuth)),
+        format!("Highest: {}", point(&p.max_at, p.max_elevation, p.max_azimuth)),
+        format!("Disappears: {}", point(&p.end_at, p.end_elevation, p.end_azimuth)),
+        format!("Visible for {} min {} s", duration.num_minutes(), duration.num_seconds() % 60),
+        format!("Orbit {}, elements epoch {}", p.rev, f.elements_epoch.format("%Y-%m-%d %H:%M UTC")),
+    ];
+
+    IcsEvent {
+        uid: format!("iss-pass-{}-{}-{}@rust-iss", p.rev, lat_key, lon_key),
+        sequence: p.revision,
+        stamp: f.computed_at,
+        start: IcsTime::At(p.start_at),
+        end: IcsTime::At(p.end_at),
+        summary: format!(
+            "ISS pass: max {:.0}° {} → {}",
+            p.max_elevation,
+            compass_point(p.start_azimuth),
+            compass_point(p.end_azimuth)
+        ),
+        description: description.join("\n"),
+        location: Some(format!("{:.2}, {:.2}", f.lat, f.lon)),
+        geo: Some((f.lat, f.lon)),
+        url: None,
+        tentative: false,
+        alarm_minutes: alarm,
+    }
+}
+
+// Предстоящие пуски как календарь iCalendar
+pub async fn launches_ics(
+    Query(q): Query<LaunchCalendarQuery>,
+    State(st): State<AppState>
+) -> Result<Response, AppError> {
+    let alarm = alarm_minutes(q.alarm, LAUNCH_ALARM_MINUTES)?;
+    let launches = st.space_service.get_launches(true, CALENDAR_LAUNCH_LIMIT).await?;
+    let events: Vec<IcsEvent> = launches.iter().filter_map(|l| launch_event(l, alarm)).collect();
+    Ok(ics_response("launches.ics", ical::calendar("Space launches", &events)))
+}
+
+// Видимые пролеты МКС над точкой как календарь iCalendar
+pub async fn iss_passes_ics(
+    Query(q): Query<IssPassCalendarQuery>,
+    State(st): State<AppState>
+) -> Result<Response, AppError> {
+    let alarm = alarm_minutes(q.alarm, ISS_PASS_ALARM_MINUTES)?;
+    let forecast = st.space_service.get_iss_passes(q.lat, q.lon, q.days.unwrap_or(7)).await?;
+    let events: Vec<IcsEvent> = forecast.passes.iter().map(|p| pass_event(&forecast, p, alarm)).collect();
+    let name = format!("ISS passes {:.2}, {:.2}", forecast.lat, forecast.lon);
+    Ok(ics_response("iss-passes.ics", ical::calendar(&name, &events)))
+}
@@ -0,0 +1,105 @@
+use axum::{extract::{Path, Query, State}, Json};
+use chrono::{Duration, NaiveDate, Utc};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use crate::domain::models::AppState;
+use crate::domain::errors::AppError;
+
+#[derive(Deserialize)]
+pub struct DonkiListQuery {
+    pub start: Option<NaiveDate>,
+    pub end: Option<NaiveDate>,
+    pub class: Option<String>,
+    pub min_speed: Option<f64>,
+    #[serde(rename = "type")]
+    pub message_type: Option<String>,
+    pub limit: Option<i64>,
+}
+
+impl DonkiListQuery {
+    /// Диапазон по умолчанию — последние 30 дней
+    fn range(&self) -> (NaiveDate, NaiveDate) {
+        let end = self.end.unwrap_or_else(|| Utc::now().date_naive());
+        (self.start.unwrap_or(end - Duration::days(29)), end)
+    }
+}
+
+// Солнечные вспышки (?start&end&class=X)
+pub async fn donki_flares(
+    Query(q): Query<DonkiListQuery>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let (start, end) = q.range();
+    let items = st.space_service
+        .get_donki_flares(start, end, q.class.as_deref(), q.limit.unwrap_or(200))
+        .await?;
+    Ok(Json(json!({ "start": start, "end": end, "count": items.len(), "items": items })))
+}
+
+// Корональные выбросы массы с анализами (?start&end&min_speed)
+pub async fn donki_cmes(
+    Query(q): Query<DonkiListQuery>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let (start, end) = q.range();
+    let items = st.space_service
+        .get_donki_cmes(start, end, q.min_speed, q.limit.unwrap_or(200))
+        .await?;
+    Ok(Json(json!({ "start": start, "end": end, "count": items.len(), "items": items })))
+}
+
+// События GST, SEP, IPS, HSS, RBE (/donki/gst?start&end)
+pub async fn donki_events(
+    Path(kind): Path<String>,
+    Query(q): Query<DonkiListQuery>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let (start, end) = q.range();
+    let items = st.space_service.get_donki_events(&kind, start, end, q.limit.unwrap_or(200)).await?;
+    Ok(Json(json!({ "type": kind, "start": start, "end": end, "count": items.len(), "items": items })))
+}
+
+// Уведомления прогнозистов (?start&end&type=FLR)
+pub async fn donki_notifications(
+    Query(q): Query<DonkiListQuery>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let (start, end) = q.range();
+    let items = st.space_service
+        .get_donki_notifications(start, end, q.message_type.as_deref(), q.limit.unwrap_or(200))
+        .await?;
+    Ok(Json(json!({ "start": start, "end": end, "count": items.len(), "items": items })))
+}
+
+// Причинная цепочка события по linkedEvents (вспышка -> CME -> геомагнитная буря)
+pub async fn donki_chain(
+    Path(activity_id): Path<String>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let chain = st.space_service.get_donki_chain(&activity_id).await?;
+    Ok(Json(json!({ "chain": chain })))
+}
+
+#[derive(Deserialize)]
+pub struct DonkiSyncQuery {
+    pub src: Option<String>,
+    pub start: Option<NaiveDate>,
+    pub end: Option<NaiveDate>,
+}
+
+// Загрузка событий за диапазон окнами по 30 дней (?src=flr,cme,gst,...&start&end)
+pub async fn donki_sync(
+    Query(q): Query<DonkiSyncQuery>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let end = q.end.unwrap_or_else(|| Utc::now().date_naive());
+    let start = q.start.unwrap_or(end - Duration::days(29));
+    let list = q.src.unwrap_or_else(|| "flr,cme".to_string());
+
+    let mut saved = serde_json::Map::new();
+    for kind in list.split(',').map(|s| s.trim().to_lowercase()).filter(|s| !s.is_empty()) {
+        let written = st.space_service.fetch_donki_range(&kind, start, end).await?;
+        saved.insert(kind, json!(written));
+    }
+    Ok(Json(json!({ "start": start, "end": end, "saved": saved })))
+}
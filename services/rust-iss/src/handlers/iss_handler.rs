@@ -1,4 +1,5 @@
-use axum::{extract::State, Json};
+use axum::{extract::{Query, State}, Json};
+use serde::Deserialize;
 use serde_json::{json, Value};
 use tracing::warn;
 use crate::domain::models::{AppState, Health, Trend};
@@ -35,6 +36,22 @@ pub async fn iss_trend(State(st): State<AppState>) -> Result<Json<Trend>, AppErr
     Ok(Json(trend))
 }
 
+#[derive(Deserialize)]
+pub struct IssPassQuery {
+    pub lat: f64,
+    pub lon: f64,
+    pub days: Option<i64>,
+}
+
+// Видимые пролеты МКС над точкой (lat, lon) на days суток вперед
+pub async fn iss_passes(
+    Query(q): Query<IssPassQuery>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let forecast = st.space_service.get_iss_passes(q.lat, q.lon, q.days.unwrap_or(7)).await?;
+    Ok(Json(json!({ "count": forecast.passes.len(), "forecast": forecast })))
+}
+
 // Универсальная витрина space_cache
 pub async fn space_latest(
     axum::extract::Path(src): axum::extract::Path<String>,
@@ -43,14 +60,15 @@ pub async fn space_latest(
     use sqlx::Row;
     
     let row = sqlx::query(
-        "SELECT fetched_at, payload FROM space_cache
+        "SELECT fetched_at, last_checked_at, payload FROM space_cache
          WHERE source = $1 ORDER BY id DESC LIMIT 1"
     ).bind(&src).fetch_optional(&st.pool).await?;
 
     if let Some(r) = row {
         let fetched_at: chrono::DateTime<Utc> = r.get("fetched_at");
+        let last_checked_at: chrono::DateTime<Utc> = r.get("last_checked_at");
         let payload: Value = r.get("payload");
-        return Ok(Json(json!({ "source": src, "fetched_at": fetched_at, "payload": payload })));
+        return Ok(Json(json!({ "source": src, "fetched_at": fetched_at, "last_checked_at": last_checked_at, "payload": payload })));
     }
     Ok(Json(json!({ "source": src, "message":"no data" })))
 }
@@ -59,7 +77,7 @@ pub async fn space_refresh(
     axum::extract::Query(q): axum::extract::Query<std::collections::HashMap<String,String>>,
     State(st): State<AppState>
 ) -> Result<Json<Value>, AppError> {
-    let list = q.get("src").cloned().unwrap_or_else(|| "apod,neo,flr,cme,spacex".to_string());
+    let list = q.get("src").cloned().unwrap_or_else(|| "apod,neo,flr,cme,noaa,spacex,ll2,tle".to_string());
     let sources_vec: Vec<String> = list.split(',')
         .map(|x| x.trim().to_lowercase())
         .filter(|x| !x.is_empty())
@@ -77,14 +95,26 @@ pub async fn space_refresh(
                 st.space_service.fetch_and_cache_neo().await?;
                 refreshed += 1;
             }
-            "flr" | "cme" => {
-                st.space_service.fetch_and_cache_donki().await?;
+            "flr" | "cme" | "gst" | "sep" | "ips" | "hss" | "rbe" | "notifications" => {
+                st.space_service.fetch_and_cache_donki_source(source).await?;
+                refreshed += 1;
+            }
+            "noaa" => {
+                st.space_service.fetch_and_cache_noaa().await?;
                 refreshed += 1;
             }
             "spacex" => {
                 st.space_service.fetch_and_cache_spacex().await?;
                 refreshed += 1;
             }
+            "ll2" => {
+                st.space_service.fetch_and_cache_ll2().await?;
+                refreshed += 1;
+            }
+            "tle" => {
+                st.space_service.fetch_and_store_iss_elements().await?;
+                refreshed += 1;
+            }
             _ => warn!("Unknown source: {}", source),
         }
     }
@@ -92,6 +122,13 @@ pub async fn space_refresh(
     Ok(Json(json!({ "refreshed": refreshed })))
 }
 
+// Внеплановое обслуживание space_cache по правилам хранения
+pub async fn space_cache_prune(State(st): State<AppState>) -> Result<Json<Value>, AppError> {
+    let reports = st.space_service.prune_space_cache().await?;
+    let removed: u64 = reports.iter().map(|r| r.removed_by_age + r.removed_by_count).sum();
+    Ok(Json(json!({ "removed": removed, "sources": reports })))
+}
+
 pub async fn space_summary(State(st): State<AppState>) -> Result<Json<Value>, AppError> {
     let osdr_count = st.osdr_repo.get_count().await.unwrap_or(0);
     
@@ -0,0 +1,37 @@
+use axum::{extract::{Path, Query, State}, Json};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use crate::domain::models::AppState;
+use crate::domain::errors::AppError;
+
+#[derive(Deserialize)]
+pub struct LaunchListQuery {
+    pub limit: Option<i64>,
+}
+
+// Предстоящие пуски, ближайшие первыми
+pub async fn launches_upcoming(
+    Query(q): Query<LaunchListQuery>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let items = st.space_service.get_launches(true, q.limit.unwrap_or(50)).await?;
+    Ok(Json(json!({ "count": items.len(), "items": items })))
+}
+
+// Прошедшие пуски, последние первыми
+pub async fn launches_past(
+    Query(q): Query<LaunchListQuery>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let items = st.space_service.get_launches(false, q.limit.unwrap_or(50)).await?;
+    Ok(Json(json!({ "count": items.len(), "items": items })))
+}
+
+// Пуск с ракетой, площадкой и журналом изменений
+pub async fn launch_detail(
+    Path(launch_id): Path<String>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let launch = st.space_service.get_launch(&launch_id).await?;
+    Ok(Json(json!({ "launch": launch })))
+}
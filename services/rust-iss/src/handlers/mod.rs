@@ -1,5 +1,11 @@
+pub mod apod_handler;
+pub mod calendar_handler;
+pub mod donki_handler;
 pub mod iss_handler;
+pub mod launch_handler;
+pub mod neo_handler;
 pub mod osdr_handler;
+pub mod space_weather_handler;
 
 pub use iss_handler::*;
 pub use osdr_handler::*;
\ No newline at end of file
@@ -0,0 +1,82 @@
+use axum::{extract::{Path, Query, State}, Json};
+use chrono::{NaiveDate, Utc};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use crate::domain::models::AppState;
+use crate::domain::errors::AppError;
+
+#[derive(Deserialize)]
+pub struct NeoUpcomingQuery {
+    pub days: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+// Сближения с Землей в ближайшие days дней (по умолчанию 7)
+pub async fn neo_upcoming(
+    Query(q): Query<NeoUpcomingQuery>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let days = q.days.unwrap_or(7);
+    let items = st.space_service.get_neo_upcoming(days, false, q.limit.unwrap_or(100)).await?;
+    Ok(Json(json!({ "days": days, "count": items.len(), "items": items })))
+}
+
+// То же, только потенциально опасные объекты
+pub async fn neo_hazardous(
+    Query(q): Query<NeoUpcomingQuery>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let days = q.days.unwrap_or(30);
+    let items = st.space_service.get_neo_upcoming(days, true, q.limit.unwrap_or(100)).await?;
+    Ok(Json(json!({ "days": days, "count": items.len(), "items": items })))
+}
+
+// Счетчики для дашборда
+pub async fn neo_count(State(st): State<AppState>) -> Result<Json<Value>, AppError> {
+    let counts = st.space_service.get_neo_counts().await?;
+    Ok(Json(json!({ "neo_total": counts.objects, "counts": counts })))
+}
+
+#[derive(Deserialize)]
+pub struct NeoAlertsQuery {
+    pub days: Option<i64>,
+    pub severity: Option<String>,
+    pub limit: Option<i64>,
+}
+
+// Тревоги по сближениям (правила NEO_ALERT_*), самые важные первыми
+pub async fn neo_alerts(
+    Query(q): Query<NeoAlertsQuery>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let days = q.days.unwrap_or(30);
+    let severity = q.severity.unwrap_or_else(|| "info".to_string());
+    let items = st.space_service.get_neo_alerts(days, &severity, q.limit.unwrap_or(100)).await?;
+    Ok(Json(json!({ "days": days, "severity": severity, "count": items.len(), "items": items })))
+}
+
+// Карточка объекта: орбита и все сближения (lookup кэшируется в neo_objects)
+pub async fn neo_detail(
+    Path(neo_id): Path<String>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let detail = st.space_service.get_neo_detail(&neo_id).await?;
+    Ok(Json(json!({ "neo": detail })))
+}
+
+#[derive(Deserialize)]
+pub struct NeoSyncQuery {
+    pub start: Option<NaiveDate>,
+    pub end: Option<NaiveDate>,
+}
+
+// Загрузка ленты за диапазон (окнами по 7 дней); по умолчанию — неделя с сегодняшнего дня
+pub async fn neo_sync(
+    Query(q): Query<NeoSyncQuery>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let start = q.start.unwrap_or_else(|| Utc::now().date_naive());
+    let end = q.end.unwrap_or(start + chrono::Duration::days(6));
+    let written = st.space_service.fetch_neo_range(start, end).await?;
+    Ok(Json(json!({ "start": start, "end": end, "objects": written })))
+}
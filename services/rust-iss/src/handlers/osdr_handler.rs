@@ -1,16 +1,31 @@
-use axum::{extract::State, Json};
+use axum::{extract::{Path, Query, State}, Json};
+use axum::body::Body;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
 use serde_json::{json, Value};
-use crate::domain::models::AppState;
+use tokio_util::io::ReaderStream;
+use tracing::error;
+use crate::domain::models::{AppState, OsdrGraph, OsdrMirrorFilter};
 use crate::domain::errors::AppError;
 //use crate::services::osdr_service;
 
 pub async fn osdr_sync(State(st): State<AppState>) -> Result<Json<Value>, AppError> {
-    let written = st.space_service.fetch_and_save_osdr_data().await?;
-    Ok(Json(json!({ "written": written })))
+    let stats = st.space_service.fetch_and_save_osdr_data().await?;
+    Ok(Json(json!({ "written": stats.items_written, "stats": stats })))
 }
 
-pub async fn osdr_list(State(st): State<AppState>) -> Result<Json<Value>, AppError> {
-    let items = st.space_service.get_osdr_list().await?;
+#[derive(Deserialize)]
+pub struct OsdrListQuery {
+    #[serde(default)]
+    pub include_withdrawn: bool,
+}
+
+pub async fn osdr_list(
+    Query(q): Query<OsdrListQuery>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let items = st.space_service.get_osdr_list(q.include_withdrawn).await?;
     let out: Vec<Value> = items.into_iter().map(|r| {
         json!({
             "id": r.id,
@@ -19,9 +34,322 @@ pub async fn osdr_list(State(st): State<AppState>) -> Result<Json<Value>, AppErr
             "status": r.status,
             "updated_at": r.updated_at,
             "inserted_at": r.inserted_at,
+            "last_seen_at": r.last_seen_at,
+            "withdrawn_at": r.withdrawn_at,
+            "withdrawn": r.withdrawn_at.is_some(),
+            "dataset": r.dataset,
             "raw": r.raw,
         })
     }).collect();
 
     Ok(Json(json!({ "items": out })))
-}
\ No newline at end of file
+}
+
+pub async fn osdr_detail(
+    Path(dataset_id): Path<String>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let d = st.space_service.get_osdr_detail(&dataset_id).await?;
+    let r = d.item;
+
+    Ok(Json(json!({
+        "id": r.id,
+        "dataset_id": r.dataset_id,
+        "title": r.title,
+        "status": r.status,
+        "updated_at": r.updated_at,
+        "inserted_at": r.inserted_at,
+        "last_seen_at": r.last_seen_at,
+        "withdrawn_at": r.withdrawn_at,
+        "withdrawn": r.withdrawn_at.is_some(),
+        "dataset": r.dataset,
+        "raw": r.raw,
+        "detail": d.detail,
+        "detail_fetched_at": d.detail_fetched_at,
+        "detail_stale": d.detail_stale,
+    })))
+}
+
+pub async fn osdr_crawl(State(st): State<AppState>) -> Result<Json<Value>, AppError> {
+    let state = st.space_service.crawl_osdr().await?;
+    Ok(Json(json!({ "crawl": state })))
+}
+
+pub async fn osdr_crawl_status(State(st): State<AppState>) -> Result<Json<Value>, AppError> {
+    match st.space_service.get_osdr_crawl_state().await? {
+        Some(state) => Ok(Json(json!({ "crawl": state }))),
+        None => Ok(Json(json!({ "crawl": null, "message": "no crawl yet" }))),
+    }
+}
+
+pub async fn osdr_sync_metrics(State(st): State<AppState>) -> Result<Json<Value>, AppError> {
+    let runs = st.space_service.get_osdr_sync_log(50).await?;
+
+    // Сколько работы сэкономлено условными запросами и хэшами содержимого
+    let pages: i64 = runs.iter().map(|r| r.stats.pages_fetched as i64).sum();
+    let pages_not_modified: i64 = runs.iter().map(|r| r.stats.pages_not_modified as i64).sum();
+    let items: i64 = runs.iter().map(|r| r.stats.items_fetched).sum();
+    let items_withdrawn: i64 = runs.iter().map(|r| r.stats.items_withdrawn).sum();
+    let items_restored: i64 = runs.iter().map(|r| r.stats.items_restored).sum();
+    let items_unchanged: i64 = runs.iter().map(|r| r.stats.items_unchanged).sum();
+    let items_written: i64 = runs.iter().map(|r| r.stats.items_written).sum();
+    let ratio = |part: i64, total: i64| if total > 0 { part as f64 / total as f64 } else { 0.0 };
+
+    Ok(Json(json!({
+        "runs": runs,
+        "totals": {
+            "pages_fetched": pages,
+            "pages_not_modified": pages_not_modified,
+            "items_fetched": items,
+            "items_unchanged": items_unchanged,
+            "items_written": items_written,
+            "items_withdrawn": items_withdrawn,
+            "items_restored": items_restored,
+        },
+        "avoided": {
+            "pages_ratio": ratio(pages_not_modified, pages),
+            "items_ratio": ratio(items_unchanged, items),
+        },
+    })))
+}
+
+pub async fn osdr_stats(State(st): State<AppState>) -> Result<Json<Value>, AppError> {
+    let stats = st.space_service.get_osdr_stats().await?;
+    Ok(Json(json!({ "stats": stats })))
+}
+
+// Зеркалирование запускается фоном: скачивание файлов может занять долго
+pub async fn osdr_mirror_dataset(
+    Path(dataset_id): Path<String>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let service = st.space_service.clone();
+    let id = dataset_id.clone();
+    tokio::spawn(async move {
+        if let Err(e) = service.mirror_osdr_dataset(&id).await {
+            error!("OSDR mirror of {} failed: {:?}", id, e);
+        }
+    });
+    Ok(Json(json!({ "started": true, "dataset_id": dataset_id })))
+}
+
+pub async fn osdr_mirror(
+    Query(filter): Query<OsdrMirrorFilter>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let service = st.space_service.clone();
+    let response = json!({ "started": true, "filter": { "q": filter.q, "status": filter.status, "limit": filter.limit } });
+    tokio::spawn(async move {
+        match service.mirror_osdr(&filter).await {
+            Ok(r) => tracing::info!("OSDR mirror by filter done: {} datasets, {} files.", r.datasets, r.files_complete),
+            Err(e) => error!("OSDR mirror by filter failed: {:?}", e),
+        }
+    });
+    Ok(Json(response))
+}
+
+pub async fn osdr_files(
+    Path(dataset_id): Path<String>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let files = st.space_service.get_osdr_files(&dataset_id).await?;
+    let out: Vec<Value> = files.into_iter().map(|f| {
+        let href = (f.state == "complete").then(|| file_href(&f.dataset_id, &f.file_name));
+        json!({ "file": f, "href": href })
+    }).collect();
+
+    Ok(Json(json!({ "dataset_id": dataset_id, "files": out })))
+}
+
+/// Ссылка на файл зеркала; сегменты пути кодируются (пробелы, '#', '?', '/' в именах)
+fn file_href(dataset_id: &str, file_name: &str) -> String {
+    let mut url = reqwest::Url::parse("http://localhost/").expect("static base URL");
+    if let Ok(mut segments) = url.path_segments_mut() {
+        segments.extend(["osdr", dataset_id, "files", file_name]);
+    }
+    url.path().to_string()
+}
+
+pub async fn osdr_file_download(
+    Path((dataset_id, file_name)): Path<(String, String)>,
+    State(st): State<AppState>
+) -> Result<Response, AppError> {
+    let f = st.space_service.get_osdr_file(&dataset_id, &file_name).await?;
+    let path = f.local_path.unwrap_or_default();
+    let file = tokio::fs::File::open(&path).await?;
+    let len = file.metadata().await?.len();
+
+    // Имя в Content-Disposition — только печатный ASCII
+    let disposition_name = f.file_name.replace(|c: char| !(c.is_ascii_graphic() || c == ' ') || c == '"', "_");
+    let etag = f.sha256.map(|sha| format!("\"{}\"", sha)).unwrap_or_default();
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/octet-stream".to_string()),
+            (header::CONTENT_LENGTH, len.to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", disposition_name)),
+            (header::ETAG, etag),
+        ],
+        Body::from_stream(ReaderStream::new(file)),
+    ).into_response())
+}
+
+pub async fn osdr_isa(
+    Path(dataset_id): Path<String>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let isa = st.space_service.get_osdr_isa(&dataset_id).await?;
+    Ok(Json(json!({ "dataset_id": dataset_id, "isa": isa })))
+}
+
+// Повторный разбор ISA-Tab из уже скачанного зеркала
+pub async fn osdr_isa_import(
+    Path(dataset_id): Path<String>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let isa = st.space_service.import_osdr_isa(&dataset_id).await?;
+    Ok(Json(json!({
+        "dataset_id": dataset_id,
+        "source": isa.source,
+        "protocols": isa.protocols.len(),
+        "samples": isa.samples.len(),
+        "assays": isa.assays.len(),
+    })))
+}
+
+#[derive(Deserialize)]
+pub struct OsdrRelatedQuery {
+    pub limit: Option<i64>,
+}
+
+pub async fn osdr_related(
+    Path(dataset_id): Path<String>,
+    Query(q): Query<OsdrRelatedQuery>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let related = st.space_service.get_osdr_related(&dataset_id, q.limit.unwrap_or(20)).await?;
+    Ok(Json(json!({ "dataset_id": dataset_id, "related": related })))
+}
+
+#[derive(Deserialize)]
+pub struct OsdrGraphQuery {
+    pub format: Option<String>,
+    pub min_weight: Option<i32>,
+}
+
+// Граф связей: JSON (по умолчанию) или GraphML (?format=graphml)
+pub async fn osdr_graph(
+    Query(q): Query<OsdrGraphQuery>,
+    State(st): State<AppState>
+) -> Result<Response, AppError> {
+    // По умолчанию отсекаем связи только по project_type/одному фактору
+    let graph = st.space_service.get_osdr_graph(q.min_weight.unwrap_or(2)).await?;
+
+    match q.format.as_deref() {
+        None | Some("json") => Ok(Json(json!({
+            "nodes": graph.nodes,
+            "edges": graph.edges,
+            "node_count": graph.nodes.len(),
+            "edge_count": graph.edges.len(),
+        })).into_response()),
+        Some("graphml") => Ok((
+            [
+                (header::CONTENT_TYPE, "application/graphml+xml; charset=utf-8"),
+                (header::CONTENT_DISPOSITION, "attachment; filename=\"osdr-graph.graphml\""),
+            ],
+            to_graphml(&graph),
+        ).into_response()),
+        Some(other) => Err(AppError::ClientError(
+            format!("Unsupported graph format '{}', expected json or graphml", other),
+            StatusCode::BAD_REQUEST,
+        )),
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;").replace('\'', "&apos;")
+}
+
+fn to_graphml(graph: &OsdrGraph) -> String {
+    let mut out = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+        "  <key id=\"title\" for=\"node\" attr.name=\"title\" attr.type=\"string\"/>\n",
+        "  <key id=\"organism\" for=\"node\" attr.name=\"organism\" attr.type=\"string\"/>\n",
+        "  <key id=\"mission\" for=\"node\" attr.name=\"mission\" attr.type=\"string\"/>\n",
+        "  <key id=\"assay\" for=\"node\" attr.name=\"assay_technology\" attr.type=\"string\"/>\n",
+        "  <key id=\"weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"int\"/>\n",
+        "  <key id=\"shared\" for=\"edge\" attr.name=\"shared\" attr.type=\"string\"/>\n",
+        "  <graph id=\"osdr\" edgedefault=\"undirected\">\n",
+    ));
+
+    for n in &graph.nodes {
+        out.push_str(&format!("    <node id=\"{}\">\n", xml_escape(&n.dataset_id)));
+        for (key, value) in [("title", &n.title), ("organism", &n.organism), ("mission", &n.mission), ("assay", &n.assay_technology)] {
+            if let Some(v) = value {
+                out.push_str(&format!("      <data key=\"{}\">{}</data>\n", key, xml_escape(v)));
+            }
+        }
+        out.push_str("    </node>\n");
+    }
+    for e in &graph.edges {
+        out.push_str(&format!(
+            "    <edge source=\"{}\" target=\"{}\">\n      <data key=\"weight\">{}</data>\n      <data key=\"shared\">{}</data>\n    </edge>\n",
+            xml_escape(&e.source), xml_escape(&e.target), e.weight, xml_escape(&e.shared.join(",")),
+        ));
+    }
+
+    out.push_str("  </graph>\n</graphml>\n");
+    out
+}
+
+pub async fn osdr_snapshots(State(st): State<AppState>) -> Result<Json<Value>, AppError> {
+    let snapshots = st.space_service.get_osdr_snapshots().await?;
+    let out: Vec<Value> = snapshots.into_iter().map(|s| {
+        let href = format!("/osdr/snapshots/{}", s.id);
+        json!({ "snapshot": s, "href": href })
+    }).collect();
+
+    Ok(Json(json!({ "snapshots": out })))
+}
+
+pub async fn osdr_snapshot_create(State(st): State<AppState>) -> Result<Json<Value>, AppError> {
+    let snapshot = st.space_service.create_osdr_snapshot().await?;
+    Ok(Json(json!({ "snapshot": snapshot })))
+}
+
+pub async fn osdr_snapshot_download(
+    Path(id): Path<String>,
+    State(st): State<AppState>
+) -> Result<Response, AppError> {
+    let s = st.space_service.get_osdr_snapshot(&id).await?;
+    let file = tokio::fs::File::open(&s.file_path).await?;
+    let len = file.metadata().await?.len();
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/gzip".to_string()),
+            (header::CONTENT_LENGTH, len.to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"osdr-{}.ndjson.gz\"", s.id)),
+            (header::ETAG, format!("\"{}\"", s.sha256)),
+        ],
+        Body::from_stream(ReaderStream::new(file)),
+    ).into_response())
+}
+
+#[derive(Deserialize)]
+pub struct OsdrSnapshotDiffQuery {
+    pub from: String,
+    pub to: String,
+}
+
+pub async fn osdr_snapshot_diff(
+    Query(q): Query<OsdrSnapshotDiffQuery>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let diff = st.space_service.diff_osdr_snapshots(&q.from, &q.to).await?;
+    Ok(Json(json!({
+        "counts": { "added": diff.added.len(), "removed": diff.removed.len(), "changed": diff.changed.len() },
+        "diff": diff,
+    })))
+}
@@ -0,0 +1,56 @@
+use axum::{extract::{Query, State}, Json};
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use crate::domain::models::AppState;
+use crate::domain::errors::AppError;
+
+// Текущий уровень активности (quiet | low | moderate | high | severe) с объяснением
+pub async fn space_weather_status(State(st): State<AppState>) -> Result<Json<Value>, AppError> {
+    let status = st.space_service.get_space_weather_status().await?;
+    Ok(Json(json!({ "status": status })))
+}
+
+#[derive(Deserialize)]
+pub struct SpaceWeatherStatsQuery {
+    pub months: Option<i64>,
+}
+
+// Месячные ряды: вспышки по классу и CME по скорости (по умолчанию 12 месяцев)
+pub async fn space_weather_stats(
+    Query(q): Query<SpaceWeatherStatsQuery>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let stats = st.space_service.get_space_weather_stats(q.months.unwrap_or(12)).await?;
+    Ok(Json(json!({ "stats": stats })))
+}
+
+#[derive(Deserialize)]
+pub struct NoaaRangeQuery {
+    pub from: Option<DateTime<Utc>>, // RFC 3339
+    pub to: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+}
+
+// Планетарный Kp NOAA: по умолчанию 3 дня назад .. 3 дня вперед (наблюдения + прогноз)
+pub async fn noaa_kp(
+    Query(q): Query<NoaaRangeQuery>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let now = Utc::now();
+    let from = q.from.unwrap_or(now - Duration::days(3));
+    let to = q.to.unwrap_or(now + Duration::days(3));
+    let kp = st.space_service.get_noaa_kp(from, to).await?;
+    Ok(Json(json!({ "from": from, "to": to, "count": kp.len(), "kp": kp })))
+}
+
+// Солнечный ветер NOAA (плотность, скорость, температура, Bx/By/Bz GSM, Bt): по умолчанию последние 24 часа
+pub async fn noaa_solar_wind(
+    Query(q): Query<NoaaRangeQuery>,
+    State(st): State<AppState>
+) -> Result<Json<Value>, AppError> {
+    let to = q.to.unwrap_or_else(Utc::now);
+    let from = q.from.unwrap_or(to - Duration::hours(24));
+    let samples = st.space_service.get_solar_wind(from, to, q.limit.unwrap_or(1440)).await?;
+    Ok(Json(json!({ "from": from, "to": to, "count": samples.len(), "solar_wind": samples })))
+}
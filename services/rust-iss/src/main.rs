@@ -4,7 +4,14 @@ use tracing_subscriber::{EnvFilter, FmtSubscriber};
 
 // Импортируем конкретные реализации
 use crate::clients::nasa_client::{create_api_client, new_nasa_client}; 
+use crate::clients::celestrak_client::new_celestrak_client;
+use crate::clients::ll2_client::new_ll2_client;
+use crate::clients::noaa_client::new_noaa_client;
+use crate::clients::spacex_client::new_spacex_client;
 use crate::repo::osdr_repo::{new_osdr_repo, OsdrRepo}; 
+use crate::repo::space_repo::new_space_repo;
+use crate::repo::donki_repo::new_donki_repo;
+use crate::repo::launch_repo::new_launch_repo;
 
 // Импорты модулей
 mod config;
@@ -47,6 +54,27 @@ async fn main() -> Result<(), AppError> {
     // Создание OSDR репозитория (Repository)
     let osdr_repo_impl = new_osdr_repo(pool.clone());
     let osdr_repo = Arc::new(osdr_repo_impl);
+
+    // Хранилище космоданных (APOD, space_cache)
+    let space_repo = Arc::new(new_space_repo(pool.clone()));
+
+    // Хранилище событий DONKI
+    let donki_repo = Arc::new(new_donki_repo(pool.clone()));
+
+    // Хранилище пусков (ракеты, площадки, журнал изменений)
+    let launch_repo = Arc::new(new_launch_repo(pool.clone()));
+
+    // Клиент NOAA SWPC (Kp, солнечный ветер)
+    let noaa_client = Arc::new(new_noaa_client(api_client.clone(), config.noaa_swpc_url.clone()));
+
+    // Клиент SpaceX API v4 (пуски, ракеты, площадки)
+    let spacex_client = Arc::new(new_spacex_client(api_client.clone(), config.spacex_api_url.clone()));
+
+    // Клиент Launch Library 2 (пуски всех провайдеров)
+    let ll2_client = Arc::new(new_ll2_client(api_client.clone(), config.ll2_api_url.clone()));
+
+    // Клиент CelesTrak GP (элементы орбиты МКС)
+    let celestrak_client = Arc::new(new_celestrak_client(api_client.clone(), config.celestrak_api_url.clone()));
     
     // !!! ИНИЦИАЛИЗАЦИЯ НОВОГО КЛИЕНТА REDIS !!!
     let legacy_pascal_client = Arc::new(
@@ -58,6 +86,13 @@ async fn main() -> Result<(), AppError> {
         config.clone(),
         nasa_client.clone(),
         osdr_repo.clone(),
+        space_repo,
+        donki_repo,
+        launch_repo,
+        noaa_client,
+        spacex_client,
+        ll2_client,
+        celestrak_client,
         legacy_pascal_client.clone()
     );
     
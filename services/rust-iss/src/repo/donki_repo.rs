@@ -0,0 +1,399 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use serde_json::Value;
+
+use crate::domain::contracts::DonkiRepoContract;
+use crate::domain::errors::AppError;
+use crate::domain::models::{
+    DonkiChainNode, DonkiCme, DonkiCmeAnalysis, DonkiEvent, DonkiFlare, DonkiLink, DonkiNotification, KpReading,
+    SpaceWeatherMonthly, StatBucket,
+};
+
+/// Колонки donki_flares, из которых собирается DonkiFlare
+const FLARE_COLUMNS: &str =
+    "flr_id, begin_time, peak_time, end_time, class_type, source_location, active_region_num, instruments, link";
+
+/// Колонки donki_cmes, из которых собирается DonkiCme (анализы грузятся отдельно)
+const CME_COLUMNS: &str =
+    "activity_id, start_time, source_location, active_region_num, catalog, note, link,
+     speed_kms, half_angle, cme_type, latitude, longitude";
+
+/// Колонки donki_events, из которых собирается DonkiEvent
+const EVENT_COLUMNS: &str =
+    "event_id, kind, event_time, location, catalog, instruments, kp_max, details, link";
+
+/// Колонки donki_notifications, из которых собирается DonkiNotification
+const NOTIFICATION_COLUMNS: &str = "message_id, message_type, issue_time, url, body";
+
+/// CME, направленный к Земле: модель ENLIL ожидает удар/касание Земли,
+/// либо широкий (half_angle >= 45) выброс из центра диска (|долгота| <= 30)
+const EARTH_DIRECTED_CME: &str =
+    "(jsonb_path_exists(raw, '$.cmeAnalyses[*].enlilList[*] ? (@.estimatedShockArrivalTime != null || @.isEarthGB == true)')
+      OR (abs(longitude) <= 30 AND half_angle >= 45))";
+
+/// Анализ CME вместе с activity_id (для раскладки по выбросам)
+#[derive(sqlx::FromRow)]
+struct CmeAnalysisRow {
+    activity_id: String,
+    #[sqlx(flatten)]
+    analysis: DonkiCmeAnalysis,
+}
+
+// =========================================================================================
+// 1. СТРУКТУРА РЕПОЗИТОРИЯ
+// =========================================================================================
+
+/// Хранилище событий космической погоды DONKI (нормализованные таблицы donki_*).
+pub struct DonkiRepo {
+    pool: PgPool,
+}
+
+pub fn new_donki_repo(pool: PgPool) -> DonkiRepo {
+    DonkiRepo { pool }
+}
+
+impl DonkiRepo {
+    /// Заменяет исходящие ребра события по его linkedEvents
+    async fn save_links(&self, event_id: &str, raw: &Value) -> Result<(), AppError> {
+        let targets: Vec<String> = raw["linkedEvents"].as_array()
+            .map(|list| list.iter().filter_map(|e| e["activityID"].as_str().map(|s| s.to_string())).collect())
+            .unwrap_or_default();
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM donki_links WHERE from_id = $1")
+            .bind(event_id)
+            .execute(&mut *tx).await?;
+        sqlx::query(
+            "INSERT INTO donki_links(from_id, to_id)
+             SELECT $1, unnest($2::text[])
+             ON CONFLICT DO NOTHING"
+        )
+        .bind(event_id).bind(&targets)
+        .execute(&mut *tx).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+// =========================================================================================
+// 2. РЕАЛИЗАЦИЯ КОНТРАКТА
+// =========================================================================================
+
+#[async_trait]
+impl DonkiRepoContract for DonkiRepo {
+
+    /// UPSERT вспышек по flrID; записи без id пропускаются
+    async fn upsert_flares(&self, items: &[Value]) -> Result<u64, AppError> {
+        let mut written = 0;
+        for raw in items {
+            let Some(f) = DonkiFlare::from_raw(raw) else { continue };
+            sqlx::query(
+                "INSERT INTO donki_flares(flr_id, begin_time, peak_time, end_time, class_type, source_location,
+                                          active_region_num, instruments, link, raw, updated_at)
+                 VALUES($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,now())
+                 ON CONFLICT (flr_id) DO UPDATE
+                 SET begin_time=EXCLUDED.begin_time, peak_time=EXCLUDED.peak_time, end_time=EXCLUDED.end_time,
+                     class_type=EXCLUDED.class_type, source_location=EXCLUDED.source_location,
+                     active_region_num=EXCLUDED.active_region_num, instruments=EXCLUDED.instruments,
+                     link=EXCLUDED.link, raw=EXCLUDED.raw, updated_at=now()"
+            )
+            .bind(&f.flr_id).bind(f.begin_time).bind(f.peak_time).bind(f.end_time)
+            .bind(&f.class_type).bind(&f.source_location).bind(f.active_region_num)
+            .bind(&f.instruments).bind(&f.link).bind(raw)
+            .execute(&self.pool).await?;
+            self.save_links(&f.flr_id, raw).await?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    /// UPSERT CME по activityID; анализы заменяются целиком (в DONKI у них нет собственного id)
+    async fn upsert_cmes(&self, items: &[Value]) -> Result<u64, AppError> {
+        let mut written = 0;
+        for raw in items {
+            let Some(c) = DonkiCme::from_raw(raw) else { continue };
+            let mut tx = self.pool.begin().await?;
+
+            sqlx::query(
+                "INSERT INTO donki_cmes(activity_id, start_time, source_location, active_region_num, catalog, note, link,
+                                        speed_kms, half_angle, cme_type, latitude, longitude, raw, updated_at)
+                 VALUES($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,$13,now())
+                 ON CONFLICT (activity_id) DO UPDATE
+                 SET start_time=EXCLUDED.start_time, source_location=EXCLUDED.source_location,
+                     active_region_num=EXCLUDED.active_region_num, catalog=EXCLUDED.catalog, note=EXCLUDED.note,
+                     link=EXCLUDED.link, speed_kms=EXCLUDED.speed_kms, half_angle=EXCLUDED.half_angle,
+                     cme_type=EXCLUDED.cme_type, latitude=EXCLUDED.latitude, longitude=EXCLUDED.longitude,
+                     raw=EXCLUDED.raw, updated_at=now()"
+            )
+            .bind(&c.activity_id).bind(c.start_time).bind(&c.source_location).bind(c.active_region_num)
+            .bind(&c.catalog).bind(&c.note).bind(&c.link).bind(c.speed_kms).bind(c.half_angle)
+            .bind(&c.cme_type).bind(c.latitude).bind(c.longitude).bind(raw)
+            .execute(&mut *tx).await?;
+
+            sqlx::query("DELETE FROM donki_cme_analyses WHERE activity_id = $1")
+                .bind(&c.activity_id)
+                .execute(&mut *tx).await?;
+            for a in &c.analyses {
+                sqlx::query(
+                    "INSERT INTO donki_cme_analyses(activity_id, time21_5, latitude, longitude, half_angle, speed_kms,
+                                                    cme_type, is_most_accurate, level_of_data, note, link)
+                     VALUES($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11)"
+                )
+                .bind(&c.activity_id).bind(a.time21_5).bind(a.latitude).bind(a.longitude).bind(a.half_angle)
+                .bind(a.speed_kms).bind(&a.cme_type).bind(a.is_most_accurate).bind(a.level_of_data)
+                .bind(&a.note).bind(&a.link)
+                .execute(&mut *tx).await?;
+            }
+
+            tx.commit().await?;
+            self.save_links(&c.activity_id, raw).await?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    async fn get_flares(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        class_prefix: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<DonkiFlare>, AppError> {
+        let flares = sqlx::query_as::<_, DonkiFlare>(&format!(
+            "SELECT {FLARE_COLUMNS} FROM donki_flares
+             WHERE begin_time BETWEEN $1 AND $2
+               AND ($3::text IS NULL OR upper(class_type) LIKE (upper($3) || '%'))
+             ORDER BY begin_time DESC
+             LIMIT $4"
+        ))
+        .bind(from).bind(to).bind(class_prefix).bind(limit)
+        .fetch_all(&self.pool).await?;
+
+        Ok(flares)
+    }
+
+    async fn get_cmes(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        min_speed: Option<f64>,
+        limit: i64,
+    ) -> Result<Vec<DonkiCme>, AppError> {
+        let mut cmes = sqlx::query_as::<_, DonkiCme>(&format!(
+            "SELECT {CME_COLUMNS} FROM donki_cmes
+             WHERE start_time BETWEEN $1 AND $2
+               AND ($3::float8 IS NULL OR speed_kms >= $3)
+             ORDER BY start_time DESC
+             LIMIT $4"
+        ))
+        .bind(from).bind(to).bind(min_speed).bind(limit)
+        .fetch_all(&self.pool).await?;
+
+        // Анализы одним запросом для всей страницы
+        let ids: Vec<String> = cmes.iter().map(|c| c.activity_id.clone()).collect();
+        let analyses = sqlx::query_as::<_, CmeAnalysisRow>(
+            "SELECT activity_id, time21_5, latitude, longitude, half_angle, speed_kms, cme_type,
+                    is_most_accurate, level_of_data, note, link
+             FROM donki_cme_analyses WHERE activity_id = ANY($1)
+             ORDER BY id"
+        )
+        .bind(&ids)
+        .fetch_all(&self.pool).await?;
+
+        for row in analyses {
+            if let Some(cme) = cmes.iter_mut().find(|c| c.activity_id == row.activity_id) {
+                cme.analyses.push(row.analysis);
+            }
+        }
+        Ok(cmes)
+    }
+
+    /// UPSERT событий одного типа по id DONKI; записи без id пропускаются
+    async fn upsert_events(&self, kind: &str, items: &[Value]) -> Result<u64, AppError> {
+        let mut written = 0;
+        for raw in items {
+            let Some(e) = DonkiEvent::from_raw(kind, raw) else { continue };
+            sqlx::query(
+                "INSERT INTO donki_events(event_id, kind, event_time, location, catalog, instruments, kp_max,
+                                          details, link, raw, updated_at)
+                 VALUES($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,now())
+                 ON CONFLICT (event_id) DO UPDATE
+                 SET kind=EXCLUDED.kind, event_time=EXCLUDED.event_time, location=EXCLUDED.location,
+                     catalog=EXCLUDED.catalog, instruments=EXCLUDED.instruments, kp_max=EXCLUDED.kp_max,
+                     details=EXCLUDED.details, link=EXCLUDED.link, raw=EXCLUDED.raw, updated_at=now()"
+            )
+            .bind(&e.event_id).bind(&e.kind).bind(e.event_time).bind(&e.location).bind(&e.catalog)
+            .bind(&e.instruments).bind(e.kp_max).bind(&e.details).bind(&e.link).bind(raw)
+            .execute(&self.pool).await?;
+            self.save_links(&e.event_id, raw).await?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    async fn upsert_notifications(&self, items: &[Value]) -> Result<u64, AppError> {
+        let mut written = 0;
+        for raw in items {
+            let Some(n) = DonkiNotification::from_raw(raw) else { continue };
+            sqlx::query(
+                "INSERT INTO donki_notifications(message_id, message_type, issue_time, url, body, updated_at)
+                 VALUES($1,$2,$3,$4,$5,now())
+                 ON CONFLICT (message_id) DO UPDATE
+                 SET message_type=EXCLUDED.message_type, issue_time=EXCLUDED.issue_time,
+                     url=EXCLUDED.url, body=EXCLUDED.body, updated_at=now()"
+            )
+            .bind(&n.message_id).bind(&n.message_type).bind(n.issue_time).bind(&n.url).bind(&n.body)
+            .execute(&self.pool).await?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    async fn get_events(
+        &self,
+        kind: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<DonkiEvent>, AppError> {
+        let events = sqlx::query_as::<_, DonkiEvent>(&format!(
+            "SELECT {EVENT_COLUMNS} FROM donki_events
+             WHERE kind = $1 AND event_time BETWEEN $2 AND $3
+             ORDER BY event_time DESC
+             LIMIT $4"
+        ))
+        .bind(kind).bind(from).bind(to).bind(limit)
+        .fetch_all(&self.pool).await?;
+
+        Ok(events)
+    }
+
+    async fn get_notifications(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        message_type: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<DonkiNotification>, AppError> {
+        let notifications = sqlx::query_as::<_, DonkiNotification>(&format!(
+            "SELECT {NOTIFICATION_COLUMNS} FROM donki_notifications
+             WHERE issue_time BETWEEN $1 AND $2
+               AND ($3::text IS NULL OR upper(message_type) = upper($3))
+             ORDER BY issue_time DESC
+             LIMIT $4"
+        ))
+        .bind(from).bind(to).bind(message_type).bind(limit)
+        .fetch_all(&self.pool).await?;
+
+        Ok(notifications)
+    }
+
+    /// Связная компонента графа вокруг события: узлы (в т.ч. несохраненные) и ребра между ними
+    async fn get_chain(&self, event_id: &str) -> Result<(Vec<DonkiChainNode>, Vec<DonkiLink>), AppError> {
+        // Ребра обходятся в обе стороны: DONKI не всегда проставляет обратную ссылку
+        let ids: Vec<String> = sqlx::query_scalar(
+            "WITH RECURSIVE chain(id) AS (
+                SELECT $1::text
+                UNION
+                SELECT CASE WHEN l.from_id = c.id THEN l.to_id ELSE l.from_id END
+                FROM donki_links l JOIN chain c ON l.from_id = c.id OR l.to_id = c.id
+             )
+             SELECT id FROM chain"
+        )
+        .bind(event_id)
+        .fetch_all(&self.pool).await?;
+
+        let nodes = sqlx::query_as::<_, DonkiChainNode>(
+            "SELECT i.id AS event_id, n.kind, n.event_time, n.label, n.link
+             FROM unnest($1::text[]) AS i(id)
+             LEFT JOIN donki_event_nodes n ON n.event_id = i.id"
+        )
+        .bind(&ids)
+        .fetch_all(&self.pool).await?;
+
+        let links = sqlx::query_as::<_, DonkiLink>(
+            "SELECT from_id, to_id FROM donki_links
+             WHERE from_id = ANY($1) AND to_id = ANY($1)
+             ORDER BY from_id, to_id"
+        )
+        .bind(&ids)
+        .fetch_all(&self.pool).await?;
+
+        Ok((nodes, links))
+    }
+
+    async fn get_flare_class_counts(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<StatBucket>, AppError> {
+        let buckets = sqlx::query_as::<_, StatBucket>(
+            "SELECT upper(left(class_type, 1)) AS key, count(*) AS count
+             FROM donki_flares
+             WHERE coalesce(peak_time, begin_time) BETWEEN $1 AND $2 AND class_type IS NOT NULL
+             GROUP BY 1
+             ORDER BY array_position(ARRAY['A','B','C','M','X'], upper(left(class_type, 1))) DESC NULLS LAST"
+        )
+        .bind(from).bind(to)
+        .fetch_all(&self.pool).await?;
+        Ok(buckets)
+    }
+
+    async fn get_earth_directed_cmes(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<DonkiCme>, AppError> {
+        let cmes = sqlx::query_as::<_, DonkiCme>(&format!(
+            "SELECT {CME_COLUMNS} FROM donki_cmes
+             WHERE start_time BETWEEN $1 AND $2 AND {EARTH_DIRECTED_CME}
+             ORDER BY speed_kms DESC NULLS LAST"
+        ))
+        .bind(from).bind(to)
+        .fetch_all(&self.pool).await?;
+        Ok(cmes)
+    }
+
+    /// Значения Kp из геомагнитных бурь DONKI (allKpIndex), по времени наблюдения
+    async fn get_kp_readings(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<KpReading>, AppError> {
+        let readings = sqlx::query_as::<_, KpReading>(
+            "SELECT DISTINCT ON (observed_at) observed_at, kp, 'donki' AS source
+             FROM (
+                SELECT (k->>'observedTime')::timestamptz AS observed_at, (k->>'kpIndex')::float8 AS kp
+                FROM donki_events, jsonb_array_elements(details->'allKpIndex') k
+                WHERE kind = 'gst' AND jsonb_typeof(details->'allKpIndex') = 'array'
+             ) r
+             WHERE observed_at BETWEEN $1 AND $2 AND kp IS NOT NULL
+             ORDER BY observed_at, kp DESC"
+        )
+        .bind(from).bind(to)
+        .fetch_all(&self.pool).await?;
+        Ok(readings)
+    }
+
+    async fn get_flare_monthly(&self, from: DateTime<Utc>) -> Result<Vec<SpaceWeatherMonthly>, AppError> {
+        let rows = sqlx::query_as::<_, SpaceWeatherMonthly>(
+            "SELECT date_trunc('month', coalesce(peak_time, begin_time)) AS month,
+                    coalesce(upper(left(class_type, 1)), '?') AS bucket, count(*) AS count
+             FROM donki_flares
+             WHERE coalesce(peak_time, begin_time) >= $1
+             GROUP BY 1, 2
+             ORDER BY 1, 2"
+        )
+        .bind(from)
+        .fetch_all(&self.pool).await?;
+        Ok(rows)
+    }
+
+    async fn get_cme_monthly(&self, from: DateTime<Utc>) -> Result<Vec<SpaceWeatherMonthly>, AppError> {
+        let rows = sqlx::query_as::<_, SpaceWeatherMonthly>(
+            "SELECT date_trunc('month', start_time) AS month,
+                    CASE WHEN speed_kms IS NULL THEN 'unknown'
+                         WHEN speed_kms < 500 THEN '<500'
+                         WHEN speed_kms < 1000 THEN '500-999'
+                         WHEN speed_kms < 1500 THEN '1000-1499'
+                         ELSE '1500+' END AS bucket,
+                    count(*) AS count
+             FROM donki_cmes
+             WHERE start_time >= $1
+             GROUP BY 1, 2
+             ORDER BY 1, 2"
+        )
+        .bind(from)
+        .fetch_all(&self.pool).await?;
+        Ok(rows)
+    }
+}
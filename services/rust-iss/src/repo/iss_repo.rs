@@ -3,6 +3,7 @@ use serde_json::Value;
 use chrono::{DateTime, Utc};
 use crate::domain::models::{IssLog, Trend};
 use crate::domain::errors::AppError;
+use crate::utils::hash;
 use crate::utils::haversine::haversine_km;
 
 // Соответствует `insert_iss_log`
@@ -75,20 +76,112 @@ pub async fn calculate_trend(pool: &PgPool) -> Result<Trend, AppError> {
     })
 }
 
-// Функции для универсального кэша space_cache
-pub async fn write_cache(pool: &PgPool, source: &str, payload: &Value) -> Result<(), AppError> {
-    sqlx::query("INSERT INTO space_cache(source, payload) VALUES ($1,$2)")
-        .bind(source).bind(payload).execute(pool).await?;
-    Ok(())
+// Служебные отметки времени наших строк (ApodEntry.fetched_at, Launch.updated_at,
+// sources[].updated_at): меняются при каждой синхронизации и в хэш содержимого не входят
+const CACHE_VOLATILE_KEYS: [&str; 2] = ["fetched_at", "updated_at"];
+
+fn strip_volatile(v: &mut Value) {
+    match v {
+        Value::Object(map) => {
+            for key in CACHE_VOLATILE_KEYS {
+                map.remove(key);
+            }
+            map.values_mut().for_each(strip_volatile);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_volatile),
+        _ => {}
+    }
+}
+
+// Функции для универсального кэша space_cache.
+// Если payload совпадает по хэшу с последней записью источника, новая строка не пишется —
+// у последней записи обновляются last_checked_at и payload (свежие служебные отметки внутри).
+// Возвращает true, если строка добавлена.
+// Писатели одного источника сериализуются advisory-блокировкой: иначе два параллельных
+// обновления видят одну и ту же последнюю запись и оба вставляют строку.
+pub async fn write_cache(pool: &PgPool, source: &str, payload: &Value) -> Result<bool, AppError> {
+    let mut stable = payload.clone();
+    strip_volatile(&mut stable);
+    let content_hash = hash::json_hash(&stable);
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+        .bind(source)
+        .execute(&mut *tx).await?;
+    let inserted = sqlx::query(
+        "WITH last AS (
+            SELECT id, content_hash FROM space_cache WHERE source = $1 ORDER BY id DESC LIMIT 1
+         ), touched AS (
+            UPDATE space_cache s SET last_checked_at = now(), payload = $2
+            FROM last WHERE s.id = last.id AND last.content_hash = $3
+            RETURNING s.id
+         )
+         INSERT INTO space_cache(source, payload, content_hash, last_checked_at)
+         SELECT $1, $2, $3, now() WHERE NOT EXISTS (SELECT 1 FROM touched)"
+    )
+    .bind(source).bind(payload).bind(&content_hash)
+    .execute(&mut *tx).await?
+    .rows_affected() > 0;
+    tx.commit().await?;
+    Ok(inserted)
+}
+
+// Источники, у которых есть записи в space_cache
+pub async fn get_cache_sources(pool: &PgPool) -> Result<Vec<String>, AppError> {
+    let sources = sqlx::query_scalar("SELECT DISTINCT source FROM space_cache ORDER BY source")
+        .fetch_all(pool).await?;
+    Ok(sources)
+}
+
+// Удаляет записи источника, не проверявшиеся с older_than, и сверх keep_rows последних.
+// Последняя запись источника остается всегда. Возвращает (удалено по возрасту, по числу, осталось).
+pub async fn prune_cache(
+    pool: &PgPool,
+    source: &str,
+    older_than: Option<DateTime<Utc>>,
+    keep_rows: Option<i64>,
+) -> Result<(u64, u64, i64), AppError> {
+    let mut tx = pool.begin().await?;
+
+    let mut by_age = 0;
+    if let Some(older_than) = older_than {
+        by_age = sqlx::query(
+            "DELETE FROM space_cache
+             WHERE source = $1 AND last_checked_at < $2
+               AND id <> (SELECT max(id) FROM space_cache WHERE source = $1)"
+        )
+        .bind(source).bind(older_than)
+        .execute(&mut *tx).await?
+        .rows_affected();
+    }
+
+    let mut by_count = 0;
+    if let Some(keep_rows) = keep_rows {
+        by_count = sqlx::query(
+            "DELETE FROM space_cache
+             WHERE source = $1
+               AND id NOT IN (SELECT id FROM space_cache WHERE source = $1 ORDER BY id DESC LIMIT $2)"
+        )
+        .bind(source).bind(keep_rows.max(1))
+        .execute(&mut *tx).await?
+        .rows_affected();
+    }
+
+    let kept: i64 = sqlx::query_scalar("SELECT count(*) FROM space_cache WHERE source = $1")
+        .bind(source)
+        .fetch_one(&mut *tx).await?;
+
+    tx.commit().await?;
+    Ok((by_age, by_count, kept))
 }
 
 // Соответствует `last_space`
 pub async fn get_latest_from_cache(pool: &PgPool, source: &str) -> Result<Option<Value>, AppError> {
     let row = sqlx::query(
-        "SELECT payload, fetched_at
+        "SELECT payload, fetched_at, last_checked_at
          FROM space_cache
          WHERE source = $1
-         ORDER BY fetched_at DESC
+         ORDER BY id DESC
          LIMIT 1"
     )
     .bind(source)
@@ -99,9 +192,65 @@ pub async fn get_latest_from_cache(pool: &PgPool, source: &str) -> Result<Option
         use sqlx::Row;
         Ok(Some(serde_json::json!({
             "fetched_at": r.get::<DateTime<Utc>, _>("fetched_at"),
+            "last_checked_at": r.get::<DateTime<Utc>, _>("last_checked_at"),
             "payload": r.get::<Value, _>("payload"),
         })))
     } else {
         Ok(None)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn strips_sync_timestamps_at_any_depth() {
+        let mut v = json!({
+            "launches": 120,
+            "fetched_at": "2024-05-01T10:00:00Z",
+            "next": {
+                "launch_id": "uuid-1",
+                "updated_at": "2024-05-01T10:00:00Z",
+                "sources": [
+                    { "provider": "ll2", "launch_id": "uuid-1", "updated_at": "2024-05-01T10:00:00Z" },
+                    { "provider": "spacex", "launch_id": "L2", "updated_at": "2024-04-30T08:00:00Z" }
+                ]
+            }
+        });
+        strip_volatile(&mut v);
+        assert_eq!(v, json!({
+            "launches": 120,
+            "next": {
+                "launch_id": "uuid-1",
+                "sources": [
+                    { "provider": "ll2", "launch_id": "uuid-1" },
+                    { "provider": "spacex", "launch_id": "L2" }
+                ]
+            }
+        }));
+    }
+
+    #[test]
+    fn hash_ignores_only_sync_timestamps() {
+        let hash = |v: Value| {
+            let mut v = v;
+            strip_volatile(&mut v);
+            hash::json_hash(&v)
+        };
+        let a = hash(json!({ "date": "2024-05-01", "title": "M31", "fetched_at": "2024-05-01T10:00:00Z" }));
+        let b = hash(json!({ "date": "2024-05-01", "title": "M31", "fetched_at": "2024-05-02T10:00:00Z" }));
+        let c = hash(json!({ "date": "2024-05-01", "title": "M33", "fetched_at": "2024-05-01T10:00:00Z" }));
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn scalars_and_empty_values_are_untouched() {
+        for v in [json!(null), json!(42), json!("updated_at"), json!([]), json!({})] {
+            let mut stripped = v.clone();
+            strip_volatile(&mut stripped);
+            assert_eq!(stripped, v);
+        }
+    }
+}
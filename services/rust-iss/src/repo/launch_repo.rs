@@ -0,0 +1,296 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use serde_json::Value;
+
+use crate::domain::contracts::LaunchRepoContract;
+use crate::domain::errors::AppError;
+use crate::domain::models::{Launch, LaunchChange, LaunchPad, LaunchRocket, LaunchSyncStats};
+
+/// Колонки launches (l) с ракетой (r), площадкой (p) и слитым дубликатом (d), из которых собирается Launch.
+/// Поля, которых нет у основного провайдера (номер полета, трансляция, эмблема), берутся из дубликата.
+const LAUNCH_COLUMNS: &str =
+    "l.launch_id, l.provider, l.name, l.agency, l.mission_name, l.mission_type, l.orbit,
+     coalesce(l.flight_number, d.flight_number) AS flight_number, l.net, l.net_precision, l.status, l.upcoming,
+     l.rocket_id, r.name AS rocket_name, l.pad_id, p.name AS pad_name, p.locality AS pad_locality,
+     p.latitude AS pad_latitude, p.longitude AS pad_longitude, coalesce(l.details, d.details) AS details,
+     coalesce(l.webcast_url, d.webcast_url) AS webcast_url, coalesce(l.patch_url, d.patch_url) AS patch_url,
+     l.image_url,
+     (SELECT jsonb_agg(jsonb_build_object('provider', s.provider, 'launch_id', s.launch_id, 'updated_at', s.updated_at)
+                       ORDER BY s.merged_into IS NOT NULL, s.provider)
+      FROM launches s WHERE s.launch_id = l.launch_id OR s.merged_into = l.launch_id) AS sources,
+     l.updated_at, coalesce(l.calendar_uid, l.launch_id) AS calendar_uid, l.revision";
+
+/// launches с разрешенными ракетой и площадкой и слитым дубликатом
+const LAUNCH_FROM: &str =
+    "launches l
+     LEFT JOIN launch_rockets r ON r.rocket_id = l.rocket_id
+     LEFT JOIN launch_pads p ON p.pad_id = l.pad_id
+     LEFT JOIN LATERAL (
+        SELECT flight_number, details, webcast_url, patch_url FROM launches
+        WHERE merged_into = l.launch_id ORDER BY updated_at DESC LIMIT 1
+     ) d ON true";
+
+/// Окно совпадения даты пуска SpaceX и Launch Library 2 (секунды)
+const LAUNCH_MATCH_WINDOW_SECS: i64 = 86400;
+/// Допуск совпадения координат площадок (градусы): один космодром
+const LAUNCH_MATCH_PAD_DEGREES: f64 = 0.5;
+
+// =========================================================================================
+// 1. СТРУКТУРА РЕПОЗИТОРИЯ
+// =========================================================================================
+
+/// Хранилище пусков всех провайдеров (launches), ракет, площадок и журнала изменений пусков.
+pub struct LaunchRepo {
+    pool: PgPool,
+}
+
+pub fn new_launch_repo(pool: PgPool) -> LaunchRepo {
+    LaunchRepo { pool }
+}
+
+impl LaunchRepo {
+    async fn save_rocket(&self, r: &LaunchRocket, raw: &Value) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO launch_rockets(rocket_id, provider, name, rocket_type, active, success_rate_pct, raw, updated_at)
+             VALUES($1,$2,$3,$4,$5,$6,$7,now())
+             ON CONFLICT (rocket_id) DO UPDATE
+             SET provider=EXCLUDED.provider, name=EXCLUDED.name, rocket_type=EXCLUDED.rocket_type,
+                 active=EXCLUDED.active, success_rate_pct=EXCLUDED.success_rate_pct, raw=EXCLUDED.raw, updated_at=now()"
+        )
+        .bind(&r.rocket_id).bind(&r.provider).bind(&r.name).bind(&r.rocket_type)
+        .bind(r.active).bind(r.success_rate_pct).bind(raw)
+        .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn save_pad(&self, p: &LaunchPad, raw: &Value) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO launch_pads(pad_id, provider, name, full_name, locality, region, latitude, longitude, status, raw, updated_at)
+             VALUES($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,now())
+             ON CONFLICT (pad_id) DO UPDATE
+             SET provider=EXCLUDED.provider, name=EXCLUDED.name, full_name=EXCLUDED.full_name,
+                 locality=EXCLUDED.locality, region=EXCLUDED.region, latitude=EXCLUDED.latitude,
+                 longitude=EXCLUDED.longitude, status=EXCLUDED.status, raw=EXCLUDED.raw, updated_at=now()"
+        )
+        .bind(&p.pad_id).bind(&p.provider).bind(&p.name).bind(&p.full_name).bind(&p.locality)
+        .bind(&p.region).bind(p.latitude).bind(p.longitude).bind(&p.status).bind(raw)
+        .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    /// UPSERT пуска со сравнением с сохраненной версией; возвращает число записанных изменений.
+    /// Смена даты у пуска, чья прежняя дата уже наступила, а он все еще не состоялся, — скраб.
+    async fn save_launch(&self, l: &Launch, raw: &Value) -> Result<u64, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let previous: Option<(String, Option<DateTime<Utc>>)> =
+            sqlx::query_as("SELECT status, net FROM launches WHERE launch_id = $1 FOR UPDATE")
+                .bind(&l.launch_id)
+                .fetch_optional(&mut *tx).await?;
+
+        let mut changes: Vec<(&str, Option<String>, Option<String>)> = Vec::new();
+        if let Some((old_status, old_net)) = previous {
+            if old_status != l.status {
+                changes.push(("status", Some(old_status), Some(l.status.clone())));
+            }
+            if old_net != l.net {
+                let kind = match old_net {
+                    Some(t) if t <= Utc::now() && l.upcoming => "scrub",
+                    _ => "net_shift",
+                };
+                changes.push((kind, old_net.map(|t| t.to_rfc3339()), l.net.map(|t| t.to_rfc3339())));
+            }
+        }
+
+        sqlx::query(
+            "INSERT INTO launches(launch_id, provider, name, agency, mission_name, mission_type, orbit, flight_number,
+                                  net, net_precision, status, upcoming, rocket_id, pad_id, details, webcast_url,
+                                  patch_url, image_url, raw, updated_at)
+             VALUES($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,$13,$14,$15,$16,$17,$18,$19,now())
+             ON CONFLICT (launch_id) DO UPDATE
+             SET provider=EXCLUDED.provider, name=EXCLUDED.name, agency=EXCLUDED.agency,
+                 mission_name=EXCLUDED.mission_name, mission_type=EXCLUDED.mission_type, orbit=EXCLUDED.orbit,
+                 flight_number=EXCLUDED.flight_number, net=EXCLUDED.net, net_precision=EXCLUDED.net_precision,
+                 status=EXCLUDED.status, upcoming=EXCLUDED.upcoming, rocket_id=EXCLUDED.rocket_id,
+                 pad_id=EXCLUDED.pad_id, details=EXCLUDED.details, webcast_url=EXCLUDED.webcast_url,
+                 patch_url=EXCLUDED.patch_url, image_url=EXCLUDED.image_url, raw=EXCLUDED.raw, updated_at=now()
+             -- без изменений строку не трогаем: updated_at (DTSTAMP в календаре) двигается только при правке
+             WHERE (launches.provider, launches.name, launches.agency, launches.mission_name, launches.mission_type,
+                    launches.orbit, launches.flight_number, launches.net, launches.net_precision, launches.status,
+                    launches.upcoming, launches.rocket_id, launches.pad_id, launches.details, launches.webcast_url,
+                    launches.patch_url, launches.image_url, launches.raw)
+                   IS DISTINCT FROM
+                   (EXCLUDED.provider, EXCLUDED.name, EXCLUDED.agency, EXCLUDED.mission_name, EXCLUDED.mission_type,
+                    EXCLUDED.orbit, EXCLUDED.flight_number, EXCLUDED.net, EXCLUDED.net_precision, EXCLUDED.status,
+                    EXCLUDED.upcoming, EXCLUDED.rocket_id, EXCLUDED.pad_id, EXCLUDED.details, EXCLUDED.webcast_url,
+                    EXCLUDED.patch_url, EXCLUDED.image_url, EXCLUDED.raw)"
+        )
+        .bind(&l.launch_id).bind(&l.provider).bind(&l.name).bind(&l.agency).bind(&l.mission_name)
+        .bind(&l.mission_type).bind(&l.orbit).bind(l.flight_number).bind(l.net).bind(&l.net_precision)
+        .bind(&l.status).bind(l.upcoming).bind(&l.rocket_id).bind(&l.pad_id).bind(&l.details)
+        .bind(&l.webcast_url).bind(&l.patch_url).bind(&l.image_url).bind(raw)
+        .execute(&mut *tx).await?;
+
+        for (kind, old_value, new_value) in &changes {
+            sqlx::query("INSERT INTO launch_changes(launch_id, kind, old_value, new_value) VALUES($1,$2,$3,$4)")
+                .bind(&l.launch_id).bind(kind).bind(old_value).bind(new_value)
+                .execute(&mut *tx).await?;
+        }
+        // Ревизию ведет основной пуск: изменения дубликата — тоже изменения события в календаре
+        if !changes.is_empty() {
+            sqlx::query(
+                "UPDATE launches SET revision = revision + $2
+                 WHERE launch_id = coalesce((SELECT merged_into FROM launches WHERE launch_id = $1), $1)"
+            )
+            .bind(&l.launch_id).bind(changes.len() as i32)
+            .execute(&mut *tx).await?;
+        }
+
+        tx.commit().await?;
+        Ok(changes.len() as u64)
+    }
+}
+
+// =========================================================================================
+// 2. РЕАЛИЗАЦИЯ КОНТРАКТА
+// =========================================================================================
+
+#[async_trait]
+impl LaunchRepoContract for LaunchRepo {
+
+    async fn upsert_spacex_rockets(&self, items: &[Value]) -> Result<u64, AppError> {
+        let mut written = 0;
+        for raw in items {
+            let Some(r) = LaunchRocket::from_spacex(raw) else { continue };
+            self.save_rocket(&r, raw).await?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    async fn upsert_spacex_pads(&self, items: &[Value]) -> Result<u64, AppError> {
+        let mut written = 0;
+        for raw in items {
+            let Some(p) = LaunchPad::from_spacex(raw) else { continue };
+            self.save_pad(&p, raw).await?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    async fn upsert_spacex_launches(&self, items: &[Value]) -> Result<LaunchSyncStats, AppError> {
+        let mut stats = LaunchSyncStats::default();
+        for raw in items {
+            let Some(l) = Launch::from_spacex(raw) else { continue };
+            stats.changes += self.save_launch(&l, raw).await?;
+            stats.written += 1;
+        }
+        Ok(stats)
+    }
+
+    /// UPSERT пусков Launch Library 2 вместе с вложенными конфигурацией ракеты и площадкой
+    async fn upsert_ll2_launches(&self, items: &[Value]) -> Result<LaunchSyncStats, AppError> {
+        let mut stats = LaunchSyncStats::default();
+        for raw in items {
+            let Some(l) = Launch::from_ll2(raw) else { continue };
+            let rocket = &raw["rocket"]["configuration"];
+            if let Some(r) = LaunchRocket::from_ll2(rocket) {
+                self.save_rocket(&r, rocket).await?;
+            }
+            if let Some(p) = LaunchPad::from_ll2(&raw["pad"]) {
+                self.save_pad(&p, &raw["pad"]).await?;
+            }
+            stats.changes += self.save_launch(&l, raw).await?;
+            stats.written += 1;
+        }
+        Ok(stats)
+    }
+
+    /// Пуск SpaceX считается дубликатом пуска Launch Library 2 того же агентства SpaceX,
+    /// если даты расходятся не больше чем на сутки, а площадки на одном космодроме;
+    /// из нескольких кандидатов берется ближайший по дате. Сопоставление один к одному: если два
+    /// пуска SpaceX выбрали один пуск LL2, за ним остается ближайший.
+    /// Связи постоянны: сопоставляются только еще не слитые пуски SpaceX и не занятые пуски LL2,
+    /// иначе при перестановке пар за UID события в календаре оказывался бы другой пуск.
+    /// Основной пуск получает calendar_uid того из пары, кто появился раньше, и ревизию больше обеих.
+    async fn merge_launch_duplicates(&self) -> Result<u64, AppError> {
+        let merged = sqlx::query(
+            "WITH best AS (
+                SELECT DISTINCT ON (x.launch_id) x.launch_id, c.launch_id AS canonical_id,
+                       abs(extract(epoch FROM c.net - x.net)) AS gap
+                FROM launches x
+                JOIN launches c ON c.provider = 'll2' AND c.agency ILIKE '%spacex%'
+                     AND abs(extract(epoch FROM c.net - x.net)) <= $1
+                LEFT JOIN launch_pads xp ON xp.pad_id = x.pad_id
+                LEFT JOIN launch_pads cp ON cp.pad_id = c.pad_id
+                WHERE x.provider = 'spacex' AND x.merged_into IS NULL
+                  AND NOT EXISTS (SELECT 1 FROM launches y WHERE y.merged_into = c.launch_id)
+                  AND (xp.latitude IS NULL OR cp.latitude IS NULL
+                       OR (abs(xp.latitude - cp.latitude) <= $2 AND abs(xp.longitude - cp.longitude) <= $2))
+                ORDER BY x.launch_id, gap, c.launch_id
+             ),
+             m AS (
+                SELECT DISTINCT ON (canonical_id) launch_id, canonical_id
+                FROM best
+                ORDER BY canonical_id, gap, launch_id
+             ),
+             linked AS (
+                UPDATE launches s SET merged_into = m.canonical_id
+                FROM m
+                WHERE s.launch_id = m.launch_id
+                RETURNING s.launch_id, s.calendar_uid, s.first_seen_at, s.revision, m.canonical_id
+             )
+             UPDATE launches c
+             SET calendar_uid = CASE WHEN linked.first_seen_at < c.first_seen_at
+                                     THEN coalesce(linked.calendar_uid, linked.launch_id)
+                                     ELSE coalesce(c.calendar_uid, c.launch_id) END,
+                 revision = greatest(c.revision, linked.revision) + 1
+             FROM linked
+             WHERE c.launch_id = linked.canonical_id"
+        )
+        .bind(LAUNCH_MATCH_WINDOW_SECS as f64).bind(LAUNCH_MATCH_PAD_DEGREES)
+        .execute(&self.pool).await?
+        .rows_affected();
+        Ok(merged)
+    }
+
+    /// Предстоящие — по возрастанию даты, прошедшие — по убыванию; дубликаты скрыты за основным пуском.
+    /// Предстоящие с NET старше суток не показываем: upstream не успел перевести их в прошедшие.
+    async fn get_launches(&self, upcoming: bool, limit: i64) -> Result<Vec<Launch>, AppError> {
+        let order = if upcoming { "ASC" } else { "DESC" };
+        let launches = sqlx::query_as::<_, Launch>(&format!(
+            "SELECT {LAUNCH_COLUMNS} FROM {LAUNCH_FROM}
+             WHERE l.upcoming = $1 AND l.merged_into IS NULL
+               AND (NOT $1 OR l.net IS NULL OR l.net >= now() - interval '1 day')
+             ORDER BY l.net {order} NULLS LAST, l.launch_id
+             LIMIT $2"
+        ))
+        .bind(upcoming).bind(limit)
+        .fetch_all(&self.pool).await?;
+
+        Ok(launches)
+    }
+
+    /// id дубликата ведет на основной пуск; журнал изменений — всех провайдеров пуска
+    async fn get_launch(&self, launch_id: &str) -> Result<Option<Launch>, AppError> {
+        let launch = sqlx::query_as::<_, Launch>(&format!(
+            "SELECT {LAUNCH_COLUMNS} FROM {LAUNCH_FROM}
+             WHERE l.launch_id = coalesce((SELECT merged_into FROM launches WHERE launch_id = $1), $1)"
+        ))
+        .bind(launch_id)
+        .fetch_optional(&self.pool).await?;
+
+        let Some(mut launch) = launch else { return Ok(None) };
+        launch.changes = sqlx::query_as::<_, LaunchChange>(
+            "SELECT c.kind, c.old_value, c.new_value, c.changed_at FROM launch_changes c
+             JOIN launches l ON l.launch_id = c.launch_id
+             WHERE l.launch_id = $1 OR l.merged_into = $1
+             ORDER BY c.changed_at, c.id"
+        )
+        .bind(&launch.launch_id)
+        .fetch_all(&self.pool).await?;
+
+        Ok(Some(launch))
+    }
+}
@@ -1,11 +1,70 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
 use async_trait::async_trait;
+use futures_util::stream::{BoxStream, StreamExt, TryStreamExt};
+use chrono::{DateTime, Utc};
 use sqlx::{PgPool, Row};
 use serde_json::Value;
 
-use crate::domain::models::OsdrItem;
+use crate::domain::models::{
+    HttpValidators, IsaAnnotation, IsaArchive, IsaAssay, IsaProtocol, IsaSample, MonthlyCount, OsdrCrawlState, OsdrDataset, OsdrDetailCache, OsdrFile, OsdrGraph, OsdrGraphEdge, OsdrGraphNode, OsdrHttpCache, OsdrItem,
+    OsdrMirrorFilter, OsdrRelated, OsdrRemoteFile, OsdrSnapshot, OsdrStats, OsdrSyncRun, OsdrSyncStats, StatBucket,
+};
 use crate::domain::errors::AppError;
 use crate::domain::contracts::OsdrRepoContract; // <--- Новый импорт
 use crate::utils::json_tools; // Ваш модуль для парсинга JSON
+use crate::utils::hash;
+
+/// Колонки osdr_items, из которых собирается OsdrItem
+const OSDR_ITEM_COLUMNS: &str =
+    "id, dataset_id, title, status, updated_at, inserted_at, raw, last_seen_at, withdrawn_at,
+     organism, mission, project_type, assay_technology, factors, release_date, doi";
+
+/// Измерения статистики — типизированные колонки osdr_items
+const STAT_STATUS: &str = "status";
+const STAT_ORGANISM: &str = "organism";
+const STAT_MISSION: &str = "mission";
+const STAT_ASSAY_TYPE: &str = "assay_technology";
+const STAT_PROJECT: &str = "project_type";
+
+/// Веса общих атрибутов для связанных датасетов: (метка, колонка osdr_items, вес)
+const RELATED_WEIGHTS: &[(&str, &str, i32)] = &[
+    ("publication", "doi", 5),
+    ("mission", "mission", 3),
+    ("organism", "organism", 2),
+    ("assay_type", "assay_technology", 2),
+    ("project_type", "project_type", 1),
+];
+
+/// Вес каждого общего фактора эксперимента
+const RELATED_FACTOR_WEIGHT: i32 = 1;
+
+/// SQL-выражения (вес, список общих атрибутов) для пары записей osdr_items с алиасами a и b
+fn related_exprs() -> (String, String) {
+    let same = |col: &str| format!("lower(NULLIF(a.{col}, '')) = lower(NULLIF(b.{col}, ''))");
+    let factors = "SELECT lower(f) FROM unnest(a.factors) f INTERSECT SELECT lower(f) FROM unnest(b.factors) f";
+
+    let score = RELATED_WEIGHTS.iter()
+        .map(|(_, col, w)| format!("CASE WHEN {} THEN {w} ELSE 0 END", same(col)))
+        .chain(std::iter::once(format!("cardinality(ARRAY({factors})) * {RELATED_FACTOR_WEIGHT}")))
+        .collect::<Vec<_>>()
+        .join(" + ");
+    let labels = RELATED_WEIGHTS.iter()
+        .map(|(label, col, _)| format!("CASE WHEN {} THEN '{label}' END", same(col)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let shared = format!("array_remove(ARRAY[{labels}]::TEXT[], NULL) || ARRAY(SELECT 'factor:' || f FROM ({factors}) s(f))");
+
+    (score, shared)
+}
+
+/// Колонки osdr_files, из которых собирается OsdrFile
+const OSDR_FILE_COLUMNS: &str =
+    "id, dataset_id, file_name, remote_url, local_path, size_bytes, downloaded_bytes, sha256, state, error, updated_at";
+
+/// Варианты ключа dataset_id в сырых записях OSDR
+pub const OSDR_ID_KEYS: &[&str] = &["dataset_id", "id", "uuid", "studyId", "accession", "osdr_id"];
 
 // =========================================================================================
 // 1. СТРУКТУРА РЕПОЗИТОРИЯ
@@ -20,6 +79,23 @@ pub fn new_osdr_repo(pool: PgPool) -> OsdrRepo {
     OsdrRepo { pool }
 }
 
+impl OsdrRepo {
+    /// Количество живых (не отозванных) датасетов по значению измерения
+    async fn count_by(&self, expr: &str) -> Result<Vec<StatBucket>, AppError> {
+        let buckets = sqlx::query_as::<_, StatBucket>(&format!(
+            "SELECT COALESCE(NULLIF({expr}, ''), 'unknown') AS key, count(*) AS count
+             FROM osdr_items
+             WHERE withdrawn_at IS NULL
+             GROUP BY 1
+             ORDER BY 2 DESC, 1
+             LIMIT 50"
+        ))
+        .fetch_all(&self.pool).await?;
+
+        Ok(buckets)
+    }
+}
+
 // =========================================================================================
 // 2. РЕАЛИЗАЦИЯ КОНТРАКТА (REPOSITORY LOGIC)
 // =========================================================================================
@@ -33,33 +109,48 @@ impl OsdrRepoContract for OsdrRepo {
         // Мы будем выполнять UPSERT для каждого элемента в списке
         for item in items {
             // Извлечение бизнес-ключей и полей из JSON
-            let id = json_tools::s_pick(item, &["dataset_id", "id", "uuid", "studyId", "accession", "osdr_id"]);
+            let id = json_tools::s_pick(item, OSDR_ID_KEYS);
             let title = json_tools::s_pick(item, &["title", "name", "label"]);
             let status = json_tools::s_pick(item, &["status", "state", "lifecycle"]);
             let updated = json_tools::t_pick(item, &["updated", "updated_at", "modified", "lastUpdated", "timestamp"]);
+            let content_hash = hash::json_hash(item);
+            let ds = OsdrDataset::from_raw(item);
 
             // ВАЖНО: Мы используем dataset_id как уникальный ключ для ON CONFLICT.
             if let Some(ds_id) = id.clone() {
                 // Использование UPSERT (ON CONFLICT)
-                // Если dataset_id уже есть, обновляем все поля (title, status, updated_at, raw).
+                // Если dataset_id уже есть, обновляем все поля (title, status, updated_at, raw, типизированные).
                 sqlx::query(
-                    "INSERT INTO osdr_items(dataset_id, title, status, updated_at, raw)
-                     VALUES($1,$2,$3,$4,$5)
+                    "INSERT INTO osdr_items(dataset_id, title, status, updated_at, raw, content_hash,
+                                            organism, mission, project_type, assay_technology, factors,
+                                            release_date, doi, extractor_version)
+                     VALUES($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,$13,$14)
                      ON CONFLICT (dataset_id) DO UPDATE
                      SET title=EXCLUDED.title, status=EXCLUDED.status,
-                         updated_at=EXCLUDED.updated_at, raw=EXCLUDED.raw"
+                         updated_at=EXCLUDED.updated_at, raw=EXCLUDED.raw,
+                         content_hash=EXCLUDED.content_hash,
+                         organism=EXCLUDED.organism, mission=EXCLUDED.mission,
+                         project_type=EXCLUDED.project_type, assay_technology=EXCLUDED.assay_technology,
+                         factors=EXCLUDED.factors, release_date=EXCLUDED.release_date,
+                         doi=EXCLUDED.doi, extractor_version=EXCLUDED.extractor_version"
                 )
-                .bind(ds_id).bind(title).bind(status).bind(updated).bind(item)
+                .bind(ds_id).bind(title).bind(status).bind(updated).bind(item).bind(content_hash)
+                .bind(ds.organism).bind(ds.mission).bind(ds.project_type).bind(ds.assay_technology)
+                .bind(ds.factors).bind(ds.release_date).bind(ds.doi).bind(OsdrDataset::EXTRACTOR_VERSION)
                 .execute(&self.pool).await
                 .map(|_| ())?; // Преобразуем результат в ()
             } else {
                 // Если dataset_id отсутствует (редкий случай), просто вставляем
                 // без гарантии уникальности (зависит от схемы БД).
                 sqlx::query(
-                    "INSERT INTO osdr_items(dataset_id, title, status, updated_at, raw)
-                     VALUES($1,$2,$3,$4,$5)"
+                    "INSERT INTO osdr_items(dataset_id, title, status, updated_at, raw, content_hash,
+                                            organism, mission, project_type, assay_technology, factors,
+                                            release_date, doi, extractor_version)
+                     VALUES($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,$13,$14)"
                 )
-                .bind::<Option<String>>(None).bind(title).bind(status).bind(updated).bind(item)
+                .bind::<Option<String>>(None).bind(title).bind(status).bind(updated).bind(item).bind(content_hash)
+                .bind(ds.organism).bind(ds.mission).bind(ds.project_type).bind(ds.assay_technology)
+                .bind(ds.factors).bind(ds.release_date).bind(ds.doi).bind(OsdrDataset::EXTRACTOR_VERSION)
                 .execute(&self.pool).await
                 .map(|_| ())?;
             }
@@ -67,18 +158,574 @@ impl OsdrRepoContract for OsdrRepo {
         Ok(())
     }
 
+    /// Переизвлекает типизированные поля у строк, извлеченных старой версией экстрактора
+    /// (или до появления колонок). Идет пачками, чтобы не держать всю таблицу в памяти.
+    async fn backfill_datasets(&self) -> Result<u64, AppError> {
+        let mut total = 0u64;
+        loop {
+            let rows = sqlx::query(
+                "SELECT id, raw FROM osdr_items
+                 WHERE extractor_version IS DISTINCT FROM $1
+                 ORDER BY id
+                 LIMIT 500"
+            )
+            .bind(OsdrDataset::EXTRACTOR_VERSION)
+            .fetch_all(&self.pool).await?;
+            if rows.is_empty() {
+                break;
+            }
+
+            for r in &rows {
+                let id: i64 = r.get("id");
+                let ds = OsdrDataset::from_raw(&r.get::<Value, _>("raw"));
+                sqlx::query(
+                    "UPDATE osdr_items
+                     SET organism=$2, mission=$3, project_type=$4, assay_technology=$5,
+                         factors=$6, release_date=$7, doi=$8, extractor_version=$9
+                     WHERE id = $1"
+                )
+                .bind(id).bind(ds.organism).bind(ds.mission).bind(ds.project_type).bind(ds.assay_technology)
+                .bind(ds.factors).bind(ds.release_date).bind(ds.doi).bind(OsdrDataset::EXTRACTOR_VERSION)
+                .execute(&self.pool).await?;
+            }
+            total += rows.len() as u64;
+        }
+        Ok(total)
+    }
+
+    async fn get_content_hashes(&self, dataset_ids: &[String]) -> Result<HashMap<String, String>, AppError> {
+        let rows = sqlx::query(
+            "SELECT dataset_id, content_hash
+             FROM osdr_items
+             WHERE dataset_id = ANY($1) AND content_hash IS NOT NULL"
+        )
+        .bind(dataset_ids)
+        .fetch_all(&self.pool).await?;
+
+        Ok(rows.into_iter()
+            .map(|r| (r.get("dataset_id"), r.get("content_hash")))
+            .collect())
+    }
+
+    /// Отмечает записи как увиденные в фиде; ранее отозванные восстанавливаются.
+    /// Возвращает число восстановленных записей.
+    async fn mark_seen(&self, dataset_ids: &[String]) -> Result<i64, AppError> {
+        let restored: i64 = sqlx::query(
+            "WITH touched AS (
+                 UPDATE osdr_items o
+                 SET last_seen_at = now(), withdrawn_at = NULL
+                 FROM osdr_items prev
+                 WHERE o.id = prev.id AND o.dataset_id = ANY($1)
+                 RETURNING prev.withdrawn_at
+             )
+             SELECT count(*) FILTER (WHERE withdrawn_at IS NOT NULL) AS c FROM touched"
+        )
+        .bind(dataset_ids)
+        .fetch_one(&self.pool).await
+        .map(|r| r.get("c"))?;
+        Ok(restored)
+    }
+
+    /// Помечает отозванными записи, не увиденные с начала полной синхронизации.
+    /// Если за синхронизацию не увидено ни одной записи (пустой ответ upstream),
+    /// ничего не помечается.
+    async fn mark_withdrawn(&self, seen_since: DateTime<Utc>) -> Result<i64, AppError> {
+        let result = sqlx::query(
+            "UPDATE osdr_items
+             SET withdrawn_at = now()
+             WHERE withdrawn_at IS NULL
+               AND dataset_id IS NOT NULL
+               AND (last_seen_at IS NULL OR last_seen_at < $1)
+               AND EXISTS (SELECT 1 FROM osdr_items WHERE last_seen_at >= $1)"
+        )
+        .bind(seen_since)
+        .execute(&self.pool).await?;
+        Ok(result.rows_affected() as i64)
+    }
+
+    async fn get_http_cache(&self, url: &str) -> Result<Option<OsdrHttpCache>, AppError> {
+        let cached = sqlx::query_as::<_, OsdrHttpCache>(
+            "SELECT etag, last_modified, next_url, dataset_ids
+             FROM osdr_http_cache
+             WHERE url = $1"
+        )
+        .bind(url)
+        .fetch_optional(&self.pool).await?;
+
+        Ok(cached)
+    }
+
+    async fn save_http_cache(
+        &self,
+        url: &str,
+        validators: &HttpValidators,
+        next_url: Option<&str>,
+        dataset_ids: &[String],
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO osdr_http_cache(url, etag, last_modified, next_url, dataset_ids, checked_at)
+             VALUES($1,$2,$3,$4,$5,now())
+             ON CONFLICT (url) DO UPDATE
+             SET etag=EXCLUDED.etag, last_modified=EXCLUDED.last_modified,
+                 next_url=EXCLUDED.next_url, dataset_ids=EXCLUDED.dataset_ids,
+                 checked_at=EXCLUDED.checked_at"
+        )
+        .bind(url).bind(&validators.etag).bind(&validators.last_modified).bind(next_url).bind(dataset_ids)
+        .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn record_sync(&self, mode: &str, started_at: DateTime<Utc>, stats: &OsdrSyncStats) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO osdr_sync_log(mode, started_at, finished_at, pages_fetched, pages_not_modified,
+                                       items_fetched, items_unchanged, items_written,
+                                       items_withdrawn, items_restored)
+             VALUES($1,$2,now(),$3,$4,$5,$6,$7,$8,$9)"
+        )
+        .bind(mode).bind(started_at)
+        .bind(stats.pages_fetched).bind(stats.pages_not_modified)
+        .bind(stats.items_fetched).bind(stats.items_unchanged).bind(stats.items_written)
+        .bind(stats.items_withdrawn).bind(stats.items_restored)
+        .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn get_sync_log(&self, limit: i64) -> Result<Vec<OsdrSyncRun>, AppError> {
+        let runs = sqlx::query_as::<_, OsdrSyncRun>(
+            "SELECT id, mode, started_at, finished_at, pages_fetched, pages_not_modified,
+                    items_fetched, items_unchanged, items_written, items_withdrawn, items_restored
+             FROM osdr_sync_log
+             ORDER BY id DESC
+             LIMIT $1"
+        )
+        .bind(limit)
+        .fetch_all(&self.pool).await?;
+
+        Ok(runs)
+    }
+
     /// Получить список элементов OSDR (как было в get_list)
-    async fn get_list(&self, limit: i64) -> Result<Vec<OsdrItem>, AppError> {
-        let items = sqlx::query_as::<_, OsdrItem>(
-            "SELECT id, dataset_id, title, status, updated_at, inserted_at, raw
+    /// Отозванные upstream записи (withdrawn_at) скрыты, если не запрошены явно.
+    async fn get_list(&self, limit: i64, include_withdrawn: bool) -> Result<Vec<OsdrItem>, AppError> {
+        let items = sqlx::query_as::<_, OsdrItem>(&format!(
+            "SELECT {OSDR_ITEM_COLUMNS}
              FROM osdr_items
+             WHERE $2 OR withdrawn_at IS NULL
              ORDER BY inserted_at DESC
              LIMIT $1"
+        ))
+        .bind(limit).bind(include_withdrawn)
+        .fetch_all(&self.pool).await?;
+
+        Ok(items)
+    }
+
+    async fn get_by_dataset_id(&self, dataset_id: &str) -> Result<Option<OsdrItem>, AppError> {
+        let item = sqlx::query_as::<_, OsdrItem>(&format!(
+            "SELECT {OSDR_ITEM_COLUMNS}
+             FROM osdr_items
+             WHERE dataset_id = $1"
+        ))
+        .bind(dataset_id)
+        .fetch_optional(&self.pool).await?;
+
+        Ok(item)
+    }
+
+    async fn get_detail_cache(&self, dataset_id: &str) -> Result<Option<OsdrDetailCache>, AppError> {
+        let cached = sqlx::query_as::<_, OsdrDetailCache>(
+            "SELECT dataset_id, fetched_at, payload
+             FROM osdr_detail_cache
+             WHERE dataset_id = $1"
+        )
+        .bind(dataset_id)
+        .fetch_optional(&self.pool).await?;
+
+        Ok(cached)
+    }
+
+    /// UPSERT по dataset_id: в кэше всегда одна, самая свежая копия.
+    async fn save_detail_cache(&self, dataset_id: &str, payload: &Value) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO osdr_detail_cache(dataset_id, fetched_at, payload)
+             VALUES($1, now(), $2)
+             ON CONFLICT (dataset_id) DO UPDATE
+             SET fetched_at=EXCLUDED.fetched_at, payload=EXCLUDED.payload"
+        )
+        .bind(dataset_id).bind(payload)
+        .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn get_crawl_state(&self, crawl: &str) -> Result<Option<OsdrCrawlState>, AppError> {
+        let state = sqlx::query_as::<_, OsdrCrawlState>(
+            "SELECT crawl, status, next_url, pages_done, items_done,
+                    started_at, updated_at, finished_at, last_error
+             FROM osdr_crawl_state
+             WHERE crawl = $1"
+        )
+        .bind(crawl)
+        .fetch_optional(&self.pool).await?;
+
+        Ok(state)
+    }
+
+    async fn save_crawl_state(&self, state: &OsdrCrawlState) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO osdr_crawl_state(crawl, status, next_url, pages_done, items_done,
+                                          started_at, updated_at, finished_at, last_error)
+             VALUES($1,$2,$3,$4,$5,$6,$7,$8,$9)
+             ON CONFLICT (crawl) DO UPDATE
+             SET status=EXCLUDED.status, next_url=EXCLUDED.next_url,
+                 pages_done=EXCLUDED.pages_done, items_done=EXCLUDED.items_done,
+                 started_at=EXCLUDED.started_at, updated_at=EXCLUDED.updated_at,
+                 finished_at=EXCLUDED.finished_at, last_error=EXCLUDED.last_error"
+        )
+        .bind(&state.crawl).bind(&state.status).bind(&state.next_url)
+        .bind(state.pages_done).bind(state.items_done)
+        .bind(state.started_at).bind(state.updated_at)
+        .bind(state.finished_at).bind(&state.last_error)
+        .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn find_dataset_ids(&self, filter: &OsdrMirrorFilter) -> Result<Vec<String>, AppError> {
+        let ids = sqlx::query(
+            "SELECT dataset_id
+             FROM osdr_items
+             WHERE dataset_id IS NOT NULL AND withdrawn_at IS NULL
+               AND ($1::TEXT IS NULL OR title ILIKE '%' || $1 || '%')
+               AND ($2::TEXT IS NULL OR status = $2)
+             ORDER BY dataset_id
+             LIMIT $3"
+        )
+        .bind(&filter.q).bind(&filter.status).bind(filter.limit.unwrap_or(10))
+        .fetch_all(&self.pool).await?
+        .into_iter()
+        .map(|r| r.get("dataset_id"))
+        .collect();
+
+        Ok(ids)
+    }
+
+    /// Новые файлы встают в pending; у известных обновляется только URL/размер,
+    /// состояние скачивания сохраняется.
+    async fn upsert_file_listing(&self, dataset_id: &str, files: &[OsdrRemoteFile]) -> Result<(), AppError> {
+        for f in files {
+            sqlx::query(
+                "INSERT INTO osdr_files(dataset_id, file_name, remote_url, size_bytes, state)
+                 VALUES($1,$2,$3,$4,'pending')
+                 ON CONFLICT (dataset_id, file_name) DO UPDATE
+                 SET remote_url=EXCLUDED.remote_url,
+                     size_bytes=COALESCE(EXCLUDED.size_bytes, osdr_files.size_bytes)"
+            )
+            .bind(dataset_id).bind(&f.name).bind(&f.url).bind(f.size)
+            .execute(&self.pool).await?;
+        }
+        Ok(())
+    }
+
+    async fn get_files(&self, dataset_id: &str) -> Result<Vec<OsdrFile>, AppError> {
+        let files = sqlx::query_as::<_, OsdrFile>(&format!(
+            "SELECT {OSDR_FILE_COLUMNS}
+             FROM osdr_files
+             WHERE dataset_id = $1
+             ORDER BY file_name"
+        ))
+        .bind(dataset_id)
+        .fetch_all(&self.pool).await?;
+
+        Ok(files)
+    }
+
+    async fn get_file(&self, dataset_id: &str, file_name: &str) -> Result<Option<OsdrFile>, AppError> {
+        let file = sqlx::query_as::<_, OsdrFile>(&format!(
+            "SELECT {OSDR_FILE_COLUMNS}
+             FROM osdr_files
+             WHERE dataset_id = $1 AND file_name = $2"
+        ))
+        .bind(dataset_id).bind(file_name)
+        .fetch_optional(&self.pool).await?;
+
+        Ok(file)
+    }
+
+    async fn update_file(&self, file: &OsdrFile) -> Result<(), AppError> {
+        sqlx::query(
+            "UPDATE osdr_files
+             SET local_path=$2, size_bytes=$3, downloaded_bytes=$4, sha256=$5,
+                 state=$6, error=$7, updated_at=now()
+             WHERE id = $1"
+        )
+        .bind(file.id).bind(&file.local_path).bind(file.size_bytes).bind(file.downloaded_bytes)
+        .bind(&file.sha256).bind(&file.state).bind(&file.error)
+        .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn replace_isa(&self, dataset_id: &str, archive: &IsaArchive) -> Result<(), AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        // Дочерние таблицы очищаются каскадом
+        sqlx::query("DELETE FROM osdr_isa_investigations WHERE dataset_id = $1")
+            .bind(dataset_id)
+            .execute(&mut *tx).await?;
+        sqlx::query(
+            "INSERT INTO osdr_isa_investigations(dataset_id, identifier, title, source, imported_at)
+             VALUES($1,$2,$3,$4,now())"
+        )
+        .bind(dataset_id).bind(&archive.identifier).bind(&archive.title).bind(&archive.source)
+        .execute(&mut *tx).await?;
+
+        for p in &archive.protocols {
+            sqlx::query(
+                "INSERT INTO osdr_isa_protocols(dataset_id, name, protocol_type, description)
+                 VALUES($1,$2,$3,$4)
+                 ON CONFLICT (dataset_id, name) DO NOTHING"
+            )
+            .bind(dataset_id).bind(&p.name).bind(&p.protocol_type).bind(&p.description)
+            .execute(&mut *tx).await?;
+        }
+
+        for a in &archive.assays {
+            sqlx::query(
+                "INSERT INTO osdr_isa_assays(dataset_id, file_name, measurement_type, technology_type, technology_platform, sample_count)
+                 VALUES($1,$2,$3,$4,$5,$6)
+                 ON CONFLICT (dataset_id, file_name) DO NOTHING"
+            )
+            .bind(dataset_id).bind(&a.file_name).bind(&a.measurement_type)
+            .bind(&a.technology_type).bind(&a.technology_platform).bind(a.sample_count)
+            .execute(&mut *tx).await?;
+        }
+
+        for sample in &archive.samples {
+            let sample_id: i64 = sqlx::query(
+                "INSERT INTO osdr_isa_samples(dataset_id, sample_name, source_name, study_file, protocols)
+                 VALUES($1,$2,$3,$4,$5)
+                 ON CONFLICT (dataset_id, study_file, sample_name) DO UPDATE
+                 SET source_name=EXCLUDED.source_name, protocols=EXCLUDED.protocols
+                 RETURNING id"
+            )
+            .bind(dataset_id).bind(&sample.sample_name).bind(&sample.source_name)
+            .bind(&sample.study_file).bind(&sample.protocols)
+            .fetch_one(&mut *tx).await
+            .map(|r| r.get("id"))?;
+
+            for (table, annotations) in [
+                ("osdr_isa_characteristics", &sample.characteristics),
+                ("osdr_isa_factor_values", &sample.factor_values),
+            ] {
+                for a in annotations {
+                    sqlx::query(&format!(
+                        "INSERT INTO {table}(sample_id, category, value, unit) VALUES($1,$2,$3,$4)"
+                    ))
+                    .bind(sample_id).bind(&a.category).bind(&a.value).bind(&a.unit)
+                    .execute(&mut *tx).await?;
+                }
+            }
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn get_isa(&self, dataset_id: &str) -> Result<Option<IsaArchive>, AppError> {
+        let Some(inv) = sqlx::query(
+            "SELECT identifier, title, source FROM osdr_isa_investigations WHERE dataset_id = $1"
+        )
+        .bind(dataset_id)
+        .fetch_optional(&self.pool).await? else {
+            return Ok(None);
+        };
+
+        let protocols = sqlx::query_as::<_, IsaProtocol>(
+            "SELECT name, protocol_type, description FROM osdr_isa_protocols WHERE dataset_id = $1 ORDER BY id"
+        )
+        .bind(dataset_id)
+        .fetch_all(&self.pool).await?;
+
+        let assays = sqlx::query_as::<_, IsaAssay>(
+            "SELECT file_name, measurement_type, technology_type, technology_platform, sample_count
+             FROM osdr_isa_assays WHERE dataset_id = $1 ORDER BY file_name"
+        )
+        .bind(dataset_id)
+        .fetch_all(&self.pool).await?;
+
+        // Аннотации образцов: sample_id -> (characteristics, factor values)
+        let mut annotations: HashMap<i64, (Vec<IsaAnnotation>, Vec<IsaAnnotation>)> = HashMap::new();
+        for (table, is_factor) in [("osdr_isa_characteristics", false), ("osdr_isa_factor_values", true)] {
+            let rows = sqlx::query(&format!(
+                "SELECT a.sample_id, a.category, a.value, a.unit
+                 FROM {table} a JOIN osdr_isa_samples s ON s.id = a.sample_id
+                 WHERE s.dataset_id = $1
+                 ORDER BY a.id"
+            ))
+            .bind(dataset_id)
+            .fetch_all(&self.pool).await?;
+
+            for r in rows {
+                let entry = annotations.entry(r.get("sample_id")).or_default();
+                let target = if is_factor { &mut entry.1 } else { &mut entry.0 };
+                target.push(IsaAnnotation {
+                    category: r.get("category"),
+                    value: r.get("value"),
+                    unit: r.get("unit"),
+                });
+            }
+        }
+
+        let samples = sqlx::query(
+            "SELECT id, sample_name, source_name, study_file, protocols
+             FROM osdr_isa_samples WHERE dataset_id = $1 ORDER BY study_file, id"
+        )
+        .bind(dataset_id)
+        .fetch_all(&self.pool).await?
+        .into_iter()
+        .map(|r| {
+            let (characteristics, factor_values) = annotations.remove(&r.get::<i64, _>("id")).unwrap_or_default();
+            IsaSample {
+                sample_name: r.get("sample_name"),
+                source_name: r.get("source_name"),
+                study_file: r.get("study_file"),
+                protocols: r.get("protocols"),
+                characteristics,
+                factor_values,
+            }
+        })
+        .collect();
+
+        Ok(Some(IsaArchive {
+            identifier: inv.get("identifier"),
+            title: inv.get("title"),
+            source: inv.get("source"),
+            protocols,
+            samples,
+            assays,
+        }))
+    }
+
+    async fn get_related(&self, dataset_id: &str, limit: i64) -> Result<Vec<OsdrRelated>, AppError> {
+        let (score, shared) = related_exprs();
+        let related = sqlx::query_as::<_, OsdrRelated>(&format!(
+            "SELECT dataset_id, title, score, shared FROM (
+                 SELECT b.dataset_id, b.title, ({score}) AS score, ({shared}) AS shared
+                 FROM osdr_items a
+                 JOIN osdr_items b ON b.dataset_id <> a.dataset_id AND b.dataset_id IS NOT NULL AND b.withdrawn_at IS NULL
+                 WHERE a.dataset_id = $1
+             ) r
+             WHERE score > 0
+             ORDER BY score DESC, dataset_id
+             LIMIT $2"
+        ))
+        .bind(dataset_id).bind(limit)
+        .fetch_all(&self.pool).await?;
+
+        Ok(related)
+    }
+
+    async fn get_graph(&self, min_weight: i32) -> Result<OsdrGraph, AppError> {
+        let nodes = sqlx::query_as::<_, OsdrGraphNode>(
+            "SELECT dataset_id, title, organism, mission, assay_technology
+             FROM osdr_items
+             WHERE dataset_id IS NOT NULL AND withdrawn_at IS NULL
+             ORDER BY dataset_id"
+        )
+        .fetch_all(&self.pool).await?;
+
+        // Каждая пара один раз (a < b)
+        let (score, shared) = related_exprs();
+        let edges = sqlx::query_as::<_, OsdrGraphEdge>(&format!(
+            "SELECT source, target, weight, shared FROM (
+                 SELECT a.dataset_id AS source, b.dataset_id AS target, ({score}) AS weight, ({shared}) AS shared
+                 FROM osdr_items a
+                 JOIN osdr_items b ON a.dataset_id < b.dataset_id
+                 WHERE a.dataset_id IS NOT NULL AND a.withdrawn_at IS NULL
+                   AND b.dataset_id IS NOT NULL AND b.withdrawn_at IS NULL
+             ) e
+             WHERE weight >= $1
+             ORDER BY weight DESC, source, target"
+        ))
+        .bind(min_weight.max(1))
+        .fetch_all(&self.pool).await?;
+
+        Ok(OsdrGraph { nodes, edges, computed_at: Utc::now() })
+    }
+
+    async fn save_snapshot(&self, snapshot: &OsdrSnapshot) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO osdr_snapshots(id, created_at, item_count, sha256, size_bytes, file_path)
+             VALUES($1,$2,$3,$4,$5,$6)
+             ON CONFLICT (id) DO UPDATE
+             SET created_at=EXCLUDED.created_at, item_count=EXCLUDED.item_count, sha256=EXCLUDED.sha256,
+                 size_bytes=EXCLUDED.size_bytes, file_path=EXCLUDED.file_path"
+        )
+        .bind(&snapshot.id).bind(snapshot.created_at).bind(snapshot.item_count)
+        .bind(&snapshot.sha256).bind(snapshot.size_bytes).bind(&snapshot.file_path)
+        .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn get_snapshots(&self, limit: i64) -> Result<Vec<OsdrSnapshot>, AppError> {
+        let snapshots = sqlx::query_as::<_, OsdrSnapshot>(
+            "SELECT id, created_at, item_count, sha256, size_bytes, file_path
+             FROM osdr_snapshots
+             ORDER BY created_at DESC
+             LIMIT $1"
         )
         .bind(limit)
         .fetch_all(&self.pool).await?;
 
-        Ok(items)
+        Ok(snapshots)
+    }
+
+    async fn get_snapshot(&self, id: &str) -> Result<Option<OsdrSnapshot>, AppError> {
+        let snapshot = sqlx::query_as::<_, OsdrSnapshot>(
+            "SELECT id, created_at, item_count, sha256, size_bytes, file_path
+             FROM osdr_snapshots
+             WHERE id = $1"
+        )
+        .bind(id)
+        .fetch_optional(&self.pool).await?;
+
+        Ok(snapshot)
+    }
+
+    async fn get_stats(&self) -> Result<OsdrStats, AppError> {
+        let totals = sqlx::query(
+            "SELECT count(*) FILTER (WHERE withdrawn_at IS NULL) AS total,
+                    count(*) FILTER (WHERE withdrawn_at IS NOT NULL) AS withdrawn
+             FROM osdr_items"
+        )
+        .fetch_one(&self.pool).await?;
+
+        // Новые (inserted_at) и обновленные upstream (updated_at) живые датасеты по месяцам
+        let monthly = sqlx::query_as::<_, MonthlyCount>(
+            "WITH ins AS (
+                 SELECT date_trunc('month', inserted_at) AS month, count(*) AS c
+                 FROM osdr_items WHERE withdrawn_at IS NULL GROUP BY 1
+             ), upd AS (
+                 SELECT date_trunc('month', updated_at) AS month, count(*) AS c
+                 FROM osdr_items WHERE withdrawn_at IS NULL AND updated_at IS NOT NULL GROUP BY 1
+             )
+             SELECT COALESCE(ins.month, upd.month) AS month,
+                    COALESCE(ins.c, 0) AS new_datasets,
+                    COALESCE(upd.c, 0) AS updated_datasets
+             FROM ins FULL OUTER JOIN upd ON ins.month = upd.month
+             ORDER BY 1"
+        )
+        .fetch_all(&self.pool).await?;
+
+        Ok(OsdrStats {
+            total: totals.get("total"),
+            withdrawn: totals.get("withdrawn"),
+            by_status: self.count_by(STAT_STATUS).await?,
+            by_organism: self.count_by(STAT_ORGANISM).await?,
+            by_mission: self.count_by(STAT_MISSION).await?,
+            by_assay_type: self.count_by(STAT_ASSAY_TYPE).await?,
+            by_project: self.count_by(STAT_PROJECT).await?,
+            monthly,
+            computed_at: Utc::now(),
+        })
     }
 
     /// Получить количество элементов (как было в get_count)
@@ -89,9 +736,16 @@ impl OsdrRepoContract for OsdrRepo {
         Ok(count)
     }
 
-    async fn get_all(&self) -> Result<Vec<OsdrItem>, AppError> {
-        // Временная заглушка
-        Ok(vec![])
+    fn stream_live(&self) -> BoxStream<'_, Result<OsdrItem, AppError>> {
+        // fetch держит ссылку на текст запроса все время чтения — строка должна жить 'static
+        static SQL: OnceLock<String> = OnceLock::new();
+        let sql = SQL.get_or_init(|| format!(
+            "SELECT {OSDR_ITEM_COLUMNS} FROM osdr_items WHERE withdrawn_at IS NULL ORDER BY dataset_id"
+        ));
+        sqlx::query_as::<_, OsdrItem>(sql)
+            .fetch(&self.pool)
+            .map_err(AppError::from)
+            .boxed()
     }
     
     async fn save(&self, _osdr: &OsdrItem) -> Result<(), AppError> {
@@ -43,6 +43,567 @@ pub async fn init_db(pool: &PgPool) -> Result<(), AppError> {
          ON osdr_items(dataset_id) WHERE dataset_id IS NOT NULL"
     ).execute(pool).await?;
 
+    sqlx::query("ALTER TABLE osdr_items ADD COLUMN IF NOT EXISTS content_hash TEXT")
+        .execute(pool).await?;
+    sqlx::query("ALTER TABLE osdr_items ADD COLUMN IF NOT EXISTS last_seen_at TIMESTAMPTZ")
+        .execute(pool).await?;
+    sqlx::query("ALTER TABLE osdr_items ADD COLUMN IF NOT EXISTS withdrawn_at TIMESTAMPTZ")
+        .execute(pool).await?;
+    // типизированные метаданные датасета (OsdrDataset)
+    sqlx::query(
+        "ALTER TABLE osdr_items
+            ADD COLUMN IF NOT EXISTS organism TEXT,
+            ADD COLUMN IF NOT EXISTS mission TEXT,
+            ADD COLUMN IF NOT EXISTS project_type TEXT,
+            ADD COLUMN IF NOT EXISTS assay_technology TEXT,
+            ADD COLUMN IF NOT EXISTS factors TEXT[] NOT NULL DEFAULT '{}',
+            ADD COLUMN IF NOT EXISTS release_date DATE,
+            ADD COLUMN IF NOT EXISTS doi TEXT,
+            ADD COLUMN IF NOT EXISTS extractor_version INT"
+    ).execute(pool).await?;
+
+    // валидаторы HTTP (ETag / Last-Modified) страниц OSDR
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS osdr_http_cache(
+            url TEXT PRIMARY KEY,
+            etag TEXT,
+            last_modified TEXT,
+            next_url TEXT,
+            checked_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )"
+    ).execute(pool).await?;
+    // dataset_id со страницы: при 304 они тоже считаются увиденными
+    sqlx::query("ALTER TABLE osdr_http_cache ADD COLUMN IF NOT EXISTS dataset_ids TEXT[] NOT NULL DEFAULT '{}'")
+        .execute(pool).await?;
+
+    // журнал синхронизаций OSDR (метрики сэкономленной работы)
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS osdr_sync_log(
+            id BIGSERIAL PRIMARY KEY,
+            mode TEXT NOT NULL,
+            started_at TIMESTAMPTZ NOT NULL,
+            finished_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+            pages_fetched INT NOT NULL DEFAULT 0,
+            pages_not_modified INT NOT NULL DEFAULT 0,
+            items_fetched BIGINT NOT NULL DEFAULT 0,
+            items_unchanged BIGINT NOT NULL DEFAULT 0,
+            items_written BIGINT NOT NULL DEFAULT 0
+        )"
+    ).execute(pool).await?;
+    sqlx::query("ALTER TABLE osdr_sync_log ADD COLUMN IF NOT EXISTS items_withdrawn BIGINT NOT NULL DEFAULT 0")
+        .execute(pool).await?;
+    sqlx::query("ALTER TABLE osdr_sync_log ADD COLUMN IF NOT EXISTS items_restored BIGINT NOT NULL DEFAULT 0")
+        .execute(pool).await?;
+
+    // кэш детальных метаданных OSDR (REST_URL)
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS osdr_detail_cache(
+            dataset_id TEXT PRIMARY KEY,
+            fetched_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+            payload JSONB NOT NULL
+        )"
+    ).execute(pool).await?;
+
+    // чекпоинт полного обхода OSDR
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS osdr_crawl_state(
+            crawl TEXT PRIMARY KEY,
+            status TEXT NOT NULL,
+            next_url TEXT,
+            pages_done INT NOT NULL DEFAULT 0,
+            items_done BIGINT NOT NULL DEFAULT 0,
+            started_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+            finished_at TIMESTAMPTZ,
+            last_error TEXT
+        )"
+    ).execute(pool).await?;
+
+    // зеркало файлов OSDR
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS osdr_files(
+            id BIGSERIAL PRIMARY KEY,
+            dataset_id TEXT NOT NULL,
+            file_name TEXT NOT NULL,
+            remote_url TEXT NOT NULL,
+            local_path TEXT,
+            size_bytes BIGINT,
+            downloaded_bytes BIGINT NOT NULL DEFAULT 0,
+            sha256 TEXT,
+            state TEXT NOT NULL DEFAULT 'pending',
+            error TEXT,
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+            UNIQUE(dataset_id, file_name)
+        )"
+    ).execute(pool).await?;
+
+    // ISA-Tab метаданные датасетов OSDR (нормализованные)
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS osdr_isa_investigations(
+            dataset_id TEXT PRIMARY KEY,
+            identifier TEXT,
+            title TEXT,
+            source TEXT NOT NULL,
+            imported_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )"
+    ).execute(pool).await?;
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS osdr_isa_protocols(
+            id BIGSERIAL PRIMARY KEY,
+            dataset_id TEXT NOT NULL REFERENCES osdr_isa_investigations(dataset_id) ON DELETE CASCADE,
+            name TEXT NOT NULL,
+            protocol_type TEXT,
+            description TEXT,
+            UNIQUE(dataset_id, name)
+        )"
+    ).execute(pool).await?;
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS osdr_isa_samples(
+            id BIGSERIAL PRIMARY KEY,
+            dataset_id TEXT NOT NULL REFERENCES osdr_isa_investigations(dataset_id) ON DELETE CASCADE,
+            sample_name TEXT NOT NULL,
+            source_name TEXT,
+            study_file TEXT NOT NULL,
+            protocols TEXT[] NOT NULL DEFAULT '{}',
+            UNIQUE(dataset_id, study_file, sample_name)
+        )"
+    ).execute(pool).await?;
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS osdr_isa_characteristics(
+            id BIGSERIAL PRIMARY KEY,
+            sample_id BIGINT NOT NULL REFERENCES osdr_isa_samples(id) ON DELETE CASCADE,
+            category TEXT NOT NULL,
+            value TEXT NOT NULL,
+            unit TEXT
+        )"
+    ).execute(pool).await?;
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS osdr_isa_factor_values(
+            id BIGSERIAL PRIMARY KEY,
+            sample_id BIGINT NOT NULL REFERENCES osdr_isa_samples(id) ON DELETE CASCADE,
+            category TEXT NOT NULL,
+            value TEXT NOT NULL,
+            unit TEXT
+        )"
+    ).execute(pool).await?;
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS osdr_isa_assays(
+            id BIGSERIAL PRIMARY KEY,
+            dataset_id TEXT NOT NULL REFERENCES osdr_isa_investigations(dataset_id) ON DELETE CASCADE,
+            file_name TEXT NOT NULL,
+            measurement_type TEXT,
+            technology_type TEXT,
+            technology_platform TEXT,
+            sample_count INT NOT NULL DEFAULT 0,
+            UNIQUE(dataset_id, file_name)
+        )"
+    ).execute(pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS ix_osdr_isa_char_sample ON osdr_isa_characteristics(sample_id)").execute(pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS ix_osdr_isa_factor_sample ON osdr_isa_factor_values(sample_id)").execute(pool).await?;
+
+    // снимки каталога OSDR (файлы ndjson.gz в osdr_snapshot_dir)
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS osdr_snapshots(
+            id TEXT PRIMARY KEY,
+            created_at TIMESTAMPTZ NOT NULL,
+            item_count BIGINT NOT NULL,
+            sha256 TEXT NOT NULL,
+            size_bytes BIGINT NOT NULL,
+            file_path TEXT NOT NULL
+        )"
+    ).execute(pool).await?;
+
+    // архив APOD: одна запись на дату
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS apod_entries(
+            date DATE PRIMARY KEY,
+            title TEXT,
+            explanation TEXT,
+            media_type TEXT,
+            url TEXT,
+            hdurl TEXT,
+            thumbnail_url TEXT,
+            copyright TEXT,
+            raw JSONB NOT NULL,
+            fetched_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )"
+    ).execute(pool).await?;
+
+    // полнотекстовый поиск APOD: заголовок весомее объяснения
+    sqlx::query(
+        "ALTER TABLE apod_entries ADD COLUMN IF NOT EXISTS search tsvector
+         GENERATED ALWAYS AS (
+             setweight(to_tsvector('english', coalesce(title, '')), 'A') ||
+             setweight(to_tsvector('english', coalesce(explanation, '')), 'B')
+         ) STORED"
+    ).execute(pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS ix_apod_entries_search ON apod_entries USING GIN(search)").execute(pool).await?;
+
+    // известные пропуски архива APOD: даты, за которые upstream записи не вернул
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS apod_gaps(
+            date DATE PRIMARY KEY,
+            checked_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )"
+    ).execute(pool).await?;
+
+    // локальные копии изображений APOD
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS apod_media(
+            date DATE PRIMARY KEY,
+            source_url TEXT,
+            state TEXT NOT NULL,
+            original_path TEXT,
+            thumb_path TEXT,
+            medium_path TEXT,
+            content_type TEXT,
+            size_bytes BIGINT,
+            width INT,
+            height INT,
+            sha256 TEXT,
+            error TEXT,
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )"
+    ).execute(pool).await?;
+
+    sqlx::query("ALTER TABLE apod_media ADD COLUMN IF NOT EXISTS retry_after TIMESTAMPTZ")
+        .execute(pool).await?;
+
+    // NeoWs: объекты и их сближения
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS neo_objects(
+            neo_id TEXT PRIMARY KEY,
+            name TEXT,
+            nasa_jpl_url TEXT,
+            absolute_magnitude_h DOUBLE PRECISION,
+            diameter_min_m DOUBLE PRECISION,
+            diameter_max_m DOUBLE PRECISION,
+            is_hazardous BOOLEAN NOT NULL DEFAULT false,
+            is_sentry BOOLEAN NOT NULL DEFAULT false,
+            raw JSONB NOT NULL,
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )"
+    ).execute(pool).await?;
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS neo_approaches(
+            id BIGSERIAL PRIMARY KEY,
+            neo_id TEXT NOT NULL REFERENCES neo_objects(neo_id) ON DELETE CASCADE,
+            approach_at TIMESTAMPTZ NOT NULL,
+            approach_date DATE NOT NULL,
+            miss_distance_km DOUBLE PRECISION,
+            miss_distance_lunar DOUBLE PRECISION,
+            velocity_kms DOUBLE PRECISION,
+            orbiting_body TEXT NOT NULL,
+            UNIQUE(neo_id, approach_at, orbiting_body)
+        )"
+    ).execute(pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS ix_neo_approaches_at ON neo_approaches(approach_at)").execute(pool).await?;
+
+    // NeoWs lookup: орбита объекта (лента ее не отдает)
+    sqlx::query("ALTER TABLE neo_objects ADD COLUMN IF NOT EXISTS orbit_class TEXT")
+        .execute(pool).await?;
+    sqlx::query("ALTER TABLE neo_objects ADD COLUMN IF NOT EXISTS orbit_class_description TEXT")
+        .execute(pool).await?;
+    sqlx::query("ALTER TABLE neo_objects ADD COLUMN IF NOT EXISTS orbital_data JSONB")
+        .execute(pool).await?;
+    sqlx::query("ALTER TABLE neo_objects ADD COLUMN IF NOT EXISTS lookup_at TIMESTAMPTZ")
+        .execute(pool).await?;
+
+    // Тревоги по сближениям NEO: одна на объект и дату сближения
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS neo_alerts(
+            id BIGSERIAL PRIMARY KEY,
+            neo_id TEXT NOT NULL REFERENCES neo_objects(neo_id) ON DELETE CASCADE,
+            approach_date DATE NOT NULL,
+            approach_at TIMESTAMPTZ NOT NULL,
+            severity TEXT NOT NULL,
+            reasons TEXT[] NOT NULL,
+            miss_distance_lunar DOUBLE PRECISION,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+            evaluated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+            UNIQUE(neo_id, approach_date)
+        )"
+    ).execute(pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS ix_neo_alerts_at ON neo_alerts(approach_at)").execute(pool).await?;
+
+    // DONKI: солнечные вспышки (FLR) и корональные выбросы массы (CME) с анализами
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS donki_flares(
+            flr_id TEXT PRIMARY KEY,
+            begin_time TIMESTAMPTZ,
+            peak_time TIMESTAMPTZ,
+            end_time TIMESTAMPTZ,
+            class_type TEXT,
+            source_location TEXT,
+            active_region_num INT,
+            instruments TEXT[] NOT NULL DEFAULT '{}',
+            link TEXT,
+            raw JSONB NOT NULL,
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )"
+    ).execute(pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS ix_donki_flares_begin ON donki_flares(begin_time)").execute(pool).await?;
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS donki_cmes(
+            activity_id TEXT PRIMARY KEY,
+            start_time TIMESTAMPTZ,
+            source_location TEXT,
+            active_region_num INT,
+            catalog TEXT,
+            note TEXT,
+            link TEXT,
+            speed_kms DOUBLE PRECISION,
+            half_angle DOUBLE PRECISION,
+            cme_type TEXT,
+            latitude DOUBLE PRECISION,
+            longitude DOUBLE PRECISION,
+            raw JSONB NOT NULL,
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )"
+    ).execute(pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS ix_donki_cmes_start ON donki_cmes(start_time)").execute(pool).await?;
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS donki_cme_analyses(
+            id BIGSERIAL PRIMARY KEY,
+            activity_id TEXT NOT NULL REFERENCES donki_cmes(activity_id) ON DELETE CASCADE,
+            time21_5 TIMESTAMPTZ,
+            latitude DOUBLE PRECISION,
+            longitude DOUBLE PRECISION,
+            half_angle DOUBLE PRECISION,
+            speed_kms DOUBLE PRECISION,
+            cme_type TEXT,
+            is_most_accurate BOOLEAN NOT NULL DEFAULT false,
+            level_of_data INT,
+            note TEXT,
+            link TEXT
+        )"
+    ).execute(pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS ix_donki_cme_analyses_activity ON donki_cme_analyses(activity_id)").execute(pool).await?;
+
+    // DONKI: прочие события (GST, SEP, IPS, HSS, RBE) и уведомления прогнозистов
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS donki_events(
+            event_id TEXT PRIMARY KEY,
+            kind TEXT NOT NULL,
+            event_time TIMESTAMPTZ,
+            location TEXT,
+            catalog TEXT,
+            instruments TEXT[] NOT NULL DEFAULT '{}',
+            kp_max DOUBLE PRECISION,
+            details JSONB,
+            link TEXT,
+            raw JSONB NOT NULL,
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )"
+    ).execute(pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS ix_donki_events_kind_time ON donki_events(kind, event_time)").execute(pool).await?;
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS donki_notifications(
+            message_id TEXT PRIMARY KEY,
+            message_type TEXT,
+            issue_time TIMESTAMPTZ,
+            url TEXT,
+            body TEXT,
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )"
+    ).execute(pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS ix_donki_notifications_time ON donki_notifications(issue_time)").execute(pool).await?;
+
+    // DONKI linkedEvents: ребра графа событий (вспышка -> CME -> буря ...)
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS donki_links(
+            from_id TEXT NOT NULL,
+            to_id TEXT NOT NULL,
+            PRIMARY KEY(from_id, to_id)
+        )"
+    ).execute(pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS ix_donki_links_to ON donki_links(to_id)").execute(pool).await?;
+    // связи для событий, сохраненных до появления таблицы
+    for (table, id_column) in [("donki_flares", "flr_id"), ("donki_cmes", "activity_id"), ("donki_events", "event_id")] {
+        sqlx::query(&format!(
+            "INSERT INTO donki_links(from_id, to_id)
+             SELECT {id_column}, e->>'activityID' FROM {table}, jsonb_array_elements(raw->'linkedEvents') e
+             WHERE jsonb_typeof(raw->'linkedEvents') = 'array' AND e->>'activityID' IS NOT NULL
+             ON CONFLICT DO NOTHING"
+        )).execute(pool).await?;
+    }
+    // все события DONKI одной выборкой: id, тип, время, краткая подпись
+    sqlx::query(
+        "CREATE OR REPLACE VIEW donki_event_nodes AS
+            SELECT flr_id AS event_id, 'flr' AS kind, coalesce(peak_time, begin_time) AS event_time,
+                   class_type AS label, link
+            FROM donki_flares
+            UNION ALL
+            SELECT activity_id, 'cme', start_time,
+                   concat_ws(' ', cme_type, round(speed_kms::numeric) || ' km/s'), link
+            FROM donki_cmes
+            UNION ALL
+            SELECT event_id, kind, event_time,
+                   coalesce('Kp ' || kp_max, location), link
+            FROM donki_events"
+    ).execute(pool).await?;
+
+    // NOAA SWPC: временные ряды планетарного Kp (наблюдения + прогноз) и солнечного ветра
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS noaa_kp(
+            time_tag TIMESTAMPTZ PRIMARY KEY,
+            kp DOUBLE PRECISION NOT NULL,
+            a_running DOUBLE PRECISION,
+            station_count INT,
+            status TEXT NOT NULL DEFAULT 'observed',
+            noaa_scale TEXT,
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )"
+    ).execute(pool).await?;
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS noaa_solar_wind(
+            time_tag TIMESTAMPTZ PRIMARY KEY,
+            density DOUBLE PRECISION,
+            speed DOUBLE PRECISION,
+            temperature DOUBLE PRECISION,
+            bx_gsm DOUBLE PRECISION,
+            by_gsm DOUBLE PRECISION,
+            bz_gsm DOUBLE PRECISION,
+            bt DOUBLE PRECISION,
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )"
+    ).execute(pool).await?;
+
+    // Пуски: ракеты и площадки провайдеров, нормализованные пуски и журнал их изменений
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS launch_rockets(
+            rocket_id TEXT PRIMARY KEY,
+            provider TEXT NOT NULL,
+            name TEXT NOT NULL,
+            rocket_type TEXT,
+            active BOOLEAN,
+            success_rate_pct DOUBLE PRECISION,
+            raw JSONB NOT NULL,
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )"
+    ).execute(pool).await?;
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS launch_pads(
+            pad_id TEXT PRIMARY KEY,
+            provider TEXT NOT NULL,
+            name TEXT NOT NULL,
+            full_name TEXT,
+            locality TEXT,
+            region TEXT,
+            latitude DOUBLE PRECISION,
+            longitude DOUBLE PRECISION,
+            status TEXT,
+            raw JSONB NOT NULL,
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )"
+    ).execute(pool).await?;
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS launches(
+            launch_id TEXT PRIMARY KEY,
+            provider TEXT NOT NULL,
+            name TEXT NOT NULL,
+            flight_number INT,
+            net TIMESTAMPTZ,
+            net_precision TEXT,
+            status TEXT NOT NULL,
+            upcoming BOOLEAN NOT NULL DEFAULT false,
+            rocket_id TEXT,
+            pad_id TEXT,
+            details TEXT,
+            webcast_url TEXT,
+            patch_url TEXT,
+            raw JSONB NOT NULL,
+            first_seen_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )"
+    ).execute(pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS ix_launches_upcoming_net ON launches(upcoming, net)").execute(pool).await?;
+    // Провайдер-независимая модель: агентство, миссия, орбита; дубликат другого провайдера -> merged_into
+    sqlx::query(
+        "ALTER TABLE launches
+            ADD COLUMN IF NOT EXISTS agency TEXT,
+            ADD COLUMN IF NOT EXISTS mission_name TEXT,
+            ADD COLUMN IF NOT EXISTS mission_type TEXT,
+            ADD COLUMN IF NOT EXISTS orbit TEXT,
+            ADD COLUMN IF NOT EXISTS image_url TEXT,
+            ADD COLUMN IF NOT EXISTS merged_into TEXT"
+    ).execute(pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS ix_launches_merged_into ON launches(merged_into)").execute(pool).await?;
+    // calendar_uid — id провайдера, под которым пуск впервые попал в календарь (переживает слияние);
+    // revision — SEQUENCE события, только растет
+    sqlx::query(
+        "ALTER TABLE launches
+            ADD COLUMN IF NOT EXISTS calendar_uid TEXT,
+            ADD COLUMN IF NOT EXISTS revision INT NOT NULL DEFAULT 0"
+    ).execute(pool).await?;
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS launch_changes(
+            id BIGSERIAL PRIMARY KEY,
+            launch_id TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            old_value TEXT,
+            new_value TEXT,
+            changed_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )"
+    ).execute(pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS ix_launch_changes_launch ON launch_changes(launch_id, changed_at)").execute(pool).await?;
+    // счетчик ревизий для строк, записанных до его появления: по журналу пуска и его дубликатов
+    sqlx::query(
+        "UPDATE launches l SET revision = j.n
+         FROM (SELECT coalesce(s.merged_into, s.launch_id) AS launch_id, count(*)::int AS n
+               FROM launch_changes c JOIN launches s ON s.launch_id = c.launch_id
+               GROUP BY 1) j
+         WHERE l.launch_id = j.launch_id AND l.revision = 0"
+    ).execute(pool).await?;
+
+    // элементы орбиты МКС (CelesTrak GP) и прогноз видимых пролетов по точкам наблюдения
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS iss_elements(
+            epoch TIMESTAMPTZ PRIMARY KEY,
+            mean_motion DOUBLE PRECISION NOT NULL,
+            eccentricity DOUBLE PRECISION NOT NULL,
+            inclination_deg DOUBLE PRECISION NOT NULL,
+            raan_deg DOUBLE PRECISION NOT NULL,
+            arg_perigee_deg DOUBLE PRECISION NOT NULL,
+            mean_anomaly_deg DOUBLE PRECISION NOT NULL,
+            mean_motion_dot DOUBLE PRECISION NOT NULL DEFAULT 0,
+            rev_at_epoch BIGINT NOT NULL DEFAULT 0,
+            raw JSONB NOT NULL,
+            fetched_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )"
+    ).execute(pool).await?;
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS iss_passes(
+            lat_key INT NOT NULL,
+            lon_key INT NOT NULL,
+            rev BIGINT NOT NULL,
+            start_at TIMESTAMPTZ NOT NULL,
+            max_at TIMESTAMPTZ NOT NULL,
+            end_at TIMESTAMPTZ NOT NULL,
+            start_elevation DOUBLE PRECISION NOT NULL,
+            max_elevation DOUBLE PRECISION NOT NULL,
+            end_elevation DOUBLE PRECISION NOT NULL,
+            start_azimuth DOUBLE PRECISION NOT NULL,
+            max_azimuth DOUBLE PRECISION NOT NULL,
+            end_azimuth DOUBLE PRECISION NOT NULL,
+            revision INT NOT NULL DEFAULT 0,
+            elements_epoch TIMESTAMPTZ NOT NULL,
+            computed_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+            PRIMARY KEY (lat_key, lon_key, rev)
+        )"
+    ).execute(pool).await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS ix_iss_passes_start ON iss_passes(lat_key, lon_key, start_at)").execute(pool).await?;
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS iss_pass_runs(
+            lat_key INT NOT NULL,
+            lon_key INT NOT NULL,
+            elements_epoch TIMESTAMPTZ NOT NULL,
+            horizon_end TIMESTAMPTZ NOT NULL,
+            computed_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+            PRIMARY KEY (lat_key, lon_key)
+        )"
+    ).execute(pool).await?;
+
     // универсальный кэш космоданных
     sqlx::query(
         "CREATE TABLE IF NOT EXISTS space_cache(
@@ -53,6 +614,15 @@ pub async fn init_db(pool: &PgPool) -> Result<(), AppError> {
         )"
     ).execute(pool).await?;
     sqlx::query("CREATE INDEX IF NOT EXISTS ix_space_cache_source ON space_cache(source,fetched_at DESC)").execute(pool).await?;
+    // хэш содержимого: неизменный payload не дает новой строки, а только отмечает проверку
+    sqlx::query(
+        "ALTER TABLE space_cache
+            ADD COLUMN IF NOT EXISTS content_hash TEXT,
+            ADD COLUMN IF NOT EXISTS last_checked_at TIMESTAMPTZ"
+    ).execute(pool).await?;
+    sqlx::query("UPDATE space_cache SET last_checked_at = fetched_at WHERE last_checked_at IS NULL").execute(pool).await?;
+    sqlx::query("ALTER TABLE space_cache ALTER COLUMN last_checked_at SET DEFAULT now(), ALTER COLUMN last_checked_at SET NOT NULL")
+        .execute(pool).await?;
 
     info!("Database schema initialized.");
     Ok(())
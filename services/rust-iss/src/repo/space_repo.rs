@@ -0,0 +1,626 @@
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::{FromRow, PgPool, Row};
+use serde_json::Value;
+
+use crate::domain::contracts::SpaceRepoContract;
+use crate::domain::errors::AppError;
+use crate::domain::models::{
+    ApodEntry, ApodMedia, ApodSearchFilter, ApodSearchHit, NeoAlert, NeoApproach, NeoApproachView, NeoCounts, NeoDetail, NeoObject,
+    IssElements, IssPass, NeoOrbit, NoaaKp, SolarWindSample,
+};
+use crate::repo::iss_repo;
+
+/// Границы подсветки ts_headline: символы из Private Use Area, в тексте APOD их нет
+const MARK_START: char = '\u{E000}';
+const MARK_STOP: char = '\u{E001}';
+
+/// Фрагмент ts_headline в безопасный HTML: текст экранируется, границы подсветки становятся <mark>
+fn highlight_html(snippet: &str) -> String {
+    let mut out = String::with_capacity(snippet.len() + 16);
+    for ch in snippet.chars() {
+        match ch {
+            MARK_START => out.push_str("<mark>"),
+            MARK_STOP => out.push_str("</mark>"),
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Колонки apod_media, из которых собирается ApodMedia
+const APOD_MEDIA_COLUMNS: &str =
+    "date, source_url, state, original_path, thumb_path, medium_path, content_type, size_bytes,
+     width, height, sha256, error, retry_after, updated_at";
+
+/// Колонки neo_approaches (a) и neo_objects (o), из которых собирается NeoApproachView
+const NEO_APPROACH_VIEW_COLUMNS: &str =
+    "o.neo_id, o.name, o.is_hazardous, o.diameter_min_m, o.diameter_max_m, '/api/neo/' || o.neo_id AS detail_url,
+     a.approach_at, a.approach_date, a.miss_distance_km, a.miss_distance_lunar, a.velocity_kms, a.orbiting_body";
+
+/// Колонки neo_objects, из которых собирается NeoObject
+const NEO_OBJECT_COLUMNS: &str =
+    "neo_id, name, nasa_jpl_url, absolute_magnitude_h, diameter_min_m, diameter_max_m, is_hazardous, is_sentry";
+
+/// Колонки neo_alerts (al) и neo_objects (o), из которых собирается NeoAlert
+const NEO_ALERT_COLUMNS: &str =
+    "al.neo_id, o.name, al.approach_at, al.approach_date, al.severity, al.reasons, al.miss_distance_lunar,
+     o.diameter_max_m, o.is_hazardous, '/api/neo/' || o.neo_id AS detail_url, al.created_at";
+
+/// Колонки noaa_solar_wind, из которых собирается SolarWindSample
+const SOLAR_WIND_COLUMNS: &str =
+    "time_tag, density, speed, temperature, bx_gsm, by_gsm, bz_gsm, bt";
+
+/// Колонки iss_elements, из которых собирается IssElements
+const ISS_ELEMENTS_COLUMNS: &str =
+    "epoch, mean_motion, eccentricity, inclination_deg, raan_deg, arg_perigee_deg, mean_anomaly_deg,
+     mean_motion_dot, rev_at_epoch";
+
+/// Колонки iss_passes, из которых собирается IssPass
+const ISS_PASS_COLUMNS: &str =
+    "rev, start_at, max_at, end_at, start_elevation, max_elevation, end_elevation,
+     start_azimuth, max_azimuth, end_azimuth, revision";
+
+/// Колонки apod_entries, из которых собирается ApodEntry
+const APOD_COLUMNS: &str =
+    "date, title, explanation, media_type, url, hdurl, thumbnail_url, copyright, fetched_at";
+
+// =========================================================================================
+// 1. СТРУКТУРА РЕПОЗИТОРИЯ
+// =========================================================================================
+
+/// Хранилище космоданных (APOD, NeoWs и др.) и универсального space_cache.
+pub struct SpaceRepo {
+    pool: PgPool,
+}
+
+pub fn new_space_repo(pool: PgPool) -> SpaceRepo {
+    SpaceRepo { pool }
+}
+
+// =========================================================================================
+// 2. РЕАЛИЗАЦИЯ КОНТРАКТА
+// =========================================================================================
+
+#[async_trait]
+impl SpaceRepoContract for SpaceRepo {
+
+    /// UPSERT записей APOD по дате; записи без даты пропускаются.
+    async fn upsert_apod(&self, items: &[Value]) -> Result<u64, AppError> {
+        let mut written = 0;
+        for raw in items {
+            let Some(e) = ApodEntry::from_raw(raw) else { continue };
+            sqlx::query(
+                "INSERT INTO apod_entries(date, title, explanation, media_type, url, hdurl, thumbnail_url, copyright, raw, fetched_at)
+                 VALUES($1,$2,$3,$4,$5,$6,$7,$8,$9,now())
+                 ON CONFLICT (date) DO UPDATE
+                 SET title=EXCLUDED.title, explanation=EXCLUDED.explanation, media_type=EXCLUDED.media_type,
+                     url=EXCLUDED.url, hdurl=EXCLUDED.hdurl, thumbnail_url=EXCLUDED.thumbnail_url,
+                     copyright=EXCLUDED.copyright, raw=EXCLUDED.raw, fetched_at=now()"
+            )
+            .bind(e.date).bind(&e.title).bind(&e.explanation).bind(&e.media_type)
+            .bind(&e.url).bind(&e.hdurl).bind(&e.thumbnail_url).bind(&e.copyright).bind(raw)
+            .execute(&self.pool).await?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    async fn get_apod(&self, date: NaiveDate) -> Result<Option<ApodEntry>, AppError> {
+        let entry = sqlx::query_as::<_, ApodEntry>(&format!(
+            "SELECT {APOD_COLUMNS} FROM apod_entries WHERE date = $1"
+        ))
+        .bind(date)
+        .fetch_optional(&self.pool).await?;
+
+        Ok(entry)
+    }
+
+    async fn get_apod_range(&self, start: NaiveDate, end: NaiveDate) -> Result<Vec<ApodEntry>, AppError> {
+        let entries = sqlx::query_as::<_, ApodEntry>(&format!(
+            "SELECT {APOD_COLUMNS} FROM apod_entries WHERE date BETWEEN $1 AND $2 ORDER BY date"
+        ))
+        .bind(start).bind(end)
+        .fetch_all(&self.pool).await?;
+
+        Ok(entries)
+    }
+
+    async fn count_apod(&self, start: NaiveDate, end: NaiveDate) -> Result<i64, AppError> {
+        let count: i64 = sqlx::query(
+            "SELECT count(*) AS c FROM (
+                 SELECT date FROM apod_entries WHERE date BETWEEN $1 AND $2
+                 UNION
+                 SELECT date FROM apod_gaps WHERE date BETWEEN $1 AND $2
+             ) d"
+        )
+        .bind(start).bind(end)
+        .fetch_one(&self.pool).await
+        .map(|r| r.get("c"))?;
+        Ok(count)
+    }
+
+    async fn mark_apod_gaps(&self, start: NaiveDate, end: NaiveDate) -> Result<u64, AppError> {
+        let res = sqlx::query(
+            "INSERT INTO apod_gaps(date, checked_at)
+             SELECT d::date, now()
+             FROM generate_series($1::date, $2::date, interval '1 day') d
+             WHERE NOT EXISTS (SELECT 1 FROM apod_entries e WHERE e.date = d::date)
+             ON CONFLICT (date) DO UPDATE SET checked_at = now()"
+        )
+        .bind(start).bind(end)
+        .execute(&self.pool).await?;
+        Ok(res.rows_affected())
+    }
+
+    /// websearch_to_tsquery понимает кавычки, OR и минус; ранг ts_rank_cd по весам A/B
+    async fn search_apod(&self, filter: &ApodSearchFilter) -> Result<Vec<ApodSearchHit>, AppError> {
+        let hits = sqlx::query_as::<_, ApodSearchHit>(
+            "SELECT date, title, media_type, url, thumbnail_url,
+                    ts_rank_cd(search, query) AS rank,
+                    ts_headline('english', coalesce(explanation, ''), query, $6) AS snippet
+             FROM apod_entries, websearch_to_tsquery('english', $1) query
+             WHERE search @@ query
+               AND ($2::DATE IS NULL OR date >= $2)
+               AND ($3::DATE IS NULL OR date <= $3)
+               AND ($4::TEXT IS NULL OR media_type = $4)
+             ORDER BY rank DESC, date DESC
+             LIMIT $5"
+        )
+        .bind(&filter.q).bind(filter.start).bind(filter.end).bind(&filter.media_type)
+        .bind(filter.limit.unwrap_or(20).clamp(1, 100))
+        .bind(format!("StartSel={}, StopSel={}, MaxWords=35, MinWords=15, MaxFragments=2", MARK_START, MARK_STOP))
+        .fetch_all(&self.pool).await?
+        .into_iter()
+        .map(|hit| ApodSearchHit { snippet: hit.snippet.as_deref().map(highlight_html), ..hit })
+        .collect();
+
+        Ok(hits)
+    }
+
+    async fn get_apod_media(&self, date: NaiveDate) -> Result<Option<ApodMedia>, AppError> {
+        let media = sqlx::query_as::<_, ApodMedia>(&format!(
+            "SELECT {APOD_MEDIA_COLUMNS} FROM apod_media WHERE date = $1"
+        ))
+        .bind(date)
+        .fetch_optional(&self.pool).await?;
+
+        Ok(media)
+    }
+
+    async fn save_apod_media(&self, m: &ApodMedia) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO apod_media(date, source_url, state, original_path, thumb_path, medium_path,
+                                    content_type, size_bytes, width, height, sha256, error, retry_after, updated_at)
+             VALUES($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,$13,now())
+             ON CONFLICT (date) DO UPDATE
+             SET source_url=EXCLUDED.source_url, state=EXCLUDED.state, original_path=EXCLUDED.original_path,
+                 thumb_path=EXCLUDED.thumb_path, medium_path=EXCLUDED.medium_path,
+                 content_type=EXCLUDED.content_type, size_bytes=EXCLUDED.size_bytes,
+                 width=EXCLUDED.width, height=EXCLUDED.height, sha256=EXCLUDED.sha256,
+                 error=EXCLUDED.error, retry_after=EXCLUDED.retry_after, updated_at=now()"
+        )
+        .bind(m.date).bind(&m.source_url).bind(&m.state).bind(&m.original_path)
+        .bind(&m.thumb_path).bind(&m.medium_path).bind(&m.content_type).bind(m.size_bytes)
+        .bind(m.width).bind(m.height).bind(&m.sha256).bind(&m.error).bind(m.retry_after)
+        .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    /// UPSERT объектов NeoWs вместе со сближениями; возвращает число объектов
+    async fn upsert_neo(&self, objects: &[Value]) -> Result<u64, AppError> {
+        let mut written = 0;
+        for raw in objects {
+            let Some(o) = NeoObject::from_raw(raw) else { continue };
+            let mut tx = self.pool.begin().await?;
+
+            sqlx::query(
+                "INSERT INTO neo_objects(neo_id, name, nasa_jpl_url, absolute_magnitude_h, diameter_min_m, diameter_max_m,
+                                         is_hazardous, is_sentry, raw, updated_at)
+                 VALUES($1,$2,$3,$4,$5,$6,$7,$8,$9,now())
+                 ON CONFLICT (neo_id) DO UPDATE
+                 SET name=EXCLUDED.name, nasa_jpl_url=EXCLUDED.nasa_jpl_url,
+                     absolute_magnitude_h=EXCLUDED.absolute_magnitude_h,
+                     diameter_min_m=EXCLUDED.diameter_min_m, diameter_max_m=EXCLUDED.diameter_max_m,
+                     is_hazardous=EXCLUDED.is_hazardous, is_sentry=EXCLUDED.is_sentry,
+                     raw=EXCLUDED.raw, updated_at=now()"
+            )
+            .bind(&o.neo_id).bind(&o.name).bind(&o.nasa_jpl_url).bind(o.absolute_magnitude_h)
+            .bind(o.diameter_min_m).bind(o.diameter_max_m).bind(o.is_hazardous).bind(o.is_sentry).bind(raw)
+            .execute(&mut *tx).await?;
+
+            for a in NeoApproach::list_from_raw(raw) {
+                sqlx::query(
+                    "INSERT INTO neo_approaches(neo_id, approach_at, approach_date, miss_distance_km,
+                                                miss_distance_lunar, velocity_kms, orbiting_body)
+                     VALUES($1,$2,$3,$4,$5,$6,$7)
+                     ON CONFLICT (neo_id, approach_at, orbiting_body) DO UPDATE
+                     SET approach_date=EXCLUDED.approach_date, miss_distance_km=EXCLUDED.miss_distance_km,
+                         miss_distance_lunar=EXCLUDED.miss_distance_lunar, velocity_kms=EXCLUDED.velocity_kms"
+                )
+                .bind(&o.neo_id).bind(a.approach_at).bind(a.approach_date).bind(a.miss_distance_km)
+                .bind(a.miss_distance_lunar).bind(a.velocity_kms).bind(&a.orbiting_body)
+                .execute(&mut *tx).await?;
+            }
+
+            tx.commit().await?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    async fn get_neo_approaches(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        hazardous_only: bool,
+        limit: i64,
+    ) -> Result<Vec<NeoApproachView>, AppError> {
+        let approaches = sqlx::query_as::<_, NeoApproachView>(&format!(
+            "SELECT {NEO_APPROACH_VIEW_COLUMNS}
+             FROM neo_approaches a JOIN neo_objects o ON o.neo_id = a.neo_id
+             WHERE a.orbiting_body = 'Earth' AND a.approach_at BETWEEN $1 AND $2
+               AND (NOT $3 OR o.is_hazardous)
+             ORDER BY a.approach_at
+             LIMIT $4"
+        ))
+        .bind(from).bind(to).bind(hazardous_only).bind(limit)
+        .fetch_all(&self.pool).await?;
+
+        Ok(approaches)
+    }
+
+    async fn get_neo_counts(&self) -> Result<NeoCounts, AppError> {
+        let counts = sqlx::query_as::<_, NeoCounts>(
+            "SELECT (SELECT count(*) FROM neo_objects) AS objects,
+                    (SELECT count(*) FROM neo_objects WHERE is_hazardous) AS hazardous,
+                    count(*) AS upcoming,
+                    count(*) FILTER (WHERE o.is_hazardous) AS hazardous_upcoming
+             FROM neo_approaches a JOIN neo_objects o ON o.neo_id = a.neo_id
+             WHERE a.orbiting_body = 'Earth' AND a.approach_at BETWEEN now() AND now() + interval '7 days'"
+        )
+        .fetch_one(&self.pool).await?;
+
+        Ok(counts)
+    }
+
+    /// Запись lookup: объект и сближения как в ленте, плюс орбита и время загрузки
+    async fn save_neo_lookup(&self, raw: &Value) -> Result<(), AppError> {
+        let object = NeoObject::from_raw(raw)
+            .ok_or_else(|| AppError::SerializationError("NeoWs lookup record has no id".to_string()))?;
+        self.upsert_neo(std::slice::from_ref(raw)).await?;
+
+        let orbit = NeoOrbit::from_raw(raw);
+        sqlx::query(
+            "UPDATE neo_objects
+             SET orbit_class=$2, orbit_class_description=$3, orbital_data=$4, lookup_at=now()
+             WHERE neo_id=$1"
+        )
+        .bind(&object.neo_id).bind(&orbit.orbit_class)
+        .bind(&orbit.orbit_class_description).bind(&orbit.orbital_data)
+        .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn get_neo_detail(&self, neo_id: &str) -> Result<Option<NeoDetail>, AppError> {
+        let object = sqlx::query_as::<_, NeoObject>(&format!(
+            "SELECT {NEO_OBJECT_COLUMNS} FROM neo_objects WHERE neo_id = $1"
+        ))
+        .bind(neo_id)
+        .fetch_optional(&self.pool).await?;
+        let Some(object) = object else { return Ok(None) };
+
+        let orbit = sqlx::query_as::<_, NeoOrbit>(
+            "SELECT orbit_class, orbit_class_description, orbital_data, lookup_at FROM neo_objects WHERE neo_id = $1"
+        )
+        .bind(neo_id)
+        .fetch_one(&self.pool).await?;
+
+        // Все сближения: из ленты и из lookup, без дублей (уникальный ключ таблицы)
+        let approaches = sqlx::query_as::<_, NeoApproach>(
+            "SELECT approach_at, approach_date, miss_distance_km, miss_distance_lunar, velocity_kms, orbiting_body
+             FROM neo_approaches WHERE neo_id = $1
+             ORDER BY approach_at"
+        )
+        .bind(neo_id)
+        .fetch_all(&self.pool).await?;
+
+        Ok(Some(NeoDetail { object, orbit, approaches }))
+    }
+
+    async fn touch_neo_lookup(&self, neo_id: &str) -> Result<(), AppError> {
+        sqlx::query("UPDATE neo_objects SET lookup_at = now() WHERE neo_id = $1")
+            .bind(neo_id)
+            .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn get_stale_neo_lookups(&self, older_than: DateTime<Utc>, limit: i64) -> Result<Vec<String>, AppError> {
+        let ids = sqlx::query_scalar::<_, String>(
+            "SELECT neo_id FROM neo_objects
+             WHERE lookup_at < $1
+             ORDER BY lookup_at
+             LIMIT $2"
+        )
+        .bind(older_than).bind(limit)
+        .fetch_all(&self.pool).await?;
+        Ok(ids)
+    }
+
+    /// Пересчет тревог окна [from, to]: UPSERT совпавших, удаление тех, что больше не срабатывают.
+    /// Возвращает число новых тревог.
+    async fn replace_neo_alerts(&self, from: DateTime<Utc>, to: DateTime<Utc>, alerts: &[NeoAlert]) -> Result<u64, AppError> {
+        let run_at = Utc::now();
+        let mut created = 0;
+        let mut tx = self.pool.begin().await?;
+
+        for a in alerts {
+            let inserted: bool = sqlx::query_scalar(
+                "INSERT INTO neo_alerts(neo_id, approach_date, approach_at, severity, reasons, miss_distance_lunar, evaluated_at)
+                 VALUES($1,$2,$3,$4,$5,$6,$7)
+                 ON CONFLICT (neo_id, approach_date) DO UPDATE
+                 SET approach_at=EXCLUDED.approach_at, severity=EXCLUDED.severity, reasons=EXCLUDED.reasons,
+                     miss_distance_lunar=EXCLUDED.miss_distance_lunar, evaluated_at=EXCLUDED.evaluated_at
+                 RETURNING (xmax = 0)"
+            )
+            .bind(&a.neo_id).bind(a.approach_date).bind(a.approach_at).bind(&a.severity)
+            .bind(&a.reasons).bind(a.miss_distance_lunar).bind(run_at)
+            .fetch_one(&mut *tx).await?;
+            if inserted {
+                created += 1;
+            }
+        }
+
+        sqlx::query("DELETE FROM neo_alerts WHERE approach_at BETWEEN $1 AND $2 AND evaluated_at < $3")
+            .bind(from).bind(to).bind(run_at)
+            .execute(&mut *tx).await?;
+
+        tx.commit().await?;
+        Ok(created)
+    }
+
+    async fn get_neo_alerts(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        min_level: i32,
+        limit: i64,
+    ) -> Result<Vec<NeoAlert>, AppError> {
+        let alerts = sqlx::query_as::<_, NeoAlert>(&format!(
+            "SELECT {NEO_ALERT_COLUMNS}
+             FROM neo_alerts al JOIN neo_objects o ON o.neo_id = al.neo_id
+             WHERE al.approach_at BETWEEN $1 AND $2
+               AND array_position(ARRAY['info','warning','critical'], al.severity) >= $3
+             ORDER BY array_position(ARRAY['info','warning','critical'], al.severity) DESC, al.approach_at
+             LIMIT $4"
+        ))
+        .bind(from).bind(to).bind(min_level).bind(limit)
+        .fetch_all(&self.pool).await?;
+
+        Ok(alerts)
+    }
+
+    async fn upsert_noaa_kp(&self, rows: &[Value]) -> Result<u64, AppError> {
+        let mut written = 0;
+        let mut tx = self.pool.begin().await?;
+        for raw in rows {
+            let Some(k) = NoaaKp::from_raw(raw) else { continue };
+            sqlx::query(
+                "INSERT INTO noaa_kp(time_tag, kp, a_running, station_count, status, noaa_scale, updated_at)
+                 VALUES($1,$2,$3,$4,$5,$6,now())
+                 ON CONFLICT (time_tag) DO UPDATE
+                 SET kp=EXCLUDED.kp, a_running=coalesce(EXCLUDED.a_running, noaa_kp.a_running),
+                     station_count=coalesce(EXCLUDED.station_count, noaa_kp.station_count),
+                     status=EXCLUDED.status, noaa_scale=coalesce(EXCLUDED.noaa_scale, noaa_kp.noaa_scale),
+                     updated_at=now()
+                 WHERE array_position(ARRAY['predicted','estimated','observed'], EXCLUDED.status)
+                    >= coalesce(array_position(ARRAY['predicted','estimated','observed'], noaa_kp.status), 0)"
+            )
+            .bind(k.time_tag).bind(k.kp).bind(k.a_running).bind(k.station_count)
+            .bind(&k.status).bind(&k.noaa_scale)
+            .execute(&mut *tx).await?;
+            written += 1;
+        }
+        tx.commit().await?;
+        Ok(written)
+    }
+
+    async fn get_noaa_kp(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<NoaaKp>, AppError> {
+        let rows = sqlx::query_as::<_, NoaaKp>(
+            "SELECT time_tag, kp, a_running, station_count, status, noaa_scale
+             FROM noaa_kp WHERE time_tag BETWEEN $1 AND $2 ORDER BY time_tag"
+        )
+        .bind(from).bind(to)
+        .fetch_all(&self.pool).await?;
+
+        Ok(rows)
+    }
+
+    async fn upsert_solar_wind(&self, rows: &[Value]) -> Result<u64, AppError> {
+        let mut written = 0;
+        let mut tx = self.pool.begin().await?;
+        for raw in rows {
+            let Some(w) = SolarWindSample::from_raw(raw) else { continue };
+            sqlx::query(
+                "INSERT INTO noaa_solar_wind(time_tag, density, speed, temperature, bx_gsm, by_gsm, bz_gsm, bt, updated_at)
+                 VALUES($1,$2,$3,$4,$5,$6,$7,$8,now())
+                 ON CONFLICT (time_tag) DO UPDATE
+                 SET density=coalesce(EXCLUDED.density, noaa_solar_wind.density),
+                     speed=coalesce(EXCLUDED.speed, noaa_solar_wind.speed),
+                     temperature=coalesce(EXCLUDED.temperature, noaa_solar_wind.temperature),
+                     bx_gsm=coalesce(EXCLUDED.bx_gsm, noaa_solar_wind.bx_gsm),
+                     by_gsm=coalesce(EXCLUDED.by_gsm, noaa_solar_wind.by_gsm),
+                     bz_gsm=coalesce(EXCLUDED.bz_gsm, noaa_solar_wind.bz_gsm),
+                     bt=coalesce(EXCLUDED.bt, noaa_solar_wind.bt),
+                     updated_at=now()"
+            )
+            .bind(w.time_tag).bind(w.density).bind(w.speed).bind(w.temperature)
+            .bind(w.bx_gsm).bind(w.by_gsm).bind(w.bz_gsm).bind(w.bt)
+            .execute(&mut *tx).await?;
+            written += 1;
+        }
+        tx.commit().await?;
+        Ok(written)
+    }
+
+    async fn get_solar_wind(&self, from: DateTime<Utc>, to: DateTime<Utc>, limit: i64) -> Result<Vec<SolarWindSample>, AppError> {
+        let rows = sqlx::query_as::<_, SolarWindSample>(&format!(
+            "SELECT * FROM (
+                SELECT {SOLAR_WIND_COLUMNS} FROM noaa_solar_wind
+                WHERE time_tag BETWEEN $1 AND $2 ORDER BY time_tag DESC LIMIT $3
+             ) w ORDER BY time_tag"
+        ))
+        .bind(from).bind(to).bind(limit)
+        .fetch_all(&self.pool).await?;
+
+        Ok(rows)
+    }
+
+    async fn save_iss_elements(&self, elements: &IssElements, raw: &Value) -> Result<(), AppError> {
+        let e = elements;
+        sqlx::query(
+            "INSERT INTO iss_elements(epoch, mean_motion, eccentricity, inclination_deg, raan_deg, arg_perigee_deg,
+                                      mean_anomaly_deg, mean_motion_dot, rev_at_epoch, raw, fetched_at)
+             VALUES($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,now())
+             ON CONFLICT (epoch) DO UPDATE
+             SET mean_motion=EXCLUDED.mean_motion, eccentricity=EXCLUDED.eccentricity,
+                 inclination_deg=EXCLUDED.inclination_deg, raan_deg=EXCLUDED.raan_deg,
+                 arg_perigee_deg=EXCLUDED.arg_perigee_deg, mean_anomaly_deg=EXCLUDED.mean_anomaly_deg,
+                 mean_motion_dot=EXCLUDED.mean_motion_dot, rev_at_epoch=EXCLUDED.rev_at_epoch,
+                 raw=EXCLUDED.raw, fetched_at=now()"
+        )
+        .bind(e.epoch).bind(e.mean_motion).bind(e.eccentricity).bind(e.inclination_deg).bind(e.raan_deg)
+        .bind(e.arg_perigee_deg).bind(e.mean_anomaly_deg).bind(e.mean_motion_dot).bind(e.rev_at_epoch).bind(raw)
+        .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn get_iss_elements(&self) -> Result<Option<(IssElements, DateTime<Utc>)>, AppError> {
+        let row = sqlx::query(&format!(
+            "SELECT {ISS_ELEMENTS_COLUMNS}, fetched_at FROM iss_elements ORDER BY epoch DESC LIMIT 1"
+        ))
+        .fetch_optional(&self.pool).await?;
+
+        row.map(|r| Ok((IssElements::from_row(&r)?, r.try_get("fetched_at")?))).transpose()
+    }
+
+    async fn get_iss_pass_run(
+        &self,
+        lat_key: i32,
+        lon_key: i32,
+    ) -> Result<Option<(DateTime<Utc>, DateTime<Utc>, DateTime<Utc>)>, AppError> {
+        let row = sqlx::query_as::<_, (DateTime<Utc>, DateTime<Utc>, DateTime<Utc>)>(
+            "SELECT elements_epoch, horizon_end, computed_at FROM iss_pass_runs WHERE lat_key = $1 AND lon_key = $2"
+        )
+        .bind(lat_key).bind(lon_key)
+        .fetch_optional(&self.pool).await?;
+
+        Ok(row)
+    }
+
+    async fn replace_iss_passes(
+        &self,
+        lat_key: i32,
+        lon_key: i32,
+        elements_epoch: DateTime<Utc>,
+        from: DateTime<Utc>,
+        horizon_end: DateTime<Utc>,
+        passes: &[IssPass],
+    ) -> Result<u64, AppError> {
+        let revs: Vec<i64> = passes.iter().map(|p| p.rev).collect();
+        let mut tx = self.pool.begin().await?;
+
+        // Будущие пролеты, которых нет в новом расчете, больше не ожидаются
+        sqlx::query("DELETE FROM iss_passes WHERE lat_key = $1 AND lon_key = $2 AND start_at >= $3 AND rev <> ALL($4)")
+            .bind(lat_key).bind(lon_key).bind(from).bind(&revs)
+            .execute(&mut *tx).await?;
+
+        for p in passes {
+            sqlx::query(
+                "INSERT INTO iss_passes(lat_key, lon_key, rev, start_at, max_at, end_at, start_elevation, max_elevation,
+                                        end_elevation, start_azimuth, max_azimuth, end_azimuth, elements_epoch, computed_at)
+                 VALUES($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,$13,now())
+                 ON CONFLICT (lat_key, lon_key, rev) DO UPDATE
+                 SET start_at=EXCLUDED.start_at, max_at=EXCLUDED.max_at, end_at=EXCLUDED.end_at,
+                     start_elevation=EXCLUDED.start_elevation, max_elevation=EXCLUDED.max_elevation,
+                     end_elevation=EXCLUDED.end_elevation, start_azimuth=EXCLUDED.start_azimuth,
+                     max_azimuth=EXCLUDED.max_azimuth, end_azimuth=EXCLUDED.end_azimuth,
+                     revision=iss_passes.revision + (iss_passes.elements_epoch <> EXCLUDED.elements_epoch)::int,
+                     elements_epoch=EXCLUDED.elements_epoch, computed_at=now()"
+            )
+            .bind(lat_key).bind(lon_key).bind(p.rev).bind(p.start_at).bind(p.max_at).bind(p.end_at)
+            .bind(p.start_elevation).bind(p.max_elevation).bind(p.end_elevation)
+            .bind(p.start_azimuth).bind(p.max_azimuth).bind(p.end_azimuth).bind(elements_epoch)
+            .execute(&mut *tx).await?;
+        }
+
+        sqlx::query(
+            "INSERT INTO iss_pass_runs(lat_key, lon_key, elements_epoch, horizon_end, computed_at)
+             VALUES($1,$2,$3,$4,now())
+             ON CONFLICT (lat_key, lon_key) DO UPDATE
+             SET elements_epoch=EXCLUDED.elements_epoch, horizon_end=EXCLUDED.horizon_end, computed_at=now()"
+        )
+        .bind(lat_key).bind(lon_key).bind(elements_epoch).bind(horizon_end)
+        .execute(&mut *tx).await?;
+
+        tx.commit().await?;
+        Ok(passes.len() as u64)
+    }
+
+    async fn get_iss_passes(&self, lat_key: i32, lon_key: i32, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<IssPass>, AppError> {
+        let rows = sqlx::query_as::<_, IssPass>(&format!(
+            "SELECT {ISS_PASS_COLUMNS} FROM iss_passes
+             WHERE lat_key = $1 AND lon_key = $2 AND start_at BETWEEN $3 AND $4
+             ORDER BY start_at"
+        ))
+        .bind(lat_key).bind(lon_key).bind(from).bind(to)
+        .fetch_all(&self.pool).await?;
+
+        Ok(rows)
+    }
+
+    async fn prune_iss_passes(&self, past_before: DateTime<Utc>, idle_before: DateTime<Utc>) -> Result<(u64, u64), AppError> {
+        let mut tx = self.pool.begin().await?;
+        let points: i64 = sqlx::query_scalar(
+            "WITH idle AS (
+                 DELETE FROM iss_pass_runs WHERE computed_at < $1 RETURNING lat_key, lon_key
+             ),
+             gone AS (
+                 DELETE FROM iss_passes p USING idle
+                 WHERE p.lat_key = idle.lat_key AND p.lon_key = idle.lon_key
+             )
+             SELECT count(*) FROM idle"
+        )
+        .bind(idle_before)
+        .fetch_one(&mut *tx).await?;
+        let passes = sqlx::query("DELETE FROM iss_passes WHERE end_at < $1")
+            .bind(past_before)
+            .execute(&mut *tx).await?
+            .rows_affected();
+        tx.commit().await?;
+        Ok((passes, points as u64))
+    }
+
+    async fn write_cache(&self, source: &str, payload: &Value) -> Result<(), AppError> {
+        iss_repo::write_cache(&self.pool, source, payload).await?;
+        Ok(())
+    }
+
+    async fn get_cache_sources(&self) -> Result<Vec<String>, AppError> {
+        iss_repo::get_cache_sources(&self.pool).await
+    }
+
+    async fn prune_cache(
+        &self,
+        source: &str,
+        older_than: Option<DateTime<Utc>>,
+        keep_rows: Option<i64>,
+    ) -> Result<(u64, u64, i64), AppError> {
+        iss_repo::prune_cache(&self.pool, source, older_than, keep_rows).await
+    }
+}
@@ -0,0 +1,11 @@
+use axum::{routing::get, Router};
+use crate::domain::models::AppState;
+use crate::handlers::apod_handler;
+
+pub fn apod_routes() -> Router<AppState> {
+    Router::new()
+        .route("/space/apod", get(apod_handler::apod))
+        .route("/space/apod/search", get(apod_handler::apod_search))
+        .route("/space/apod/backfill", get(apod_handler::apod_backfill))
+        .route("/space/apod/:date/image", get(apod_handler::apod_image))
+}
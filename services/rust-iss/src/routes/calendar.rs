@@ -0,0 +1,9 @@
+use axum::{routing::get, Router};
+use crate::domain::models::AppState;
+use crate::handlers::calendar_handler;
+
+pub fn calendar_routes() -> Router<AppState> {
+    Router::new()
+        .route("/calendar/launches.ics", get(calendar_handler::launches_ics))
+        .route("/calendar/iss-passes.ics", get(calendar_handler::iss_passes_ics))
+}
@@ -0,0 +1,14 @@
+use axum::{routing::get, Router};
+use crate::domain::models::AppState;
+use crate::handlers::donki_handler;
+
+pub fn donki_routes() -> Router<AppState> {
+    Router::new()
+        .route("/donki/flares", get(donki_handler::donki_flares))
+        .route("/donki/cmes", get(donki_handler::donki_cmes))
+        .route("/donki/notifications", get(donki_handler::donki_notifications))
+        .route("/donki/sync", get(donki_handler::donki_sync))
+        .route("/donki/chain/:activity_id", get(donki_handler::donki_chain))
+        // gst | sep | ips | hss | rbe
+        .route("/donki/:kind", get(donki_handler::donki_events))
+}
@@ -7,8 +7,10 @@ pub fn iss_routes() -> Router<AppState> {
         .route("/last", get(iss_handler::last_iss))
         .route("/fetch", get(iss_handler::trigger_iss))
         .route("/iss/trend", get(iss_handler::iss_trend))
+        .route("/iss/passes", get(iss_handler::iss_passes))
         // Space cache
         .route("/space/:src/latest", get(iss_handler::space_latest))
         .route("/space/refresh", get(iss_handler::space_refresh))
+        .route("/space/cache/prune", get(iss_handler::space_cache_prune))
         .route("/space/summary", get(iss_handler::space_summary))
 }
\ No newline at end of file
@@ -0,0 +1,10 @@
+use axum::{routing::get, Router};
+use crate::domain::models::AppState;
+use crate::handlers::launch_handler;
+
+pub fn launch_routes() -> Router<AppState> {
+    Router::new()
+        .route("/launches/upcoming", get(launch_handler::launches_upcoming))
+        .route("/launches/past", get(launch_handler::launches_past))
+        .route("/launches/:id", get(launch_handler::launch_detail))
+}
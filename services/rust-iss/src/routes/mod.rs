@@ -2,8 +2,14 @@ use axum::{routing::get, Router};
 use crate::domain::models::AppState;
 use crate::handlers::{iss_handler, osdr_handler};
 
+pub mod apod;
+pub mod calendar;
+pub mod donki;
 pub mod iss;
+pub mod launches;
+pub mod neo;
 pub mod osdr;
+pub mod space_weather;
 
 pub fn app_router(state: AppState) -> Router {
     Router::new()
@@ -12,5 +18,11 @@ pub fn app_router(state: AppState) -> Router {
         // Подключаем роуты модулей
         .merge(iss::iss_routes())
         .merge(osdr::osdr_routes())
+        .merge(apod::apod_routes())
+        .merge(neo::neo_routes())
+        .merge(donki::donki_routes())
+        .merge(space_weather::space_weather_routes())
+        .merge(launches::launch_routes())
+        .merge(calendar::calendar_routes())
         .with_state(state)
 }
\ No newline at end of file
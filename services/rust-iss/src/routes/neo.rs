@@ -0,0 +1,13 @@
+use axum::{routing::get, Router};
+use crate::domain::models::AppState;
+use crate::handlers::neo_handler;
+
+pub fn neo_routes() -> Router<AppState> {
+    Router::new()
+        .route("/neo/upcoming", get(neo_handler::neo_upcoming))
+        .route("/neo/hazardous", get(neo_handler::neo_hazardous))
+        .route("/neo/count", get(neo_handler::neo_count))
+        .route("/neo/alerts", get(neo_handler::neo_alerts))
+        .route("/neo/sync", get(neo_handler::neo_sync))
+        .route("/neo/:neo_id", get(neo_handler::neo_detail))
+}
@@ -5,5 +5,22 @@ use crate::handlers::osdr_handler;
 pub fn osdr_routes() -> Router<AppState> {
     Router::new()
         .route("/osdr/sync", get(osdr_handler::osdr_sync))
+        .route("/osdr/sync/metrics", get(osdr_handler::osdr_sync_metrics))
         .route("/osdr/list", get(osdr_handler::osdr_list))
+        .route("/osdr/stats", get(osdr_handler::osdr_stats))
+        .route("/osdr/crawl", get(osdr_handler::osdr_crawl))
+        .route("/osdr/crawl/status", get(osdr_handler::osdr_crawl_status))
+        .route("/osdr/mirror", get(osdr_handler::osdr_mirror))
+        .route("/osdr/graph", get(osdr_handler::osdr_graph))
+        .route("/osdr/snapshots", get(osdr_handler::osdr_snapshots))
+        .route("/osdr/snapshots/create", get(osdr_handler::osdr_snapshot_create))
+        .route("/osdr/snapshots/diff", get(osdr_handler::osdr_snapshot_diff))
+        .route("/osdr/snapshots/:id", get(osdr_handler::osdr_snapshot_download))
+        .route("/osdr/:dataset_id", get(osdr_handler::osdr_detail))
+        .route("/osdr/:dataset_id/mirror", get(osdr_handler::osdr_mirror_dataset))
+        .route("/osdr/:dataset_id/files", get(osdr_handler::osdr_files))
+        .route("/osdr/:dataset_id/related", get(osdr_handler::osdr_related))
+        .route("/osdr/:dataset_id/isa", get(osdr_handler::osdr_isa))
+        .route("/osdr/:dataset_id/isa/import", get(osdr_handler::osdr_isa_import))
+        .route("/osdr/:dataset_id/files/:file_name", get(osdr_handler::osdr_file_download))
 }
\ No newline at end of file
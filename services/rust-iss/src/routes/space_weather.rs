@@ -0,0 +1,11 @@
+use axum::{routing::get, Router};
+use crate::domain::models::AppState;
+use crate::handlers::space_weather_handler;
+
+pub fn space_weather_routes() -> Router<AppState> {
+    Router::new()
+        .route("/space-weather/status", get(space_weather_handler::space_weather_status))
+        .route("/space-weather/stats", get(space_weather_handler::space_weather_stats))
+        .route("/space-weather/kp", get(space_weather_handler::noaa_kp))
+        .route("/space-weather/solar-wind", get(space_weather_handler::noaa_solar_wind))
+}
@@ -1,13 +1,83 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use async_trait::async_trait;
+use futures_util::StreamExt;
+use tokio::sync::{Mutex, RwLock};
 use tracing::{error, info, instrument, warn};
 use serde_json::Value;
+use axum::http::StatusCode;
+use chrono::{Datelike, Duration, NaiveDate, Utc};
 
 use crate::domain::errors::AppError;
-use crate::domain::contracts::{NasaClientContract, OsdrRepoContract, SpaceServiceContract};
-use crate::domain::models::{AppConfig, IssPosition, OsdrItem}; // Добавьте импорты
+use crate::domain::contracts::{
+    CelestrakClientContract, DonkiRepoContract, LaunchRepoContract, Ll2ClientContract, NasaClientContract, NoaaClientContract, OsdrRepoContract, SpaceRepoContract,
+    SpaceServiceContract, SpacexClientContract,
+};
+use crate::domain::models::{
+    ApodEntry, ApodImage, ApodMedia, ApodSearchFilter, ApodSearchHit, AppConfig, CachePruneReport, KpReading, Launch, NoaaKp, SolarWindSample, SpaceWeatherStats, SpaceWeatherStatus, SPACE_WEATHER_LEVELS, DonkiChain, DonkiChainNode, DonkiChainStage, DonkiCme, DonkiEvent, DonkiFlare, DonkiNotification, DONKI_EVENT_TYPES, NeoAlert, NeoApproachView, NeoCounts, NeoDetail, DownloadOutcome, HttpValidators, IsaArchive, IssElements, IssPassForecast, IssPosition, MirrorReport, OsdrCrawlState, OsdrDetail, OsdrFile,
+    OsdrGraph, OsdrItem, OsdrMirrorFilter, OsdrRelated, OsdrSnapshot, OsdrSnapshotDiff, OsdrStats, OsdrSyncRun, OsdrSyncStats,
+}; // Добавьте импорты
+use crate::repo::osdr_repo::OSDR_ID_KEYS;
+use crate::utils::{hash, imaging, isatab, json_tools, orbit};
 use crate::clients::legacy_pascal_client::LegacyPascalClientContract; // !!! НОВЫЙ ИМПОРТ !!!
 
+/// Ключ чекпоинта полного обхода OSDR в osdr_crawl_state
+const OSDR_CRAWL: &str = "osdr";
+/// Сколько строк снимка может ждать сжатия: чтение из БД не обгоняет запись больше чем на столько
+const SNAPSHOT_CHANNEL_LINES: usize = 1024;
+
+/// Сколько последних дней APOD перезапрашивает плановое обновление
+const APOD_RECENT_DAYS: i64 = 7;
+/// Окно догрузки архива APOD (дней на запрос)
+const APOD_BACKFILL_WINDOW_DAYS: i64 = 30;
+/// Через сколько повторять неудачное кэширование изображения APOD (часов)
+const APOD_MEDIA_FAILED_RETRY_HOURS: i64 = 6;
+/// Изображение больше лимита: перепроверяем редко — вдруг лимит подняли или upstream заменил файл
+const APOD_MEDIA_SKIPPED_RETRY_DAYS: i64 = 7;
+/// Максимальный диапазон /space/apod?start=&end= (дней)
+const APOD_MAX_RANGE_DAYS: i64 = 366;
+/// Окно ленты NeoWs: API принимает не больше 7 дней за запрос
+const NEO_FEED_WINDOW_DAYS: i64 = 7;
+/// Максимальный диапазон ручной загрузки ленты NeoWs (дней), как у APOD
+const NEO_MAX_RANGE_DAYS: i64 = 366;
+/// Горизонт планового обновления NeoWs (дней вперед)
+const NEO_REFRESH_DAYS: i64 = 7;
+/// Через сколько часов lookup объекта считается устаревшим
+const NEO_LOOKUP_MAX_AGE_HOURS: i64 = 24;
+/// Сколько устаревших lookup обновляет один проход фоновой задачи
+const NEO_LOOKUP_REFRESH_BATCH: i64 = 20;
+/// Источники DONKI, которые обновляет фоновая задача (каждый независимо)
+const DONKI_KINDS: &[&str] = &["flr", "cme", "gst", "sep", "ips", "hss", "rbe", "notifications"];
+/// Окна /space-weather/status: вспышки (часы), CME к Земле (дни), Kp (часы)
+const SW_FLARE_WINDOW_HOURS: i64 = 24;
+const SW_CME_WINDOW_DAYS: i64 = 3;
+const SW_KP_WINDOW_HOURS: i64 = 72;
+/// Продукты NOAA SWPC, которые обновляет фоновая задача (каждый независимо)
+const NOAA_PRODUCTS: &[&str] = &["kp", "kp-forecast", "plasma", "mag"];
+/// Горизонт прогноза пролетов МКС (дней): по умолчанию и максимум
+const ISS_PASS_DEFAULT_DAYS: i64 = 7;
+const ISS_PASS_MAX_DAYS: i64 = 10;
+/// Минимальная высота видимого пролета над горизонтом (градусы)
+const ISS_PASS_MIN_ELEVATION: f64 = 10.0;
+/// Через сколько часов расчет пролетов для точки пересчитывается
+const ISS_PASS_MAX_AGE_HOURS: i64 = 12;
+/// Элементы орбиты, загруженные раньше этого (часы), перезапрашиваются при расчете пролетов.
+/// Считаем от fetched_at, а не от эпохи: эпоха CelesTrak сама бывает старше суток.
+const ISS_ELEMENTS_MAX_AGE_HOURS: i64 = 24;
+/// Прошедшие пролеты хранятся столько часов после окончания
+const ISS_PASS_KEEP_PAST_HOURS: i64 = 24;
+/// Точка, которую не запрашивали столько дней, удаляется вместе с пролетами
+const ISS_PASS_POINT_IDLE_DAYS: i64 = 30;
+/// Окно запроса DONKI (дней на запрос)
+const DONKI_WINDOW_DAYS: i64 = 30;
+/// Максимальный диапазон ручной загрузки DONKI (дней), как у APOD и NeoWs
+const DONKI_MAX_RANGE_DAYS: i64 = 366;
+/// Сколько последних дней DONKI перезапрашивает плановое обновление
+const DONKI_REFRESH_DAYS: i64 = 30;
+
 // =========================================================================================
 // 1. СТРУКТУРА СЕРВИСА (ОРКЕСТРАТОР БИЗНЕС-ЛОГИКИ)
 // =========================================================================================
@@ -19,36 +89,346 @@ pub struct SpaceService {
     config: AppConfig, // Нужна для URL'ов
     nasa_client: Arc<dyn NasaClientContract>,
     osdr_repo: Arc<dyn OsdrRepoContract>,
+    space_repo: Arc<dyn SpaceRepoContract>,
+    donki_repo: Arc<dyn DonkiRepoContract>,
+    launch_repo: Arc<dyn LaunchRepoContract>,
+    noaa_client: Arc<dyn NoaaClientContract>,
+    spacex_client: Arc<dyn SpacexClientContract>,
+    ll2_client: Arc<dyn Ll2ClientContract>,
+    celestrak_client: Arc<dyn CelestrakClientContract>,
     // !!! ДОБАВЛЕННЫЙ КЛИЕНТ ДЛЯ GOLANG ВОРКЕРА !!!
     legacy_pascal_client: Arc<dyn LegacyPascalClientContract>, 
+    // In-memory кэш /osdr/stats (свежесть по computed_at)
+    osdr_stats_cache: RwLock<Option<OsdrStats>>,
+    // Полный граф (min_weight = 1): попарное сравнение датасетов квадратично, порог режем в памяти
+    osdr_graph_cache: RwLock<Option<OsdrGraph>>,
+    // Обход OSDR один на процесс: ручной и фоновый двигали бы один чекпоинт
+    osdr_crawl_lock: Mutex<()>,
+    // Датасеты, которые сейчас зеркалируются
+    osdr_mirror_jobs: std::sync::Mutex<HashSet<String>>,
+    // Даты APOD, изображения которых сейчас кэшируются (общий original.part)
+    apod_media_jobs: std::sync::Mutex<HashSet<String>>,
 }
 
 impl SpaceService {
     /// Конструктор для создания нового экземпляра SpaceService
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         config: AppConfig, 
         nasa_client: Arc<dyn NasaClientContract>, 
         osdr_repo: Arc<dyn OsdrRepoContract>,
+        space_repo: Arc<dyn SpaceRepoContract>,
+        donki_repo: Arc<dyn DonkiRepoContract>,
+        launch_repo: Arc<dyn LaunchRepoContract>,
+        noaa_client: Arc<dyn NoaaClientContract>,
+        spacex_client: Arc<dyn SpacexClientContract>,
+        ll2_client: Arc<dyn Ll2ClientContract>,
+        celestrak_client: Arc<dyn CelestrakClientContract>,
         legacy_pascal_client: Arc<dyn LegacyPascalClientContract>
     ) -> Self {
         Self {
             config,
             nasa_client,
             osdr_repo,
+            space_repo,
+            donki_repo,
+            launch_repo,
+            noaa_client,
+            spacex_client,
+            ll2_client,
+            celestrak_client,
             legacy_pascal_client,
+            osdr_stats_cache: RwLock::new(None),
+            osdr_graph_cache: RwLock::new(None),
+            osdr_crawl_lock: Mutex::new(()),
+            osdr_mirror_jobs: std::sync::Mutex::new(HashSet::new()),
+            apod_media_jobs: std::sync::Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Ключ api.nasa.gov; без ключа — общий DEMO_KEY с жесткими лимитами
+    fn nasa_api_key(&self) -> &str {
+        if self.config.nasa_key.is_empty() { "DEMO_KEY" } else { &self.config.nasa_key }
+    }
+
+    /// Диапазон дат фильтра DONKI как интервал времени [start 00:00, end 24:00)
+    fn donki_bounds(start: NaiveDate, end: NaiveDate) -> Result<(chrono::DateTime<Utc>, chrono::DateTime<Utc>), AppError> {
+        if start > end {
+            return Err(AppError::ClientError(format!("Empty DONKI range {}..{}", start, end), StatusCode::BAD_REQUEST));
+        }
+        let day_start = |d: NaiveDate| d.and_hms_opt(0, 0, 0).expect("valid time").and_utc();
+        Ok((day_start(start), day_start(end + Duration::days(1)) - Duration::seconds(1)))
+    }
+
+    /// Одно окно DONKI: запрос нужного типа событий и UPSERT в его таблицу
+    async fn fetch_donki_window(&self, kind: &str, start: NaiveDate, end: NaiveDate) -> Result<u64, AppError> {
+        let (start, end) = (start.to_string(), end.to_string());
+        let key = self.nasa_api_key();
+        let event_type = DONKI_EVENT_TYPES.iter().find(|t| t.0 == kind);
+        let items = match (kind, event_type) {
+            ("flr", _) => self.nasa_client.fetch_donki_flr(&start, &end, key).await?,
+            ("cme", _) => self.nasa_client.fetch_donki_cme(&start, &end, key).await?,
+            ("notifications", _) => self.nasa_client.fetch_donki_events("notifications", &start, &end, key).await?,
+            (_, Some((_, endpoint, _, _))) => self.nasa_client.fetch_donki_events(endpoint, &start, &end, key).await?,
+            (other, None) => return Err(AppError::ClientError(format!("Unknown DONKI source '{}'", other), StatusCode::BAD_REQUEST)),
+        };
+        let items = items.as_array().cloned().unwrap_or_default();
+        match kind {
+            "flr" => self.donki_repo.upsert_flares(&items).await,
+            "cme" => self.donki_repo.upsert_cmes(&items).await,
+            "notifications" => self.donki_repo.upsert_notifications(&items).await,
+            _ => self.donki_repo.upsert_events(kind, &items).await,
+        }
+    }
+
+    /// Проверка диапазона дат APOD: не раньше начала архива и не позже сегодняшнего дня
+    fn apod_bounds(start: NaiveDate, end: NaiveDate) -> Result<(NaiveDate, NaiveDate), AppError> {
+        let start = start.max(ApodEntry::first_date());
+        let end = end.min(ApodEntry::latest_date());
+        if start > end {
+            return Err(AppError::ClientError(
+                format!("APOD range {}..{} is outside the archive ({}..today)", start, end, ApodEntry::first_date()),
+                StatusCode::BAD_REQUEST,
+            ));
         }
+        Ok((start, end))
+    }
+
+    /// Даты без записи после успешного ответа upstream — пропуски архива, повторно не запрашиваются.
+    /// Последнюю дату не отмечаем: запись за нее может появиться позже.
+    async fn mark_apod_gaps(&self, start: NaiveDate, end: NaiveDate) -> Result<(), AppError> {
+        let end = end.min(ApodEntry::latest_date() - Duration::days(1));
+        if start <= end {
+            let marked = self.space_repo.mark_apod_gaps(start, end).await?;
+            if marked > 0 {
+                info!("APOD {}..{}: {} dates recorded as archive gaps.", start, end, marked);
+            }
+        }
+        Ok(())
+    }
+
+    /// Локальные копии изображений для записей APOD; сбои только логируются
+    async fn cache_apod_media_for(&self, entries: &[ApodEntry]) {
+        for e in entries.iter().filter(|e| e.media_type.as_deref() == Some("image")) {
+            if let Err(err) = self.cache_apod_media(e.date).await {
+                warn!("APOD media cache for {} failed: {}", e.date, err);
+            }
+        }
+    }
+
+    /// Синхронизирует одну страницу OSDR: условный запрос по сохраненным ETag/Last-Modified,
+    /// отсев записей с неизменным content_hash, UPSERT остальных, отметка last_seen_at.
+    /// Возвращает ссылку на следующую страницу (при 304 — сохраненную) и общее число записей фида, если известно.
+    async fn sync_osdr_page(&self, url: &str, stats: &mut OsdrSyncStats) -> Result<(Option<String>, Option<i64>), AppError> {
+        let cached = self.osdr_repo.get_http_cache(url).await?;
+        let validators = cached.as_ref()
+            .map(|c| HttpValidators { etag: c.etag.clone(), last_modified: c.last_modified.clone() })
+            .unwrap_or_default();
+
+        let page = self.nasa_client.fetch_osdr_page(url, &validators).await?;
+        stats.pages_fetched += 1;
+        if page.not_modified {
+            stats.pages_not_modified += 1;
+            let Some(cached) = cached else { return Ok((None, None)) };
+            stats.items_restored += self.osdr_repo.mark_seen(&cached.dataset_ids).await?;
+            return Ok((cached.next_url, None));
+        }
+        let total = page.items.len() as i64;
+        stats.items_fetched += total;
+
+        // Отсев неизмененных записей до обращения к osdr_items на запись
+        let ids: Vec<String> = page.items.iter()
+            .filter_map(|item| json_tools::s_pick(item, OSDR_ID_KEYS))
+            .collect();
+        let known = self.osdr_repo.get_content_hashes(&ids).await?;
+        let changed: Vec<Value> = page.items.into_iter()
+            .filter(|item| {
+                json_tools::s_pick(item, OSDR_ID_KEYS)
+                    .and_then(|id| known.get(&id))
+                    .is_none_or(|h| *h != hash::json_hash(item))
+            })
+            .collect();
+        stats.items_unchanged += total - changed.len() as i64;
+
+        self.osdr_repo.upsert_osdr_items(&changed).await?;
+        stats.items_written += changed.len() as i64;
+        stats.items_restored += self.osdr_repo.mark_seen(&ids).await?;
+
+        self.osdr_repo.save_http_cache(url, &page.validators, page.next_url.as_deref(), &ids).await?;
+        Ok((page.next_url, page.total))
+    }
+
+    /// Скачивает один файл зеркала в dir: докачка .part, лимит размера, SHA-256.
+    /// Возвращает обновленное состояние и число байт, скачанных в этом прогоне.
+    async fn mirror_file(&self, mut file: OsdrFile, dir: &Path) -> Result<(OsdrFile, u64), AppError> {
+        let name = mirror_file_name(&file.file_name);
+        let target = dir.join(&name);
+        if file.state == "complete" {
+            if let Some(local) = &file.local_path {
+                if tokio::fs::try_exists(local).await.unwrap_or(false) {
+                    return Ok((file, 0));
+                }
+            }
+        }
+
+        let cap = self.config.osdr_mirror_max_file_bytes;
+        if file.size_bytes.is_some_and(|size| size as u64 > cap) {
+            file.state = "skipped".to_string();
+            file.error = Some(format!("size exceeds cap of {} bytes", cap));
+            self.osdr_repo.update_file(&file).await?;
+            return Ok((file, 0));
+        }
+
+        let part = dir.join(format!("{}.part", name));
+        let before = tokio::fs::metadata(&part).await.map(|m| m.len()).unwrap_or(0);
+        file.state = "downloading".to_string();
+        file.error = None;
+        self.osdr_repo.update_file(&file).await?;
+
+        let mut new_bytes = 0;
+        match self.nasa_client.download_file(&file.remote_url, &part, cap).await {
+            Ok(DownloadOutcome::Complete(size, _)) => {
+                file.sha256 = Some(hash::sha256_file(&part).await?);
+                tokio::fs::rename(&part, &target).await?;
+                file.state = "complete".to_string();
+                file.size_bytes = Some(size as i64);
+                file.downloaded_bytes = size as i64;
+                file.local_path = Some(target.to_string_lossy().into_owned());
+                new_bytes = size.saturating_sub(before);
+            }
+            Ok(DownloadOutcome::TooLarge(size)) => {
+                let _ = tokio::fs::remove_file(&part).await;
+                file.state = "skipped".to_string();
+                file.error = Some(format!("size {} exceeds cap of {} bytes", size, cap));
+                file.downloaded_bytes = 0;
+            }
+            Err(e) => {
+                // .part остается на диске — следующий прогон продолжит с этого места
+                warn!("Download of {} failed: {}", file.remote_url, e);
+                file.state = "failed".to_string();
+                file.error = Some(e.to_string());
+                file.downloaded_bytes = tokio::fs::metadata(&part).await.map(|m| m.len() as i64).unwrap_or(0);
+            }
+        }
+        self.osdr_repo.update_file(&file).await?;
+
+        Ok((file, new_bytes))
+    }
+
+    /// Достает REST_URL из сырой записи OSDR; относительные ссылки
+    /// достраиваются от хоста config.nasa_url.
+    fn osdr_rest_url(&self, raw: &Value) -> Option<String> {
+        let url = json_tools::s_pick(raw, &["REST_URL", "rest_url", "restUrl"])?;
+        if url.starts_with("http://") || url.starts_with("https://") {
+            return Some(url);
+        }
+        reqwest::Url::parse(&self.config.nasa_url).ok()?
+            .join(&url).ok()
+            .map(|u| u.to_string())
     }
 }
 
+/// Имя файла/каталога, безопасное для файловой системы зеркала
+/// (без разделителей путей и ведущих точек).
+fn safe_file_name(name: &str) -> String {
+    let cleaned: String = name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+        .collect();
+    let cleaned = cleaned.trim_start_matches('.');
+    if cleaned.is_empty() { "_".to_string() } else { cleaned.to_string() }
+}
+
+/// Локальное имя файла зеркала: если имя пришлось очистить, к основе добавляется хэш
+/// исходного имени — "a b.txt" и "a_b.txt" не попадут в один файл.
+fn mirror_file_name(name: &str) -> String {
+    let cleaned = safe_file_name(name);
+    if cleaned == name {
+        return cleaned;
+    }
+    let suffix = &hash::sha256_hex(name.as_bytes())[..8];
+    match cleaned.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => format!("{}-{}.{}", stem, suffix, ext),
+        _ => format!("{}-{}", cleaned, suffix),
+    }
+}
+
+/// Промах в лунных расстояниях; неизвестный — дальше любого известного
+fn lunar_or_farthest(lunar: Option<f64>) -> f64 {
+    lunar.unwrap_or(f64::INFINITY)
+}
+
+/// Отметка "ключ в работе": снимается при drop, даже если задача завершилась ошибкой
+struct InFlight<'a> {
+    keys: &'a std::sync::Mutex<HashSet<String>>,
+    key: String,
+}
+
+impl<'a> InFlight<'a> {
+    /// None, если ключ уже занят другой задачей
+    fn claim(keys: &'a std::sync::Mutex<HashSet<String>>, key: &str) -> Option<Self> {
+        let mut set = keys.lock().unwrap_or_else(|e| e.into_inner());
+        set.insert(key.to_string()).then(|| Self { keys, key: key.to_string() })
+    }
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        self.keys.lock().unwrap_or_else(|e| e.into_inner()).remove(&self.key);
+    }
+}
+
+/// Пишет строки NDJSON из канала в gzip-файл, возвращает их число (синхронно — через spawn_blocking)
+fn gzip_ndjson(path: &Path, mut lines: tokio::sync::mpsc::Receiver<String>) -> std::io::Result<usize> {
+    let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    let mut encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+    let mut count = 0;
+    while let Some(line) = lines.blocking_recv() {
+        encoder.write_all(line.as_bytes())?;
+        encoder.write_all(b"\n")?;
+        count += 1;
+    }
+    encoder.finish()?.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+    Ok(count)
+}
+
+/// Читает снимок: dataset_id -> хэш строки NDJSON (синхронно — через spawn_blocking)
+fn read_snapshot_hashes(path: &Path) -> Result<HashMap<String, String>, AppError> {
+    let reader = std::io::BufReader::new(flate2::read::GzDecoder::new(std::fs::File::open(path)?));
+    let mut hashes = HashMap::new();
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let v: Value = serde_json::from_str(&line)
+            .map_err(|e| AppError::SerializationError(format!("Broken snapshot line in {}: {}", path.display(), e)))?;
+        if let Some(id) = v.get("dataset_id").and_then(|x| x.as_str()) {
+            hashes.insert(id.to_string(), hash::json_hash(&v));
+        }
+    }
+    Ok(hashes)
+}
+
 /// Конструктор для внедрения зависимостей.
+#[allow(clippy::too_many_arguments)]
 pub fn new_space_service(
     config: AppConfig, 
     nasa_client: Arc<dyn NasaClientContract>, 
     osdr_repo: Arc<dyn OsdrRepoContract>,
+    space_repo: Arc<dyn SpaceRepoContract>,
+    donki_repo: Arc<dyn DonkiRepoContract>,
+    launch_repo: Arc<dyn LaunchRepoContract>,
+    noaa_client: Arc<dyn NoaaClientContract>,
+    spacex_client: Arc<dyn SpacexClientContract>,
+    ll2_client: Arc<dyn Ll2ClientContract>,
+    celestrak_client: Arc<dyn CelestrakClientContract>,
     // !!! НОВЫЙ ПАРАМЕТР В КОНСТРУКТОРЕ !!!
     legacy_pascal_client: Arc<dyn LegacyPascalClientContract>
 ) -> impl SpaceServiceContract {
-    SpaceService::new(config, nasa_client, osdr_repo, legacy_pascal_client)
+    SpaceService::new(
+        config, nasa_client, osdr_repo, space_repo, donki_repo, launch_repo, noaa_client, spacex_client, ll2_client,
+        celestrak_client, legacy_pascal_client,
+    )
 }
 
 // =========================================================================================
@@ -62,20 +442,28 @@ impl SpaceServiceContract for SpaceService {
     /// ОСНОВНАЯ ЗАДАЧА: OSDR (Orchestrates Client call and Repo UPSERT)
     /// -------------------------------------------------------------------------------------
     #[instrument(skip(self), level = "info")]
-    async fn fetch_and_save_osdr_data(&self) -> Result<(), AppError> {
+    async fn fetch_and_save_osdr_data(&self) -> Result<OsdrSyncStats, AppError> {
         info!("Starting fetch_and_save_osdr_data cycle...");
 
+        let started_at = chrono::Utc::now();
         let osdr_url = &self.config.nasa_url;
         
-        // 1. Fetch
-        let items = self.nasa_client.fetch_osdr_list(osdr_url).await?;
-        info!("Successfully fetched {} items from OSDR API.", items.len());
+        // Fetch (условный запрос) + Save/Upsert только изменившихся записей
+        let mut stats = OsdrSyncStats::default();
+        let (next_url, total) = self.sync_osdr_page(osdr_url, &mut stats).await?;
 
-        // 2. Save/Upsert
-        self.osdr_repo.upsert_osdr_items(&items).await?;
-        info!("Successfully UPSERTED OSDR data into database.");
-        
-        Ok(())
+        // Отсутствие ссылки на следующую страницу еще не доказывает полноту фида:
+        // отозванные ищем, только если upstream сообщил общее число и оно совпало с увиденным
+        if next_url.is_none() && total.is_some_and(|t| t == stats.items_fetched) {
+            stats.items_withdrawn = self.osdr_repo.mark_withdrawn(started_at).await?;
+        }
+        info!(
+            "OSDR sync: {} fetched, {} unchanged, {} written, {} pages not modified.",
+            stats.items_fetched, stats.items_unchanged, stats.items_written, stats.pages_not_modified
+        );
+
+        self.osdr_repo.record_sync("sync", started_at, &stats).await?;
+        Ok(stats)
     }
 
     /// -------------------------------------------------------------------------------------
@@ -103,10 +491,400 @@ impl SpaceServiceContract for SpaceService {
     /// Получение списка OSDR
     /// -------------------------------------------------------------------------------------
     #[instrument(skip(self), level = "info")]
-    async fn get_osdr_list(&self) -> Result<Vec<OsdrItem>, AppError> {
+    async fn get_osdr_list(&self, include_withdrawn: bool) -> Result<Vec<OsdrItem>, AppError> {
         info!("Getting OSDR list...");
         // Получаем 100 записей или все, в зависимости от того, что реализовано
-        self.osdr_repo.get_list(100).await
+        self.osdr_repo.get_list(100, include_withdrawn).await
+    }
+
+    /// -------------------------------------------------------------------------------------
+    /// Детальная карточка OSDR (REST_URL + кэш в Postgres с TTL, stale при сбое upstream)
+    /// -------------------------------------------------------------------------------------
+    #[instrument(skip(self), level = "info")]
+    async fn get_osdr_detail(&self, dataset_id: &str) -> Result<OsdrDetail, AppError> {
+        let item = self.osdr_repo.get_by_dataset_id(dataset_id).await?
+            .ok_or_else(|| AppError::NotFound(format!("OSDR dataset '{}' not found", dataset_id)))?;
+
+        let cached = self.osdr_repo.get_detail_cache(dataset_id).await?;
+        let ttl = chrono::Duration::seconds(self.config.osdr_detail_ttl as i64);
+        if let Some(c) = &cached {
+            if chrono::Utc::now() - c.fetched_at < ttl {
+                return Ok(OsdrDetail {
+                    detail: Some(c.payload.clone()),
+                    detail_fetched_at: Some(c.fetched_at),
+                    detail_stale: false,
+                    item,
+                });
+            }
+        }
+
+        let Some(rest_url) = self.osdr_rest_url(&item.raw) else {
+            warn!("OSDR dataset {} has no REST_URL, serving cached detail only.", dataset_id);
+            return Ok(OsdrDetail {
+                detail: cached.as_ref().map(|c| c.payload.clone()),
+                detail_fetched_at: cached.as_ref().map(|c| c.fetched_at),
+                detail_stale: cached.is_some(),
+                item,
+            });
+        };
+
+        match self.nasa_client.fetch_osdr_detail(&rest_url).await {
+            Ok(payload) => {
+                self.osdr_repo.save_detail_cache(dataset_id, &payload).await?;
+                Ok(OsdrDetail {
+                    detail: Some(payload),
+                    detail_fetched_at: Some(chrono::Utc::now()),
+                    detail_stale: false,
+                    item,
+                })
+            }
+            Err(e) => match cached {
+                Some(c) => {
+                    warn!("OSDR detail fetch for {} failed: {}. Serving stale cache.", dataset_id, e);
+                    Ok(OsdrDetail {
+                        detail: Some(c.payload),
+                        detail_fetched_at: Some(c.fetched_at),
+                        detail_stale: true,
+                        item,
+                    })
+                }
+                None => Err(e),
+            },
+        }
+    }
+
+    /// -------------------------------------------------------------------------------------
+    /// Полный обход OSDR: чекпоинт после каждой страницы, лимит страниц за запуск
+    /// -------------------------------------------------------------------------------------
+    #[instrument(skip(self), level = "info")]
+    async fn crawl_osdr(&self) -> Result<OsdrCrawlState, AppError> {
+        let Ok(_guard) = self.osdr_crawl_lock.try_lock() else {
+            return Err(AppError::ClientError("OSDR crawl is already running".to_string(), StatusCode::CONFLICT));
+        };
+        let now = chrono::Utc::now();
+        let mut state = match self.osdr_repo.get_crawl_state(OSDR_CRAWL).await? {
+            // Незавершенный обход продолжаем с сохраненной страницы
+            Some(s) if s.status != "completed" && s.next_url.is_some() => {
+                info!("Resuming OSDR crawl from page {} ({:?}).", s.pages_done + 1, s.next_url);
+                s
+            }
+            _ => OsdrCrawlState {
+                crawl: OSDR_CRAWL.to_string(),
+                status: "running".to_string(),
+                next_url: Some(self.config.nasa_url.clone()),
+                pages_done: 0,
+                items_done: 0,
+                started_at: now,
+                updated_at: now,
+                finished_at: None,
+                last_error: None,
+            },
+        };
+        state.status = "running".to_string();
+        state.last_error = None;
+
+        let mut stats = OsdrSyncStats::default();
+        for _ in 0..self.config.osdr_crawl_page_budget.max(1) {
+            let Some(url) = state.next_url.clone() else { break };
+
+            let fetched_before = stats.items_fetched;
+            let next_url = match self.sync_osdr_page(&url, &mut stats).await {
+                Ok((next_url, _)) => next_url,
+                Err(e) => {
+                    error!("OSDR crawl failed on {}: {}", url, e);
+                    state.status = "failed".to_string();
+                    state.last_error = Some(e.to_string());
+                    state.updated_at = chrono::Utc::now();
+                    self.osdr_repo.save_crawl_state(&state).await?;
+                    self.osdr_repo.record_sync("crawl", now, &stats).await?;
+                    return Err(e);
+                }
+            };
+
+            state.pages_done += 1;
+            state.items_done += stats.items_fetched - fetched_before;
+            // Защита от зацикливания на ссылке на ту же страницу
+            state.next_url = next_url.filter(|next| *next != url);
+            state.updated_at = chrono::Utc::now();
+            if state.next_url.is_none() {
+                state.status = "completed".to_string();
+                state.finished_at = Some(state.updated_at);
+                // Обход завершен: все, что не встречалось с его начала, отозвано upstream
+                stats.items_withdrawn = self.osdr_repo.mark_withdrawn(state.started_at).await?;
+                info!("OSDR crawl marked {} datasets as withdrawn.", stats.items_withdrawn);
+            }
+            self.osdr_repo.save_crawl_state(&state).await?;
+        }
+
+        if state.status == "running" {
+            state.status = "paused".to_string();
+            self.osdr_repo.save_crawl_state(&state).await?;
+        }
+        info!("OSDR crawl {}: {} pages, {} items.", state.status, state.pages_done, state.items_done);
+        self.osdr_repo.record_sync("crawl", now, &stats).await?;
+
+        Ok(state)
+    }
+
+    async fn get_osdr_crawl_state(&self) -> Result<Option<OsdrCrawlState>, AppError> {
+        self.osdr_repo.get_crawl_state(OSDR_CRAWL).await
+    }
+
+    async fn get_osdr_sync_log(&self, limit: i64) -> Result<Vec<OsdrSyncRun>, AppError> {
+        self.osdr_repo.get_sync_log(limit).await
+    }
+
+    #[instrument(skip(self), level = "info")]
+    async fn mirror_osdr_dataset(&self, dataset_id: &str) -> Result<MirrorReport, AppError> {
+        // Два прогона одного датасета писали бы в одни и те же .part
+        let Some(_guard) = InFlight::claim(&self.osdr_mirror_jobs, dataset_id) else {
+            return Err(AppError::ClientError(
+                format!("OSDR dataset '{}' is already being mirrored", dataset_id),
+                StatusCode::CONFLICT,
+            ));
+        };
+        self.osdr_repo.get_by_dataset_id(dataset_id).await?
+            .ok_or_else(|| AppError::NotFound(format!("OSDR dataset '{}' not found", dataset_id)))?;
+
+        let listing_url = self.config.osdr_files_url.replace("{dataset_id}", dataset_id);
+        let remote = self.nasa_client.fetch_osdr_file_list(&listing_url).await?;
+        info!("OSDR dataset {} lists {} files.", dataset_id, remote.len());
+        self.osdr_repo.upsert_file_listing(dataset_id, &remote).await?;
+
+        let dir = PathBuf::from(&self.config.osdr_mirror_dir).join(safe_file_name(dataset_id));
+        tokio::fs::create_dir_all(&dir).await?;
+
+        let mut report = MirrorReport { datasets: 1, ..Default::default() };
+        for file in self.osdr_repo.get_files(dataset_id).await? {
+            let (file, new_bytes) = self.mirror_file(file, &dir).await?;
+            report.bytes_downloaded += new_bytes as i64;
+            match file.state.as_str() {
+                "complete" => report.files_complete += 1,
+                "skipped" => report.files_skipped += 1,
+                _ => report.files_failed += 1,
+            }
+        }
+        info!(
+            "OSDR mirror {}: {} complete, {} skipped, {} failed, {} bytes downloaded.",
+            dataset_id, report.files_complete, report.files_skipped, report.files_failed, report.bytes_downloaded
+        );
+
+        // ISA-Tab из свежего зеркала; датасеты без ISA-Tab не считаются ошибкой зеркалирования
+        if report.files_complete > 0 {
+            if let Err(e) = self.import_osdr_isa(dataset_id).await {
+                warn!("OSDR ISA-Tab import for {} skipped: {}", dataset_id, e);
+            }
+        }
+
+        Ok(report)
+    }
+
+    #[instrument(skip(self), level = "info")]
+    async fn mirror_osdr(&self, filter: &OsdrMirrorFilter) -> Result<MirrorReport, AppError> {
+        let mut report = MirrorReport::default();
+        for dataset_id in self.osdr_repo.find_dataset_ids(filter).await? {
+            match self.mirror_osdr_dataset(&dataset_id).await {
+                Ok(r) => {
+                    report.datasets += 1;
+                    report.files_complete += r.files_complete;
+                    report.files_skipped += r.files_skipped;
+                    report.files_failed += r.files_failed;
+                    report.bytes_downloaded += r.bytes_downloaded;
+                }
+                Err(e) => error!("OSDR mirror of {} failed: {}", dataset_id, e),
+            }
+        }
+        Ok(report)
+    }
+
+    async fn get_osdr_files(&self, dataset_id: &str) -> Result<Vec<OsdrFile>, AppError> {
+        self.osdr_repo.get_files(dataset_id).await
+    }
+
+    async fn get_osdr_file(&self, dataset_id: &str, file_name: &str) -> Result<OsdrFile, AppError> {
+        self.osdr_repo.get_file(dataset_id, file_name).await?
+            .filter(|f| f.state == "complete" && f.local_path.is_some())
+            .ok_or_else(|| AppError::NotFound(format!("File '{}' of '{}' is not mirrored", file_name, dataset_id)))
+    }
+
+    #[instrument(skip(self), level = "info")]
+    async fn import_osdr_isa(&self, dataset_id: &str) -> Result<IsaArchive, AppError> {
+        let dir = PathBuf::from(&self.config.osdr_mirror_dir).join(safe_file_name(dataset_id));
+        if !tokio::fs::try_exists(&dir).await? {
+            return Err(AppError::NotFound(format!("OSDR dataset '{}' is not mirrored", dataset_id)));
+        }
+
+        // Разбор синхронный (std::fs, zip) — выносим из рантайма
+        let archive = tokio::task::spawn_blocking(move || isatab::parse_mirror(&dir))
+            .await
+            .map_err(|e| AppError::InternalError(format!("ISA-Tab parser task failed: {}", e)))??;
+
+        self.osdr_repo.replace_isa(dataset_id, &archive).await?;
+        info!(
+            "OSDR ISA-Tab {}: {} protocols, {} samples, {} assays from {}.",
+            dataset_id, archive.protocols.len(), archive.samples.len(), archive.assays.len(), archive.source
+        );
+        Ok(archive)
+    }
+
+    async fn get_osdr_isa(&self, dataset_id: &str) -> Result<IsaArchive, AppError> {
+        self.osdr_repo.get_isa(dataset_id).await?
+            .ok_or_else(|| AppError::NotFound(format!("No ISA-Tab metadata for '{}'", dataset_id)))
+    }
+
+    async fn get_osdr_related(&self, dataset_id: &str, limit: i64) -> Result<Vec<OsdrRelated>, AppError> {
+        self.osdr_repo.get_by_dataset_id(dataset_id).await?
+            .ok_or_else(|| AppError::NotFound(format!("OSDR dataset '{}' not found", dataset_id)))?;
+        self.osdr_repo.get_related(dataset_id, limit.clamp(1, 100)).await
+    }
+
+    #[instrument(skip(self), level = "info")]
+    async fn create_osdr_snapshot(&self) -> Result<OsdrSnapshot, AppError> {
+        let created_at = chrono::Utc::now();
+        // Микросекунды: два снимка подряд не должны перезаписать друг друга
+        let id = created_at.format("%Y%m%dT%H%M%S%6fZ").to_string();
+
+        let dir = PathBuf::from(&self.config.osdr_snapshot_dir);
+        tokio::fs::create_dir_all(&dir).await?;
+        let file_name = format!("osdr-{}.ndjson.gz", id);
+        let path = dir.join(&file_name);
+        let part = dir.join(format!("{}.part", file_name));
+
+        // Строки читаются из БД потоком и сжимаются в отдельном потоке прямо в файл
+        let (tx, rx) = tokio::sync::mpsc::channel::<String>(SNAPSHOT_CHANNEL_LINES);
+        let writer_part = part.clone();
+        let writer = tokio::task::spawn_blocking(move || gzip_ndjson(&writer_part, rx));
+
+        // Строка NDJSON — живая запись osdr_items без last_seen_at: он меняется при каждой синхронизации
+        let mut items = self.osdr_repo.stream_live();
+        let mut read_err = None;
+        while let Some(item) = items.next().await {
+            let line = item.and_then(|item| {
+                let mut v = serde_json::to_value(&item).map_err(|e| AppError::SerializationError(e.to_string()))?;
+                if let Some(obj) = v.as_object_mut() {
+                    obj.remove("last_seen_at");
+                }
+                Ok(v.to_string())
+            });
+            match line {
+                Ok(line) => {
+                    if tx.send(line).await.is_err() {
+                        break; // писатель упал — его ошибку вернет join ниже
+                    }
+                }
+                Err(e) => {
+                    read_err = Some(e);
+                    break;
+                }
+            }
+        }
+        drop(items);
+        drop(tx);
+
+        let written = writer.await
+            .map_err(|e| AppError::InternalError(format!("Snapshot task failed: {}", e)))?;
+        let item_count = match read_err.map_or_else(|| written.map_err(AppError::from), Err) {
+            Ok(count) => count as i64,
+            Err(e) => {
+                let _ = tokio::fs::remove_file(&part).await;
+                return Err(e);
+            }
+        };
+        tokio::fs::rename(&part, &path).await?;
+
+        let size_bytes = tokio::fs::metadata(&path).await?.len() as i64;
+        let sha256 = hash::sha256_file(&path).await?;
+
+        let snapshot = OsdrSnapshot {
+            id: id.clone(),
+            created_at,
+            item_count,
+            sha256,
+            size_bytes,
+            file_path: path.to_string_lossy().into_owned(),
+        };
+        let manifest = serde_json::json!({
+            "id": snapshot.id,
+            "created_at": snapshot.created_at,
+            "item_count": snapshot.item_count,
+            "sha256": snapshot.sha256,
+            "size_bytes": snapshot.size_bytes,
+            "file": file_name,
+        });
+        tokio::fs::write(dir.join(format!("osdr-{}.manifest.json", id)), manifest.to_string()).await?;
+        self.osdr_repo.save_snapshot(&snapshot).await?;
+
+        info!("OSDR snapshot {}: {} items, {} bytes.", snapshot.id, snapshot.item_count, snapshot.size_bytes);
+        Ok(snapshot)
+    }
+
+    async fn get_osdr_snapshots(&self) -> Result<Vec<OsdrSnapshot>, AppError> {
+        self.osdr_repo.get_snapshots(1000).await
+    }
+
+    async fn get_osdr_snapshot(&self, id: &str) -> Result<OsdrSnapshot, AppError> {
+        self.osdr_repo.get_snapshot(id).await?
+            .ok_or_else(|| AppError::NotFound(format!("OSDR snapshot '{}' not found", id)))
+    }
+
+    #[instrument(skip(self), level = "info")]
+    async fn diff_osdr_snapshots(&self, from: &str, to: &str) -> Result<OsdrSnapshotDiff, AppError> {
+        let from_path = PathBuf::from(self.get_osdr_snapshot(from).await?.file_path);
+        let to_path = PathBuf::from(self.get_osdr_snapshot(to).await?.file_path);
+
+        let (old, new) = tokio::task::spawn_blocking(move || {
+            Ok::<_, AppError>((read_snapshot_hashes(&from_path)?, read_snapshot_hashes(&to_path)?))
+        })
+        .await
+        .map_err(|e| AppError::InternalError(format!("Snapshot diff task failed: {}", e)))??;
+
+        let mut added: Vec<String> = new.keys().filter(|id| !old.contains_key(*id)).cloned().collect();
+        let mut removed: Vec<String> = old.keys().filter(|id| !new.contains_key(*id)).cloned().collect();
+        let mut changed: Vec<String> = new.iter()
+            .filter(|(id, h)| old.get(*id).is_some_and(|o| o != *h))
+            .map(|(id, _)| id.clone())
+            .collect();
+        added.sort();
+        removed.sort();
+        changed.sort();
+
+        Ok(OsdrSnapshotDiff { from: from.to_string(), to: to.to_string(), added, removed, changed })
+    }
+
+    #[instrument(skip(self), level = "info")]
+    async fn get_osdr_graph(&self, min_weight: i32) -> Result<OsdrGraph, AppError> {
+        let ttl = chrono::Duration::seconds(self.config.osdr_stats_ttl as i64);
+        let cached = self.osdr_graph_cache.read().await.as_ref()
+            .filter(|g| chrono::Utc::now() - g.computed_at < ttl)
+            .cloned();
+        let graph = match cached {
+            Some(graph) => graph,
+            None => {
+                let graph = self.osdr_repo.get_graph(1).await?;
+                *self.osdr_graph_cache.write().await = Some(graph.clone());
+                graph
+            }
+        };
+
+        let min_weight = min_weight.max(1);
+        Ok(OsdrGraph {
+            edges: graph.edges.into_iter().filter(|e| e.weight >= min_weight).collect(),
+            ..graph
+        })
+    }
+
+    #[instrument(skip(self), level = "info")]
+    async fn get_osdr_stats(&self) -> Result<OsdrStats, AppError> {
+        let ttl = chrono::Duration::seconds(self.config.osdr_stats_ttl as i64);
+        if let Some(stats) = self.osdr_stats_cache.read().await.as_ref() {
+            if chrono::Utc::now() - stats.computed_at < ttl {
+                return Ok(stats.clone());
+            }
+        }
+
+        let stats = self.osdr_repo.get_stats().await?;
+        *self.osdr_stats_cache.write().await = Some(stats.clone());
+        Ok(stats)
     }
 
     /// -------------------------------------------------------------------------------------
@@ -120,32 +898,910 @@ impl SpaceServiceContract for SpaceService {
         Ok(())
     }
 
+    /// -------------------------------------------------------------------------------------
+    /// APOD: архив по датам в apod_entries, витрина последней записи в space_cache
+    /// -------------------------------------------------------------------------------------
     #[instrument(skip(self), level = "info")]
     async fn fetch_and_cache_apod(&self) -> Result<(), AppError> {
-        warn!("STUB: fetch_and_cache_apod not yet implemented in Service Layer.");
-        // TODO: Реализовать логику APOD
+        // Последние дни целиком: upstream иногда правит записи задним числом
+        let today = ApodEntry::latest_date();
+        let start = today - Duration::days(APOD_RECENT_DAYS - 1);
+        let items = self.nasa_client.fetch_apod(&start.to_string(), None, self.nasa_api_key()).await?;
+        let written = self.space_repo.upsert_apod(&items).await?;
+        info!("APOD refresh: {} entries saved.", written);
+
+        let recent = self.space_repo.get_apod_range(start, today).await?;
+        self.cache_apod_media_for(&recent).await;
+
+        if let Some(latest) = recent.last() {
+            let payload = serde_json::to_value(latest).map_err(|e| AppError::SerializationError(e.to_string()))?;
+            self.space_repo.write_cache("apod", &payload).await?;
+        }
         Ok(())
     }
 
+    async fn get_apod(&self, date: NaiveDate) -> Result<ApodEntry, AppError> {
+        let (date, _) = Self::apod_bounds(date, date)?;
+        if let Some(entry) = self.space_repo.get_apod(date).await? {
+            return Ok(entry);
+        }
+        let not_found = || AppError::NotFound(format!("No APOD for {}", date));
+        // Без записи, но дата известна — это пропуск архива
+        if self.space_repo.count_apod(date, date).await? > 0 {
+            return Err(not_found());
+        }
+
+        let d = date.to_string();
+        let items = self.nasa_client.fetch_apod(&d, Some(&d), self.nasa_api_key()).await?;
+        self.space_repo.upsert_apod(&items).await?;
+        match self.space_repo.get_apod(date).await? {
+            Some(entry) => Ok(entry),
+            None => {
+                self.mark_apod_gaps(date, date).await?;
+                Err(not_found())
+            }
+        }
+    }
+
+    async fn get_apod_range(&self, start: NaiveDate, end: NaiveDate) -> Result<Vec<ApodEntry>, AppError> {
+        let (start, end) = Self::apod_bounds(start, end)?;
+        if (end - start).num_days() >= APOD_MAX_RANGE_DAYS {
+            return Err(AppError::ClientError(
+                format!("APOD range is limited to {} days", APOD_MAX_RANGE_DAYS),
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+
+        // Недостающие даты догружаем; при сбое upstream отдаем то, что есть
+        let days = (end - start).num_days() + 1;
+        if self.space_repo.count_apod(start, end).await? < days {
+            if let Err(e) = self.backfill_apod(start, end).await {
+                warn!("APOD backfill {}..{} failed, serving stored entries: {}", start, end, e);
+            }
+        }
+        self.space_repo.get_apod_range(start, end).await
+    }
+
+    #[instrument(skip(self), level = "info")]
+    async fn backfill_apod(&self, start: NaiveDate, end: NaiveDate) -> Result<u64, AppError> {
+        let (start, end) = Self::apod_bounds(start, end)?;
+
+        let mut written = 0;
+        let mut window_start = start;
+        while window_start <= end {
+            let window_end = (window_start + Duration::days(APOD_BACKFILL_WINDOW_DAYS - 1)).min(end);
+            let days = (window_end - window_start).num_days() + 1;
+
+            // Окно уже сохранено целиком — upstream не трогаем
+            if self.space_repo.count_apod(window_start, window_end).await? < days {
+                let items = self.nasa_client.fetch_apod(
+                    &window_start.to_string(), Some(&window_end.to_string()), self.nasa_api_key(),
+                ).await?;
+                written += self.space_repo.upsert_apod(&items).await?;
+                self.mark_apod_gaps(window_start, window_end).await?;
+                let entries = self.space_repo.get_apod_range(window_start, window_end).await?;
+                self.cache_apod_media_for(&entries).await;
+            }
+            window_start = window_end + Duration::days(1);
+        }
+
+        info!("APOD backfill {}..{}: {} entries saved.", start, end, written);
+        Ok(written)
+    }
+
+    async fn search_apod(&self, filter: &ApodSearchFilter) -> Result<Vec<ApodSearchHit>, AppError> {
+        if filter.q.as_deref().is_none_or(|q| q.trim().is_empty()) {
+            return Err(AppError::ClientError("Search query q is required".to_string(), StatusCode::BAD_REQUEST));
+        }
+        self.space_repo.search_apod(filter).await
+    }
+
+    #[instrument(skip(self), level = "info")]
+    async fn cache_apod_media(&self, date: NaiveDate) -> Result<ApodMedia, AppError> {
+        let entry = self.get_apod(date).await?;
+        let fresh = |m: &ApodMedia| {
+            let stored = m.original_path.as_deref().is_some_and(|p| Path::new(p).exists());
+            let backoff = matches!(m.state.as_str(), "failed" | "skipped") && m.retry_after.is_some_and(|t| t > Utc::now());
+            m.state == "video" || (m.state == "complete" && stored) || backoff
+        };
+        if let Some(m) = self.space_repo.get_apod_media(date).await?.filter(fresh) {
+            return Ok(m);
+        }
+
+        // Дату уже качает другой запрос — не трогаем его original.part, отдаем ссылку на upstream
+        let Some(_guard) = InFlight::claim(&self.apod_media_jobs, &date.to_string()) else {
+            return Ok(ApodMedia {
+                date,
+                source_url: entry.url.clone(),
+                state: "pending".to_string(),
+                updated_at: Utc::now(),
+                ..Default::default()
+            });
+        };
+        // Между проверкой и захватом предыдущий владелец мог успеть сохранить результат
+        if let Some(m) = self.space_repo.get_apod_media(date).await?.filter(fresh) {
+            return Ok(m);
+        }
+
+        let mut media = ApodMedia { date, updated_at: Utc::now(), ..Default::default() };
+        if entry.media_type.as_deref() != Some("image") {
+            media.state = "video".to_string();
+            media.source_url = entry.url.clone();
+            self.space_repo.save_apod_media(&media).await?;
+            return Ok(media);
+        }
+
+        let dir = PathBuf::from(&self.config.apod_media_dir).join(date.to_string());
+        tokio::fs::create_dir_all(&dir).await?;
+        let part = dir.join("original.part");
+        let max_bytes = self.config.apod_media_max_bytes;
+
+        // Сначала HD-версия; если она больше лимита — обычная
+        let mut candidates: Vec<String> = [&entry.hdurl, &entry.url].into_iter().flatten().cloned().collect();
+        candidates.dedup();
+        let mut downloaded = None;
+        let mut too_large = false;
+        for url in candidates {
+            let _ = tokio::fs::remove_file(&part).await;
+            match self.nasa_client.download_file(&url, &part, max_bytes).await {
+                Ok(DownloadOutcome::Complete(bytes, content_type)) => match imaging::image_kind(content_type.as_deref(), &url) {
+                    Some(kind) => {
+                        downloaded = Some((url, bytes, kind));
+                        break;
+                    }
+                    None => {
+                        warn!("APOD image {} has unsupported Content-Type {:?}.", url, content_type);
+                        media.error = Some(format!("{} is not a supported image ({})", url, content_type.unwrap_or_default()));
+                    }
+                },
+                Ok(DownloadOutcome::TooLarge(bytes)) => {
+                    info!("APOD image {} is {} bytes, over the {} byte cap.", url, bytes, max_bytes);
+                    too_large = true;
+                    media.error = Some(format!("{} exceeds {} bytes", url, max_bytes));
+                }
+                Err(e) => {
+                    warn!("APOD image download {} failed: {}", url, e);
+                    media.error = Some(e.to_string());
+                }
+            }
+        }
+
+        let Some((url, bytes, (ext, content_type))) = downloaded else {
+            let _ = tokio::fs::remove_file(&part).await;
+            let (state, retry_in) = if too_large {
+                ("skipped", Duration::days(APOD_MEDIA_SKIPPED_RETRY_DAYS))
+            } else {
+                ("failed", Duration::hours(APOD_MEDIA_FAILED_RETRY_HOURS))
+            };
+            media.state = state.to_string();
+            media.retry_after = Some(Utc::now() + retry_in);
+            media.source_url = entry.url.clone();
+            self.space_repo.save_apod_media(&media).await?;
+            return Ok(media);
+        };
+
+        let original = dir.join(format!("original.{}", ext));
+        tokio::fs::rename(&part, &original).await?;
+        media.source_url = Some(url);
+        media.original_path = Some(original.to_string_lossy().into_owned());
+        media.content_type = Some(content_type.to_string());
+        media.size_bytes = Some(bytes as i64);
+        media.sha256 = Some(hash::sha256_file(&original).await?);
+        media.error = None;
+
+        let (thumb, medium) = (dir.join("thumb.jpg"), dir.join("medium.jpg"));
+        let (t, m) = (thumb.clone(), medium.clone());
+        let rendered = tokio::task::spawn_blocking(move || imaging::render_variants(&original, &t, &m))
+            .await
+            .map_err(|e| AppError::InternalError(format!("APOD render task failed: {}", e)))?;
+        match rendered {
+            Ok((width, height)) => {
+                media.state = "complete".to_string();
+                media.width = Some(width as i32);
+                media.height = Some(height as i32);
+                media.thumb_path = Some(thumb.to_string_lossy().into_owned());
+                media.medium_path = Some(medium.to_string_lossy().into_owned());
+            }
+            // Не изображение (например, HTML-заглушка) или сверх пределов декодирования — оригинал не отдаем
+            Err(e) => {
+                media.state = "failed".to_string();
+                media.error = Some(format!("Cannot decode image: {}", e));
+                media.retry_after = Some(Utc::now() + Duration::hours(APOD_MEDIA_FAILED_RETRY_HOURS));
+            }
+        }
+
+        self.space_repo.save_apod_media(&media).await?;
+        Ok(media)
+    }
+
+    async fn get_apod_image(&self, date: NaiveDate, size: &str) -> Result<ApodImage, AppError> {
+        if !matches!(size, "thumb" | "medium" | "original") {
+            return Err(AppError::ClientError(
+                format!("Unknown image size '{}', expected thumb, medium or original", size),
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+
+        let entry = self.get_apod(date).await?;
+        let original_url = || entry.url.clone()
+            .ok_or_else(|| AppError::NotFound(format!("APOD {} has no media URL", date)));
+
+        // Видео и незакэшированные изображения — ссылка на upstream
+        if entry.media_type.as_deref() != Some("image") {
+            return Ok(ApodImage::Redirect(original_url()?));
+        }
+        let media = self.cache_apod_media(date).await?;
+        if media.state != "complete" {
+            return Ok(ApodImage::Redirect(original_url()?));
+        }
+
+        let (path, content_type) = match size {
+            "thumb" => (media.thumb_path, "image/jpeg".to_string()),
+            "medium" => (media.medium_path, "image/jpeg".to_string()),
+            _ => (media.original_path, media.content_type.unwrap_or_else(|| "image/jpeg".to_string())),
+        };
+        let path = path.ok_or_else(|| AppError::NotFound(format!("APOD {} has no {} image", date, size)))?;
+
+        Ok(ApodImage::File {
+            path,
+            content_type,
+            etag: format!("\"{}-{}\"", media.sha256.unwrap_or_default(), size),
+            updated_at: media.updated_at,
+        })
+    }
+
+    /// -------------------------------------------------------------------------------------
+    /// NeoWs: объекты и сближения в neo_objects/neo_approaches, счетчики в space_cache
+    /// -------------------------------------------------------------------------------------
     #[instrument(skip(self), level = "info")]
     async fn fetch_and_cache_neo(&self) -> Result<(), AppError> {
-        warn!("STUB: fetch_and_cache_neo not yet implemented in Service Layer.");
-        // TODO: Реализовать логику NEO
+        let today = Utc::now().date_naive();
+        let written = self.fetch_neo_range(today, today + Duration::days(NEO_REFRESH_DAYS - 1)).await?;
+        info!("NeoWs refresh: {} objects saved.", written);
+
+        let counts = self.space_repo.get_neo_counts().await?;
+        let payload = serde_json::to_value(&counts).map_err(|e| AppError::SerializationError(e.to_string()))?;
+        self.space_repo.write_cache("neo", &payload).await?;
         Ok(())
     }
+
+    #[instrument(skip(self), level = "info")]
+    async fn fetch_neo_range(&self, start: NaiveDate, end: NaiveDate) -> Result<u64, AppError> {
+        if start > end {
+            return Err(AppError::ClientError(format!("Empty NeoWs range {}..{}", start, end), StatusCode::BAD_REQUEST));
+        }
+        if (end - start).num_days() >= NEO_MAX_RANGE_DAYS {
+            return Err(AppError::ClientError(
+                format!("NeoWs range is limited to {} days", NEO_MAX_RANGE_DAYS),
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+
+        let mut written = 0;
+        let mut window_start = start;
+        while window_start <= end {
+            let window_end = (window_start + Duration::days(NEO_FEED_WINDOW_DAYS - 1)).min(end);
+            let feed = self.nasa_client.fetch_neo_feed(
+                &window_start.to_string(), &window_end.to_string(), self.nasa_api_key(),
+            ).await?;
+
+            // near_earth_objects: { "YYYY-MM-DD": [объекты] }
+            let objects: Vec<Value> = feed["near_earth_objects"].as_object()
+                .map(|by_date| by_date.values().filter_map(|v| v.as_array()).flatten().cloned().collect())
+                .unwrap_or_default();
+            written += self.space_repo.upsert_neo(&objects).await?;
+
+            window_start = window_end + Duration::days(1);
+        }
+
+        // Правила тревог проверяются после каждой загрузки ленты
+        let created = self.evaluate_neo_alerts().await?;
+        if created > 0 {
+            info!("NEO alerts: {} new.", created);
+        }
+        Ok(written)
+    }
+
+    #[instrument(skip(self), level = "info")]
+    async fn evaluate_neo_alerts(&self) -> Result<u64, AppError> {
+        let rules = &self.config.neo_alert_rules;
+        let from = Utc::now();
+        // Дальше окна планового обновления лента не подгружается — там тревоги строились бы по устаревшим данным
+        let to = from + Duration::days(rules.horizon_days.clamp(1, NEO_REFRESH_DAYS));
+        let approaches = self.space_repo.get_neo_approaches(from, to, false, i64::MAX).await?;
+
+        // Одна тревога на объект и дату: при нескольких сближениях за день — самое близкое
+        let mut alerts: Vec<NeoAlert> = Vec::new();
+        for a in &approaches {
+            let Some((severity, reasons)) = rules.evaluate(a) else { continue };
+            let alert = NeoAlert::from_approach(a, severity, reasons);
+            // Без промаха — самое дальнее (Option сравнивает None как меньшее)
+            let miss = lunar_or_farthest(alert.miss_distance_lunar);
+            match alerts.iter_mut().find(|x| x.neo_id == alert.neo_id && x.approach_date == alert.approach_date) {
+                Some(existing) if miss.partial_cmp(&lunar_or_farthest(existing.miss_distance_lunar)) == Some(Ordering::Less) => {
+                    *existing = alert
+                }
+                Some(_) => {}
+                None => alerts.push(alert),
+            }
+        }
+
+        self.space_repo.replace_neo_alerts(from, to, &alerts).await
+    }
+
+    async fn get_neo_alerts(&self, days: i64, min_severity: &str, limit: i64) -> Result<Vec<NeoAlert>, AppError> {
+        let min_level = match min_severity {
+            "info" => 1,
+            "warning" => 2,
+            "critical" => 3,
+            other => return Err(AppError::ClientError(
+                format!("Unknown severity '{}', expected info|warning|critical", other),
+                StatusCode::BAD_REQUEST,
+            )),
+        };
+        let now = Utc::now();
+        let to = now + Duration::days(days.clamp(1, 365));
+        self.space_repo.get_neo_alerts(now, to, min_level, limit.clamp(1, 500)).await
+    }
+
+    async fn get_neo_upcoming(&self, days: i64, hazardous_only: bool, limit: i64) -> Result<Vec<NeoApproachView>, AppError> {
+        let now = Utc::now();
+        let to = now + Duration::days(days.clamp(1, 365));
+        self.space_repo.get_neo_approaches(now, to, hazardous_only, limit.clamp(1, 500)).await
+    }
+
+    async fn get_neo_counts(&self) -> Result<NeoCounts, AppError> {
+        self.space_repo.get_neo_counts().await
+    }
+
+    #[instrument(skip(self), level = "info")]
+    async fn get_neo_detail(&self, neo_id: &str) -> Result<NeoDetail, AppError> {
+        if neo_id.is_empty() || !neo_id.chars().all(|c| c.is_ascii_digit()) {
+            return Err(AppError::ClientError(format!("Invalid NEO id '{}'", neo_id), StatusCode::BAD_REQUEST));
+        }
+
+        let cached = self.space_repo.get_neo_detail(neo_id).await?;
+        let fresh = cached.as_ref()
+            .and_then(|d| d.orbit.lookup_at)
+            .is_some_and(|at| at > Utc::now() - Duration::hours(NEO_LOOKUP_MAX_AGE_HOURS));
+        if fresh {
+            return cached.ok_or_else(|| AppError::InternalError("NEO detail vanished".to_string()));
+        }
+
+        match self.nasa_client.fetch_neo_lookup(neo_id, self.nasa_api_key()).await {
+            Ok(Some(raw)) => self.space_repo.save_neo_lookup(&raw).await?,
+            Ok(None) => {
+                if cached.is_none() {
+                    return Err(AppError::NotFound(format!("NEO {} not found", neo_id)));
+                }
+                // lookup отвечает 404 — следующая попытка не раньше, чем через NEO_LOOKUP_MAX_AGE_HOURS
+                self.space_repo.touch_neo_lookup(neo_id).await?;
+            }
+            // NeoWs недоступен — отдаем то, что уже есть локально
+            Err(e) if cached.is_some() => {
+                warn!("NeoWs lookup {} failed, serving local data: {:?}", neo_id, e);
+                return cached.ok_or_else(|| AppError::InternalError("NEO detail vanished".to_string()));
+            }
+            Err(e) => return Err(e),
+        }
+
+        self.space_repo.get_neo_detail(neo_id).await?
+            .ok_or_else(|| AppError::NotFound(format!("NEO {} not found", neo_id)))
+    }
+
+    #[instrument(skip(self), level = "info")]
+    async fn refresh_neo_lookups(&self) -> Result<u64, AppError> {
+        let older_than = Utc::now() - Duration::hours(NEO_LOOKUP_MAX_AGE_HOURS);
+        let stale = self.space_repo.get_stale_neo_lookups(older_than, NEO_LOOKUP_REFRESH_BATCH).await?;
+
+        let mut refreshed = 0;
+        for neo_id in stale {
+            match self.nasa_client.fetch_neo_lookup(&neo_id, self.nasa_api_key()).await {
+                Ok(Some(raw)) => {
+                    self.space_repo.save_neo_lookup(&raw).await?;
+                    refreshed += 1;
+                }
+                // Отметка времени и для 404: иначе объект навсегда остается в голове очереди устаревших
+                Ok(None) => {
+                    warn!("NEO {} disappeared from NeoWs lookup", neo_id);
+                    self.space_repo.touch_neo_lookup(&neo_id).await?;
+                }
+                Err(e) => warn!("NeoWs lookup refresh {} failed: {:?}", neo_id, e),
+            }
+        }
+        Ok(refreshed)
+    }
     
+    /// -------------------------------------------------------------------------------------
+    /// DONKI: каждый тип событий в своей таблице, сводка по типу в space_cache
+    /// -------------------------------------------------------------------------------------
     #[instrument(skip(self), level = "info")]
     async fn fetch_and_cache_donki(&self) -> Result<(), AppError> {
-        warn!("STUB: fetch_and_cache_donki not yet implemented in Service Layer.");
-        // TODO: Реализовать логику DONKI
-        Ok(())
+        // Источники независимы: сбой одного не мешает обновить остальные
+        let mut failed = Vec::new();
+        for kind in DONKI_KINDS {
+            if let Err(e) = self.fetch_and_cache_donki_source(kind).await {
+                error!("DONKI {} refresh failed: {:?}", kind, e);
+                failed.push(*kind);
+            }
+        }
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::ClientError(format!("DONKI refresh failed for: {}", failed.join(", ")), StatusCode::BAD_GATEWAY))
+        }
+    }
+
+    #[instrument(skip(self), level = "info")]
+    async fn fetch_and_cache_noaa(&self) -> Result<(), AppError> {
+        // Продукты независимы: сбой одного не мешает обновить остальные
+        let mut failed = Vec::new();
+        for product in NOAA_PRODUCTS {
+            let rows = match *product {
+                "kp" => self.noaa_client.fetch_kp_index().await,
+                "kp-forecast" => self.noaa_client.fetch_kp_forecast().await,
+                "plasma" => self.noaa_client.fetch_solar_wind_plasma().await,
+                _ => self.noaa_client.fetch_solar_wind_mag().await,
+            };
+            let written = match rows {
+                Ok(rows) if product.starts_with("kp") => self.space_repo.upsert_noaa_kp(&rows).await,
+                Ok(rows) => self.space_repo.upsert_solar_wind(&rows).await,
+                Err(e) => Err(e),
+            };
+            match written {
+                Ok(n) => info!("NOAA {} refresh: {} rows saved.", product, n),
+                Err(e) => {
+                    error!("NOAA {} refresh failed: {:?}", product, e);
+                    failed.push(*product);
+                }
+            }
+        }
+
+        // Сводка для /space/:src/latest: последнее наблюдение Kp и последняя точка ветра
+        let now = Utc::now();
+        let kp_latest = self.space_repo.get_noaa_kp(now - Duration::days(3), now).await?
+            .into_iter()
+            .rfind(|k| k.status == "observed");
+        let wind_latest = self.space_repo.get_solar_wind(now - Duration::days(1), now, 1).await?.pop();
+        let payload = serde_json::json!({ "kp_latest": kp_latest, "solar_wind_latest": wind_latest, "failed": failed });
+        self.space_repo.write_cache("noaa", &payload).await?;
+
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::ClientError(format!("NOAA refresh failed for: {}", failed.join(", ")), StatusCode::BAD_GATEWAY))
+        }
+    }
+
+    #[instrument(skip(self), level = "info")]
+    async fn fetch_and_cache_donki_source(&self, kind: &str) -> Result<(), AppError> {
+        let end = Utc::now().date_naive();
+        let start = end - Duration::days(DONKI_REFRESH_DAYS - 1);
+        let written = self.fetch_donki_range(kind, start, end).await?;
+        info!("DONKI {} refresh: {} events saved.", kind, written);
+
+        // Сводка для /space/:src/latest: число событий за окно и самое свежее
+        let (from, to) = (Utc::now() - Duration::days(DONKI_REFRESH_DAYS), Utc::now());
+        let summary = |count: usize, latest: Value| serde_json::json!({ "days": DONKI_REFRESH_DAYS, "count": count, "latest": latest });
+        let payload = match kind {
+            "flr" => {
+                let items = self.donki_repo.get_flares(from, to, None, i64::MAX).await?;
+                summary(items.len(), serde_json::to_value(items.first()).unwrap_or_default())
+            }
+            "cme" => {
+                let items = self.donki_repo.get_cmes(from, to, None, i64::MAX).await?;
+                summary(items.len(), serde_json::to_value(items.first()).unwrap_or_default())
+            }
+            "notifications" => {
+                let items = self.donki_repo.get_notifications(from, to, None, i64::MAX).await?;
+                summary(items.len(), serde_json::to_value(items.first()).unwrap_or_default())
+            }
+            _ => {
+                let items = self.donki_repo.get_events(kind, from, to, i64::MAX).await?;
+                summary(items.len(), serde_json::to_value(items.first()).unwrap_or_default())
+            }
+        };
+        self.space_repo.write_cache(kind, &payload).await
+    }
+
+    #[instrument(skip(self), level = "info")]
+    async fn fetch_donki_range(&self, kind: &str, start: NaiveDate, end: NaiveDate) -> Result<u64, AppError> {
+        if start > end {
+            return Err(AppError::ClientError(format!("Empty DONKI range {}..{}", start, end), StatusCode::BAD_REQUEST));
+        }
+        if (end - start).num_days() >= DONKI_MAX_RANGE_DAYS {
+            return Err(AppError::ClientError(
+                format!("DONKI range is limited to {} days", DONKI_MAX_RANGE_DAYS),
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+
+        let mut written = 0;
+        let mut window_start = start;
+        while window_start <= end {
+            let window_end = (window_start + Duration::days(DONKI_WINDOW_DAYS - 1)).min(end);
+            written += self.fetch_donki_window(kind, window_start, window_end).await?;
+            window_start = window_end + Duration::days(1);
+        }
+        Ok(written)
+    }
+
+    async fn get_donki_flares(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        class_prefix: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<DonkiFlare>, AppError> {
+        let (from, to) = Self::donki_bounds(start, end)?;
+        self.donki_repo.get_flares(from, to, class_prefix, limit.clamp(1, 1000)).await
+    }
+
+    async fn get_donki_cmes(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        min_speed: Option<f64>,
+        limit: i64,
+    ) -> Result<Vec<DonkiCme>, AppError> {
+        let (from, to) = Self::donki_bounds(start, end)?;
+        self.donki_repo.get_cmes(from, to, min_speed, limit.clamp(1, 1000)).await
+    }
+
+    async fn get_donki_events(&self, kind: &str, start: NaiveDate, end: NaiveDate, limit: i64) -> Result<Vec<DonkiEvent>, AppError> {
+        if !DONKI_EVENT_TYPES.iter().any(|t| t.0 == kind) {
+            return Err(AppError::NotFound(format!("Unknown DONKI event type '{}'", kind)));
+        }
+        let (from, to) = Self::donki_bounds(start, end)?;
+        self.donki_repo.get_events(kind, from, to, limit.clamp(1, 1000)).await
+    }
+
+    async fn get_donki_notifications(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        message_type: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<DonkiNotification>, AppError> {
+        let (from, to) = Self::donki_bounds(start, end)?;
+        self.donki_repo.get_notifications(from, to, message_type, limit.clamp(1, 1000)).await
+    }
+
+    #[instrument(skip(self), level = "info")]
+    async fn get_donki_chain(&self, activity_id: &str) -> Result<DonkiChain, AppError> {
+        let (mut nodes, links) = self.donki_repo.get_chain(activity_id).await?;
+        // Событие неизвестно ни как узел, ни как конец ребра
+        if links.is_empty() && nodes.iter().all(|n| n.kind.is_none()) {
+            return Err(AppError::NotFound(format!("DONKI event {} not found", activity_id)));
+        }
+
+        let stored: Vec<bool> = nodes.iter().map(|n| n.kind.is_some()).collect();
+        for n in &mut nodes {
+            n.fill_from_id();
+        }
+        let mut stages: Vec<(DonkiChainNode, bool)> = nodes.into_iter().zip(stored).collect();
+        // По времени; события без времени — в конце
+        stages.sort_by_key(|(n, _)| (n.event_time.is_none(), n.event_time, n.event_id.clone()));
+
+        let hours = |a: chrono::DateTime<Utc>, b: chrono::DateTime<Utc>| (b - a).num_seconds() as f64 / 3600.0;
+        let start = stages.first().and_then(|(n, _)| n.event_time);
+        let mut previous = None;
+        let stages: Vec<DonkiChainStage> = stages.into_iter().map(|(node, stored)| {
+            let stage = DonkiChainStage {
+                hours_since_previous: previous.zip(node.event_time).map(|(p, t)| hours(p, t)),
+                hours_since_start: start.zip(node.event_time).map(|(s, t)| hours(s, t)),
+                node,
+                stored,
+            };
+            previous = stage.node.event_time.or(previous);
+            stage
+        }).collect();
+
+        let total_hours = stages.iter().filter_map(|s| s.hours_since_start).reduce(f64::max);
+        Ok(DonkiChain { activity_id: activity_id.to_string(), stages, links, total_hours })
+    }
+
+    /// -------------------------------------------------------------------------------------
+    /// КОСМИЧЕСКАЯ ПОГОДА: сводный уровень и статистика по сохраненным данным DONKI
+    /// -------------------------------------------------------------------------------------
+    #[instrument(skip(self), level = "info")]
+    async fn get_space_weather_status(&self) -> Result<SpaceWeatherStatus, AppError> {
+        let now = Utc::now();
+        let mut level = 0;
+        let mut explanation = Vec::new();
+
+        // Вспышки: уровень по самому сильному классу (X10+ / X / M / C)
+        let flare_from = now - Duration::hours(SW_FLARE_WINDOW_HOURS);
+        let flares_by_class = self.donki_repo.get_flare_class_counts(flare_from, now).await?;
+        let strongest_flare = self.donki_repo.get_flares(flare_from, now, None, 1000).await?
+            .into_iter()
+            .filter_map(|f| f.class_type)
+            .max_by(|a, b| DonkiFlare::class_flux(a).partial_cmp(&DonkiFlare::class_flux(b)).unwrap_or(Ordering::Equal));
+        if let Some(class) = &strongest_flare {
+            let flux = DonkiFlare::class_flux(class).unwrap_or(0.0);
+            let flare_level = match flux {
+                f if f >= 1e-3 => 4,
+                f if f >= 1e-4 => 3,
+                f if f >= 1e-5 => 2,
+                f if f >= 1e-6 => 1,
+                _ => 0,
+            };
+            level = level.max(flare_level);
+            explanation.push(format!("strongest flare in last {}h: {}", SW_FLARE_WINDOW_HOURS, class));
+        }
+
+        // CME к Земле: уровень по скорости самого быстрого
+        let earth_directed_cmes = self.donki_repo
+            .get_earth_directed_cmes(now - Duration::days(SW_CME_WINDOW_DAYS), now).await?;
+        if let Some(fastest) = earth_directed_cmes.first() {
+            let speed = fastest.speed_kms.unwrap_or(0.0);
+            let cme_level = match speed {
+                s if s >= 1500.0 => 3,
+                s if s >= 1000.0 => 2,
+                _ => 1,
+            };
+            level = level.max(cme_level);
+            explanation.push(format!(
+                "{} Earth-directed CME(s) in last {} days, fastest {:.0} km/s",
+                earth_directed_cmes.len(), SW_CME_WINDOW_DAYS, speed,
+            ));
+        }
+
+        // Kp: шкала геомагнитных бурь G1 (Kp 5) .. G4+ (Kp 8+)
+        let kp_from = now - Duration::hours(SW_KP_WINDOW_HOURS);
+        let mut kp = self.donki_repo.get_kp_readings(kp_from, now).await?;
+        // NOAA SWPC: наблюдения и оценки (прогноз в статус не входит)
+        kp.extend(self.space_repo.get_noaa_kp(kp_from, now).await?
+            .into_iter()
+            .filter(|k| k.status != "predicted")
+            .map(|k| KpReading { observed_at: k.time_tag, kp: k.kp, source: "noaa".to_string() }));
+        kp.sort_by_key(|r| r.observed_at);
+        let kp_max = kp.iter().map(|r| r.kp).reduce(f64::max);
+        if let Some(max) = kp_max {
+            let kp_level = match max {
+                k if k >= 8.0 => 4,
+                k if k >= 7.0 => 3,
+                k if k >= 5.0 => 2,
+                k if k >= 4.0 => 1,
+                _ => 0,
+            };
+            level = level.max(kp_level);
+            explanation.push(format!("max Kp in last {}h: {:.2}", SW_KP_WINDOW_HOURS, max));
+        }
+
+        if explanation.is_empty() {
+            explanation.push("no flares, Earth-directed CMEs or geomagnetic storms in stored DONKI/NOAA data".to_string());
+        }
+
+        Ok(SpaceWeatherStatus {
+            level: SPACE_WEATHER_LEVELS[level].to_string(),
+            level_code: level,
+            explanation,
+            flares_by_class,
+            strongest_flare,
+            earth_directed_cmes,
+            kp_max,
+            kp,
+            computed_at: now,
+        })
+    }
+
+    async fn get_noaa_kp(&self, from: chrono::DateTime<Utc>, to: chrono::DateTime<Utc>) -> Result<Vec<NoaaKp>, AppError> {
+        if from > to {
+            return Err(AppError::ClientError("from must not be after to".to_string(), StatusCode::BAD_REQUEST));
+        }
+        self.space_repo.get_noaa_kp(from, to).await
+    }
+
+    async fn get_solar_wind(&self, from: chrono::DateTime<Utc>, to: chrono::DateTime<Utc>, limit: i64) -> Result<Vec<SolarWindSample>, AppError> {
+        if from > to {
+            return Err(AppError::ClientError("from must not be after to".to_string(), StatusCode::BAD_REQUEST));
+        }
+        self.space_repo.get_solar_wind(from, to, limit.clamp(1, 10_000)).await
+    }
+
+    async fn get_space_weather_stats(&self, months: i64) -> Result<SpaceWeatherStats, AppError> {
+        let months = months.clamp(1, 120);
+        let now = Utc::now();
+        // Начало месяца months-1 месяцев назад
+        let first = now.date_naive().with_day(1).expect("valid day")
+            .checked_sub_months(chrono::Months::new(months as u32 - 1))
+            .expect("valid month");
+        let from = first.and_hms_opt(0, 0, 0).expect("valid time").and_utc();
+
+        Ok(SpaceWeatherStats {
+            months,
+            flares_by_class: self.donki_repo.get_flare_monthly(from).await?,
+            cmes_by_speed: self.donki_repo.get_cme_monthly(from).await?,
+            computed_at: now,
+        })
     }
 
     #[instrument(skip(self), level = "info")]
     async fn fetch_and_cache_spacex(&self) -> Result<(), AppError> {
-        warn!("STUB: fetch_and_cache_spacex not yet implemented in Service Layer.");
-        // TODO: Реализовать логику SpaceX
-        Ok(())
+        // Сначала справочники: пуски ссылаются на ракеты и площадки по id
+        let rockets = self.spacex_client.fetch_rockets().await?;
+        let rockets_written = self.launch_repo.upsert_spacex_rockets(&rockets).await?;
+        let pads = self.spacex_client.fetch_launchpads().await?;
+        let pads_written = self.launch_repo.upsert_spacex_pads(&pads).await?;
+
+        let launches = self.spacex_client.fetch_launches().await?;
+        let stats = self.launch_repo.upsert_spacex_launches(&launches).await?;
+        let merged = self.launch_repo.merge_launch_duplicates().await?;
+        info!(
+            "SpaceX refresh: {} launches ({} changes, {} merged into Launch Library 2), {} rockets, {} pads.",
+            stats.written, stats.changes, merged, rockets_written, pads_written,
+        );
+
+        // Сводка для /space/:src/latest: итог синхронизации и ближайший пуск
+        let next = self.launch_repo.get_launches(true, 1).await?.pop();
+        let payload = serde_json::json!({ "launches": stats.written, "changes": stats.changes, "next": next });
+        self.space_repo.write_cache("spacex", &payload).await
+    }
+
+    #[instrument(skip(self), level = "info")]
+    async fn fetch_and_cache_ll2(&self) -> Result<(), AppError> {
+        // Списки независимы: сбой одного не мешает обновить другой
+        let limit = self.config.ll2_page_limit;
+        let mut written = 0;
+        let mut changes = 0;
+        let mut failed = Vec::new();
+        for list in ["upcoming", "previous"] {
+            let items = if list == "upcoming" {
+                self.ll2_client.fetch_upcoming_launches(limit).await
+            } else {
+                self.ll2_client.fetch_previous_launches(limit).await
+            };
+            match items {
+                Ok(items) => {
+                    let stats = self.launch_repo.upsert_ll2_launches(&items).await?;
+                    written += stats.written;
+                    changes += stats.changes;
+                }
+                Err(e) => {
+                    error!("Launch Library 2 {} refresh failed: {:?}", list, e);
+                    failed.push(list);
+                }
+            }
+        }
+        let merged = self.launch_repo.merge_launch_duplicates().await?;
+        info!("Launch Library 2 refresh: {} launches ({} changes), {} SpaceX duplicates merged.", written, changes, merged);
+
+        let next = self.launch_repo.get_launches(true, 1).await?.pop();
+        let payload = serde_json::json!({ "launches": written, "changes": changes, "merged": merged, "next": next });
+        self.space_repo.write_cache("ll2", &payload).await?;
+
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::ClientError(format!("Launch Library 2 refresh failed for: {}", failed.join(", ")), StatusCode::BAD_GATEWAY))
+        }
+    }
+
+    async fn get_launches(&self, upcoming: bool, limit: i64) -> Result<Vec<Launch>, AppError> {
+        self.launch_repo.get_launches(upcoming, limit.clamp(1, 500)).await
+    }
+
+    async fn get_launch(&self, launch_id: &str) -> Result<Launch, AppError> {
+        self.launch_repo.get_launch(launch_id).await?
+            .ok_or_else(|| AppError::NotFound(format!("Launch '{}' not found", launch_id)))
+    }
+
+    #[instrument(skip(self), level = "info")]
+    async fn fetch_and_store_iss_elements(&self) -> Result<IssElements, AppError> {
+        let raw = self.celestrak_client.fetch_iss_elements().await?;
+        let elements = IssElements::from_omm(&raw)
+            .ok_or_else(|| AppError::SerializationError("CelesTrak OMM is missing orbital elements".to_string()))?;
+        self.space_repo.save_iss_elements(&elements, &raw).await?;
+        info!("ISS elements stored: epoch {}, rev {}.", elements.epoch, elements.rev_at_epoch);
+
+        let payload = serde_json::json!({ "epoch": elements.epoch, "rev_at_epoch": elements.rev_at_epoch, "elements": raw });
+        self.space_repo.write_cache("tle", &payload).await?;
+        Ok(elements)
+    }
+
+    #[instrument(skip(self), level = "info")]
+    async fn get_iss_passes(&self, lat: f64, lon: f64, days: i64) -> Result<IssPassForecast, AppError> {
+        if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+            return Err(AppError::ClientError(format!("Invalid coordinates {},{}", lat, lon), StatusCode::BAD_REQUEST));
+        }
+        if !(1..=ISS_PASS_MAX_DAYS).contains(&days) {
+            return Err(AppError::ClientError(
+                format!("days must be within 1..={} (default {})", ISS_PASS_MAX_DAYS, ISS_PASS_DEFAULT_DAYS),
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+
+        // Точка наблюдения округляется до 0.01° (~1 км): ключ кэша и стабильных UID пролетов
+        let (lat_key, lon_key) = ((lat * 100.0).round() as i32, (lon * 100.0).round() as i32);
+        let (lat, lon) = (lat_key as f64 / 100.0, lon_key as f64 / 100.0);
+
+        let now = Utc::now();
+        let stored = self.space_repo.get_iss_elements().await?;
+        let elements = match stored {
+            Some((el, fetched_at)) if now - fetched_at < Duration::hours(ISS_ELEMENTS_MAX_AGE_HOURS) => el,
+            stored => match self.fetch_and_store_iss_elements().await {
+                Ok(el) => el,
+                // Без сети считаем по последним сохраненным элементам, если они есть
+                Err(e) => {
+                    warn!("ISS elements refresh failed: {:?}", e);
+                    stored.map(|(el, _)| el).ok_or_else(|| {
+                        AppError::ClientError("No ISS orbital elements available".to_string(), StatusCode::BAD_GATEWAY)
+                    })?
+                }
+            },
+        };
+
+        let horizon = now + Duration::days(days);
+        let run = self.space_repo.get_iss_pass_run(lat_key, lon_key).await?;
+        let computed_at = match run {
+            Some((epoch, horizon_end, computed_at))
+                if epoch == elements.epoch
+                    && horizon_end >= horizon
+                    && now - computed_at < Duration::hours(ISS_PASS_MAX_AGE_HOURS) => computed_at,
+            _ => {
+                // Считаем с запасом: максимальный горизонт плюс время жизни расчета — тогда
+                // запрос на ISS_PASS_MAX_DAYS попадает в кэш все ISS_PASS_MAX_AGE_HOURS
+                let horizon_end = now + Duration::days(ISS_PASS_MAX_DAYS) + Duration::hours(ISS_PASS_MAX_AGE_HOURS);
+                let el = elements.clone();
+                let passes = tokio::task::spawn_blocking(move || {
+                    orbit::visible_passes(&el, lat, lon, now, horizon_end, ISS_PASS_MIN_ELEVATION)
+                })
+                .await
+                .map_err(|e| AppError::InternalError(format!("ISS pass task failed: {}", e)))?;
+                let written = self.space_repo
+                    .replace_iss_passes(lat_key, lon_key, elements.epoch, now, horizon_end, &passes).await?;
+                info!("ISS passes for {},{}: {} computed from elements {}.", lat, lon, written, elements.epoch);
+                now
+            }
+        };
+
+        // Пролет, идущий прямо сейчас, тоже попадает в прогноз
+        let passes = self.space_repo.get_iss_passes(lat_key, lon_key, now - Duration::minutes(10), horizon).await?
+            .into_iter()
+            .filter(|p| p.end_at >= now)
+            .collect();
+
+        Ok(IssPassForecast { lat, lon, elements_epoch: elements.epoch, computed_at, passes })
+    }
+
+    #[instrument(skip(self), level = "info")]
+    async fn prune_iss_passes(&self) -> Result<(u64, u64), AppError> {
+        let now = Utc::now();
+        let (passes, points) = self.space_repo.prune_iss_passes(
+            now - Duration::hours(ISS_PASS_KEEP_PAST_HOURS),
+            now - Duration::days(ISS_PASS_POINT_IDLE_DAYS),
+        ).await?;
+        if passes + points > 0 {
+            info!("ISS passes pruned: {} passes, {} idle points.", passes, points);
+        }
+        Ok((passes, points))
+    }
+
+    #[instrument(skip(self), level = "info")]
+    async fn prune_space_cache(&self) -> Result<Vec<CachePruneReport>, AppError> {
+        let rules = &self.config.space_cache_retention;
+        let now = Utc::now();
+        let mut reports = Vec::new();
+
+        for source in self.space_repo.get_cache_sources().await? {
+            // Правило источника, иначе общее "*"; без правил источник не чистится
+            let Some(retention) = rules.get(&source).or_else(|| rules.get("*")).cloned() else { continue };
+            let older_than = retention.max_age_hours.map(|h| now - Duration::hours(h));
+            let (removed_by_age, removed_by_count, kept) =
+                self.space_repo.prune_cache(&source, older_than, retention.max_rows).await?;
+            if removed_by_age + removed_by_count > 0 {
+                info!(
+                    "space_cache '{}': removed {} by age, {} by count, {} kept.",
+                    source, removed_by_age, removed_by_count, kept
+                );
+            }
+            reports.push(CachePruneReport { source, retention, removed_by_age, removed_by_count, kept });
+        }
+
+        let removed: u64 = reports.iter().map(|r| r.removed_by_age + r.removed_by_count).sum();
+        info!("space_cache maintenance: {} rows removed across {} sources.", removed, reports.len());
+        Ok(reports)
     }
 }
 
@@ -173,4 +1829,16 @@ pub async fn get_iss_trend(_st: &crate::domain::models::AppState) -> Result<crat
         status: "ok".to_string(), // String
         message: "not implemented".to_string() // String
     })
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_miss_distance_sorts_as_farthest() {
+        assert_eq!(lunar_or_farthest(Some(3.5)).partial_cmp(&lunar_or_farthest(None)), Some(Ordering::Less));
+        assert_eq!(lunar_or_farthest(None).partial_cmp(&lunar_or_farthest(Some(1e9))), Some(Ordering::Greater));
+        assert_eq!(lunar_or_farthest(None).partial_cmp(&lunar_or_farthest(None)), Some(Ordering::Equal));
+    }
+}
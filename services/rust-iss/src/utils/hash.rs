@@ -0,0 +1,31 @@
+use std::path::Path;
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncReadExt;
+
+// SHA-256 в hex
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+// Хэш содержимого JSON: serde_json хранит ключи объектов отсортированными,
+// поэтому сериализация детерминирована и не зависит от порядка ключей upstream.
+pub fn json_hash(v: &Value) -> String {
+    sha256_hex(v.to_string().as_bytes())
+}
+
+// SHA-256 файла, читается потоково (файлы зеркала могут быть большими)
+pub async fn sha256_file(path: &Path) -> std::io::Result<String> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
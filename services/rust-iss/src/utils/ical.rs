@@ -0,0 +1,198 @@
+use chrono::{DateTime, NaiveDate, Utc};
+
+// Минимальный генератор iCalendar (RFC 5545) для подписок на календари:
+// строки через CRLF, сворачивание длиннее 75 октетов, экранирование текстовых значений.
+
+/// Начало/конец события: точное время (UTC) или весь день
+pub enum IcsTime {
+    At(DateTime<Utc>),
+    Date(NaiveDate),
+}
+
+pub struct IcsEvent {
+    pub uid: String,                     // стабилен между выгрузками: клиент заменяет событие, а не дублирует
+    pub sequence: i32,                   // растет при изменении события
+    pub stamp: DateTime<Utc>,
+    pub start: IcsTime,
+    pub end: IcsTime,
+    pub summary: String,
+    pub description: String,
+    pub location: Option<String>,
+    pub geo: Option<(f64, f64)>,
+    pub url: Option<String>,
+    pub tentative: bool,
+    pub alarm_minutes: Option<i64>,      // напоминание за N минут до начала
+}
+
+/// Экранирование TEXT: \ ; , и переводы строк
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace(';', "\\;").replace(',', "\\,").replace("\r\n", "\\n").replace('\n', "\\n")
+}
+
+/// Дописывает строку свойства, сворачивая ее по 75 октетов (не разрывая UTF-8 символы)
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for ch in line.chars() {
+        if width + ch.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(ch);
+        width += ch.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+fn format_time(name: &str, t: &IcsTime) -> String {
+    match t {
+        IcsTime::At(t) => format!("{}:{}", name, t.format("%Y%m%dT%H%M%SZ")),
+        IcsTime::Date(d) => format!("{};VALUE=DATE:{}", name, d.format("%Y%m%d")),
+    }
+}
+
+/// Календарь с событиями (text/calendar)
+pub fn calendar(name: &str, events: &[IcsEvent]) -> String {
+    let mut out = String::new();
+    for line in [
+        "BEGIN:VCALENDAR",
+        "VERSION:2.0",
+        "PRODID:-//rust-iss//space calendar//EN",
+        "CALSCALE:GREGORIAN",
+        "METHOD:PUBLISH",
+        "REFRESH-INTERVAL;VALUE=DURATION:PT1H",
+        "X-PUBLISHED-TTL:PT1H",
+    ] {
+        push_line(&mut out, line);
+    }
+    push_line(&mut out, &format!("X-WR-CALNAME:{}", escape(name)));
+
+    for e in events {
+        push_line(&mut out, "BEGIN:VEVENT");
+        push_line(&mut out, &format!("UID:{}", e.uid));
+        push_line(&mut out, &format!("SEQUENCE:{}", e.sequence));
+        push_line(&mut out, &format!("DTSTAMP:{}", e.stamp.format("%Y%m%dT%H%M%SZ")));
+        push_line(&mut out, &format_time("DTSTART", &e.start));
+        push_line(&mut out, &format_time("DTEND", &e.end));
+        push_line(&mut out, &format!("SUMMARY:{}", escape(&e.summary)));
+        push_line(&mut out, &format!("DESCRIPTION:{}", escape(&e.description)));
+        if let Some(location) = &e.location {
+            push_line(&mut out, &format!("LOCATION:{}", escape(location)));
+        }
+        if let Some((lat, lon)) = e.geo {
+            push_line(&mut out, &format!("GEO:{:.6};{:.6}", lat, lon));
+        }
+        if let Some(url) = &e.url {
+            push_line(&mut out, &format!("URL:{}", url));
+        }
+        push_line(&mut out, if e.tentative { "STATUS:TENTATIVE" } else { "STATUS:CONFIRMED" });
+        push_line(&mut out, "TRANSP:TRANSPARENT");
+        if let Some(minutes) = e.alarm_minutes {
+            push_line(&mut out, "BEGIN:VALARM");
+            push_line(&mut out, "ACTION:DISPLAY");
+            push_line(&mut out, &format!("DESCRIPTION:{}", escape(&e.summary)));
+            push_line(&mut out, &format!("TRIGGER:-PT{}M", minutes));
+            push_line(&mut out, "END:VALARM");
+        }
+        push_line(&mut out, "END:VEVENT");
+    }
+
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// Обратное сворачивание (RFC 5545, 3.1): CRLF + пробел убираются
+    fn unfold(s: &str) -> String {
+        s.replace("\r\n ", "")
+    }
+
+    #[test]
+    fn short_line_is_not_folded() {
+        let mut out = String::new();
+        push_line(&mut out, "SUMMARY:ISS");
+        assert_eq!(out, "SUMMARY:ISS\r\n");
+    }
+
+    #[test]
+    fn folds_at_75_octets() {
+        let line = format!("DESCRIPTION:{}", "x".repeat(150));
+        let mut out = String::new();
+        push_line(&mut out, &line);
+
+        let physical: Vec<&str> = out.trim_end_matches("\r\n").split("\r\n").collect();
+        assert_eq!(physical.len(), 3);
+        assert_eq!(physical[0].len(), 75);
+        assert!(physical[1..].iter().all(|l| l.starts_with(' ') && l.len() <= 75));
+        assert_eq!(unfold(&out), format!("{}\r\n", line));
+    }
+
+    #[test]
+    fn folding_keeps_utf8_characters_whole() {
+        // Кириллица — 2 октета на символ: граница в 75 октетов приходится на середину символа
+        let line = format!("SUMMARY:{}", "МКС над Москвой ".repeat(10));
+        let mut out = String::new();
+        push_line(&mut out, &line);
+
+        for l in out.split("\r\n") {
+            assert!(l.len() <= 75, "{} octets: {:?}", l.len(), l);
+        }
+        assert!(out.matches("\r\n ").count() >= 3);
+        assert_eq!(unfold(&out), format!("{}\r\n", line));
+    }
+
+    #[test]
+    fn escapes_text_values() {
+        assert_eq!(escape("a,b;c\\d"), "a\\,b\\;c\\\\d");
+        assert_eq!(escape("line 1\nline 2\r\nline 3"), "line 1\\nline 2\\nline 3");
+    }
+
+    #[test]
+    fn calendar_with_event() {
+        let start = Utc.with_ymd_and_hms(2024, 5, 1, 20, 15, 0).unwrap();
+        let ics = calendar(
+            "ISS, Moscow",
+            &[IcsEvent {
+                uid: "iss-56388@rust-iss".into(),
+                sequence: 2,
+                stamp: start,
+                start: IcsTime::At(start),
+                end: IcsTime::At(start + chrono::Duration::minutes(5)),
+                summary: "ISS pass; max 45°".into(),
+                description: "SW → NE".into(),
+                location: None,
+                geo: Some((55.75, 37.62)),
+                url: None,
+                tentative: false,
+                alarm_minutes: Some(10),
+            }],
+        );
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n") && ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(!ics.replace("\r\n", "").contains('\n'));
+        for line in [
+            "X-WR-CALNAME:ISS\\, Moscow",
+            "UID:iss-56388@rust-iss",
+            "SEQUENCE:2",
+            "DTSTART:20240501T201500Z",
+            "DTEND:20240501T202000Z",
+            "SUMMARY:ISS pass\\; max 45°",
+            "GEO:55.750000;37.620000",
+            "STATUS:CONFIRMED",
+            "BEGIN:VALARM",
+            "TRIGGER:-PT10M",
+            "END:VEVENT",
+        ] {
+            assert!(ics.contains(&format!("{}\r\n", line)), "missing {}", line);
+        }
+    }
+
+    #[test]
+    fn all_day_event() {
+        let day = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        assert_eq!(format_time("DTSTART", &IcsTime::Date(day)), "DTSTART;VALUE=DATE:20240501");
+    }
+}
@@ -0,0 +1,71 @@
+use std::path::Path;
+
+use image::imageops::FilterType;
+use image::io::{Limits, Reader};
+use image::{DynamicImage, ImageFormat};
+
+// Уменьшенные версии изображений APOD. Синхронно (декодирование/ресайз) — через spawn_blocking.
+
+/// Большая сторона миниатюры, px
+pub const THUMB_PX: u32 = 320;
+/// Большая сторона средней версии, px
+pub const MEDIUM_PX: u32 = 1024;
+/// Пределы декодирования: сторона и память под пиксели (защита от "пиксельных бомб")
+const DECODE_MAX_PX: u32 = 20_000;
+const DECODE_MAX_ALLOC: u64 = 1024 * 1024 * 1024;
+
+/// Строит thumb и medium (JPEG) из оригинала, возвращает размеры оригинала
+pub fn render_variants(original: &Path, thumb: &Path, medium: &Path) -> Result<(u32, u32), image::ImageError> {
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(DECODE_MAX_PX);
+    limits.max_image_height = Some(DECODE_MAX_PX);
+    limits.max_alloc = Some(DECODE_MAX_ALLOC);
+
+    // Формат — по сигнатуре файла, а не по расширению
+    let mut reader = Reader::open(original)?.with_guessed_format()?;
+    reader.limits(limits);
+    let img = reader.decode()?;
+    let (width, height) = (img.width(), img.height());
+
+    save_jpeg(&fit(&img, THUMB_PX, FilterType::Triangle), thumb)?;
+    save_jpeg(&fit(&img, MEDIUM_PX, FilterType::Lanczos3), medium)?;
+
+    Ok((width, height))
+}
+
+/// Вписывает изображение в квадрат max_px, не увеличивая маленькие
+fn fit(img: &DynamicImage, max_px: u32, filter: FilterType) -> DynamicImage {
+    if img.width() <= max_px && img.height() <= max_px {
+        img.clone()
+    } else {
+        img.resize(max_px, max_px, filter)
+    }
+}
+
+/// JPEG без альфа-канала (PNG/GIF с прозрачностью переводятся в RGB)
+fn save_jpeg(img: &DynamicImage, path: &Path) -> Result<(), image::ImageError> {
+    DynamicImage::ImageRgb8(img.to_rgb8()).save_with_format(path, ImageFormat::Jpeg)
+}
+
+/// Расширение и MIME-тип оригинала по Content-Type ответа; None — не поддерживаемое изображение.
+/// Только без Content-Type (или с application/octet-stream) смотрим на путь URL, по умолчанию JPEG.
+pub fn image_kind(content_type: Option<&str>, url: &str) -> Option<(&'static str, &'static str)> {
+    let mime = content_type
+        .and_then(|c| c.split(';').next())
+        .map(|c| c.trim().to_lowercase())
+        .filter(|c| !c.is_empty() && c != "application/octet-stream");
+    match mime.as_deref() {
+        Some("image/jpeg") | Some("image/jpg") | Some("image/pjpeg") => Some(("jpg", "image/jpeg")),
+        Some("image/png") => Some(("png", "image/png")),
+        Some("image/gif") => Some(("gif", "image/gif")),
+        Some(_) => None,
+        None => {
+            let path = url.split(['?', '#']).next().unwrap_or_default().to_lowercase();
+            match path.rsplit('.').next() {
+                Some("png") => Some(("png", "image/png")),
+                Some("gif") => Some(("gif", "image/gif")),
+                _ => Some(("jpg", "image/jpeg")),
+            }
+        }
+    }
+}
@@ -0,0 +1,445 @@
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::Path;
+
+use crate::domain::errors::AppError;
+use crate::domain::models::{IsaAnnotation, IsaArchive, IsaAssay, IsaProtocol, IsaSample};
+
+// Разбор ISA-Tab: investigation (i_*.txt), study (s_*.txt), assay (a_*.txt).
+// Функции синхронные (std::fs, zip) — из async-кода вызывать через spawn_blocking.
+
+/// Раздел investigation-файла: имя (INVESTIGATION, STUDY PROTOCOLS, ...) и строки "метка -> значения"
+type Section = (String, Vec<(String, Vec<String>)>);
+
+/// Предел размера одного файла ISA-Tab (распакованного): защита от zip-бомб
+const ISA_MAX_FILE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Файл ISA-Tab по имени: i_/s_/a_ префикс и расширение .txt
+pub fn is_isa_file(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    (lower.starts_with("i_") || lower.starts_with("s_") || lower.starts_with("a_")) && lower.ends_with(".txt")
+}
+
+/// Разбирает ISA-Tab из каталога (файлы в корне каталога)
+pub fn parse_dir(dir: &Path) -> Result<IsaArchive, AppError> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()).map(|n| n.to_string()) else { continue };
+        if path.is_file() && is_isa_file(&name) {
+            if std::fs::metadata(&path)?.len() > ISA_MAX_FILE_BYTES {
+                return Err(too_large(&name));
+            }
+            let bytes = std::fs::read(&path)?;
+            files.push((name, String::from_utf8_lossy(&bytes).into_owned()));
+        }
+    }
+    parse_files(&dir.to_string_lossy(), files)
+}
+
+/// Разбирает ISA-Tab из zip-архива (файлы могут лежать во вложенной папке)
+pub fn parse_zip(path: &Path) -> Result<IsaArchive, AppError> {
+    let zip_err = |e: zip::result::ZipError| {
+        AppError::SerializationError(format!("Invalid ISA-Tab zip {}: {}", path.display(), e))
+    };
+
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(path)?).map_err(zip_err)?;
+    let mut files = Vec::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(zip_err)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().rsplit('/').next().unwrap_or_default().to_string();
+        if !is_isa_file(&name) {
+            continue;
+        }
+        // Заявленный в архиве размер может врать — читаем не больше предела + 1 байт
+        if entry.size() > ISA_MAX_FILE_BYTES {
+            return Err(too_large(&name));
+        }
+        let mut bytes = Vec::new();
+        entry.by_ref().take(ISA_MAX_FILE_BYTES + 1).read_to_end(&mut bytes)?;
+        if bytes.len() as u64 > ISA_MAX_FILE_BYTES {
+            return Err(too_large(&name));
+        }
+        files.push((name, String::from_utf8_lossy(&bytes).into_owned()));
+    }
+    parse_files(&path.to_string_lossy(), files)
+}
+
+fn too_large(name: &str) -> AppError {
+    AppError::SerializationError(format!(
+        "ISA-Tab file {} exceeds {} MB", name, ISA_MAX_FILE_BYTES / 1024 / 1024
+    ))
+}
+
+/// Разбирает ISA-Tab из каталога зеркала датасета: распакованные i_*.txt,
+/// иначе zip с "ISA" в имени, иначе первый zip (по имени), в котором нашелся investigation-файл.
+pub fn parse_mirror(dir: &Path) -> Result<IsaArchive, AppError> {
+    let mut zips = Vec::new();
+    let mut has_investigation = false;
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_lowercase();
+        if !path.is_file() {
+            continue;
+        }
+        if name.starts_with("i_") && is_isa_file(&name) {
+            has_investigation = true;
+        } else if name.ends_with(".zip") {
+            zips.push((name.contains("isa"), path));
+        }
+    }
+    if has_investigation {
+        return parse_dir(dir);
+    }
+
+    // Сначала архивы с "ISA" в имени
+    zips.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    let mut last_err = None;
+    for (_, path) in zips {
+        match parse_zip(&path) {
+            Ok(archive) => return Ok(archive),
+            Err(e) => last_err = Some(e),
+        }
+    }
+    Err(last_err.unwrap_or_else(|| {
+        AppError::NotFound(format!("No ISA-Tab files in mirror {}", dir.display()))
+    }))
+}
+
+/// Разбирает набор файлов ISA-Tab (имя, содержимое)
+pub fn parse_files(source: &str, files: Vec<(String, String)>) -> Result<IsaArchive, AppError> {
+    let (_, investigation) = files.iter()
+        .find(|(name, _)| name.to_lowercase().starts_with("i_"))
+        .ok_or_else(|| AppError::NotFound(format!("No ISA-Tab investigation file (i_*.txt) in {}", source)))?;
+    let sections = parse_investigation(investigation);
+
+    let mut archive = IsaArchive {
+        identifier: first_value(&sections, "INVESTIGATION", "Investigation Identifier")
+            .or_else(|| first_value(&sections, "STUDY", "Study Identifier")),
+        title: first_value(&sections, "INVESTIGATION", "Investigation Title")
+            .or_else(|| first_value(&sections, "STUDY", "Study Title")),
+        source: source.to_string(),
+        ..Default::default()
+    };
+
+    // Колонки раздела выровнены: i-е значение каждой строки относится к i-му протоколу/ассею
+    for (_, rows) in sections.iter().filter(|(name, _)| name == "STUDY PROTOCOLS") {
+        let names = row_values(rows, "Study Protocol Name");
+        let types = row_values(rows, "Study Protocol Type");
+        let descriptions = row_values(rows, "Study Protocol Description");
+        for (i, name) in names.iter().enumerate().filter(|(_, n)| !n.is_empty()) {
+            if archive.protocols.iter().any(|p| p.name == *name) {
+                continue;
+            }
+            archive.protocols.push(IsaProtocol {
+                name: name.clone(),
+                protocol_type: non_empty(types.get(i)),
+                description: non_empty(descriptions.get(i)),
+            });
+        }
+    }
+
+    for (_, rows) in sections.iter().filter(|(name, _)| name == "STUDY ASSAYS") {
+        let names = row_values(rows, "Study Assay File Name");
+        let measurement = row_values(rows, "Study Assay Measurement Type");
+        let technology = row_values(rows, "Study Assay Technology Type");
+        let platform = row_values(rows, "Study Assay Technology Platform");
+        for (i, name) in names.iter().enumerate().filter(|(_, n)| !n.is_empty()) {
+            archive.assays.push(IsaAssay {
+                file_name: name.clone(),
+                measurement_type: non_empty(measurement.get(i)),
+                technology_type: non_empty(technology.get(i)),
+                technology_platform: non_empty(platform.get(i)),
+                sample_count: 0,
+            });
+        }
+    }
+
+    for (name, content) in files.iter().filter(|(n, _)| n.to_lowercase().starts_with("s_")) {
+        archive.samples.extend(parse_study_table(name, content));
+    }
+
+    // Таблицы ассеев: число образцов; файлы, не перечисленные в investigation, тоже учитываем
+    for (name, content) in files.iter().filter(|(n, _)| n.to_lowercase().starts_with("a_")) {
+        let sample_count = count_samples(content);
+        match archive.assays.iter_mut().find(|a| a.file_name == *name) {
+            Some(assay) => assay.sample_count = sample_count,
+            None => archive.assays.push(IsaAssay {
+                file_name: name.clone(),
+                sample_count,
+                ..Default::default()
+            }),
+        }
+    }
+
+    Ok(archive)
+}
+
+/// Строка TSV: значения без обрамляющих кавычек ("" внутри — экранированная кавычка)
+fn split_tsv(line: &str) -> Vec<String> {
+    line.trim_end_matches(['\r', '\n'])
+        .split('\t')
+        .map(|cell| {
+            let cell = cell.trim();
+            match cell.strip_prefix('"').and_then(|c| c.strip_suffix('"')) {
+                Some(inner) => inner.replace("\"\"", "\""),
+                None => cell.to_string(),
+            }
+        })
+        .collect()
+}
+
+fn data_lines(content: &str) -> impl Iterator<Item = &str> {
+    content.lines().filter(|l| !l.trim().is_empty() && !l.starts_with('#'))
+}
+
+/// Investigation-файл: заголовок раздела — строка из одной ячейки в верхнем регистре
+fn parse_investigation(content: &str) -> Vec<Section> {
+    let mut sections: Vec<Section> = Vec::new();
+    for line in data_lines(content) {
+        let mut cells = split_tsv(line);
+        let label = cells.remove(0);
+        let is_header = cells.iter().all(|c| c.is_empty())
+            && !label.is_empty()
+            && label.chars().all(|c| !c.is_lowercase());
+        if is_header {
+            sections.push((label, Vec::new()));
+        } else if let Some((_, rows)) = sections.last_mut() {
+            rows.push((label, cells));
+        }
+    }
+    sections
+}
+
+fn row_values(rows: &[(String, Vec<String>)], label: &str) -> Vec<String> {
+    rows.iter()
+        .find(|(l, _)| l == label)
+        .map(|(_, values)| values.clone())
+        .unwrap_or_default()
+}
+
+fn first_value(sections: &[Section], section: &str, label: &str) -> Option<String> {
+    sections.iter()
+        .filter(|(name, _)| name == section)
+        .find_map(|(_, rows)| non_empty(row_values(rows, label).first()))
+}
+
+fn non_empty(v: Option<&String>) -> Option<String> {
+    v.filter(|s| !s.is_empty()).cloned()
+}
+
+/// "Characteristics[organism]" -> "organism"
+fn bracket_name(column: &str, prefix: &str) -> Option<String> {
+    let name = column.strip_prefix(prefix)?.trim().strip_prefix('[')?.strip_suffix(']')?;
+    Some(name.trim().to_string())
+}
+
+/// Таблица исследования: строка — образец; Unit относится к предшествующей аннотации.
+/// Строки с повторяющимся Sample Name (ветвление протоколов) сливаются в один образец.
+fn parse_study_table(file: &str, content: &str) -> Vec<IsaSample> {
+    let mut lines = data_lines(content);
+    let Some(header) = lines.next().map(split_tsv) else { return vec![] };
+
+    let mut samples: Vec<IsaSample> = Vec::new();
+    for line in lines {
+        let cells = split_tsv(line);
+        let mut sample = IsaSample { study_file: file.to_string(), ..Default::default() };
+        // Куда относится ближайшая колонка Unit: Some(false) — характеристика, Some(true) — фактор
+        let mut last: Option<bool> = None;
+
+        for (i, column) in header.iter().enumerate() {
+            let value = cells.get(i).map(|c| c.trim()).unwrap_or_default();
+            if column == "Unit" {
+                let target = match last {
+                    Some(true) => sample.factor_values.last_mut(),
+                    Some(false) => sample.characteristics.last_mut(),
+                    None => None,
+                };
+                if let (Some(annotation), false) = (target, value.is_empty()) {
+                    annotation.unit = Some(value.to_string());
+                }
+                continue;
+            }
+            if column.starts_with("Term Source REF") || column.starts_with("Term Accession Number") {
+                continue;
+            }
+            last = None;
+
+            if column == "Source Name" {
+                sample.source_name = (!value.is_empty()).then(|| value.to_string());
+            } else if column == "Sample Name" {
+                sample.sample_name = value.to_string();
+            } else if column == "Protocol REF" {
+                if !value.is_empty() && !sample.protocols.iter().any(|p| p == value) {
+                    sample.protocols.push(value.to_string());
+                }
+            } else if let Some(category) = bracket_name(column, "Characteristics") {
+                if !value.is_empty() {
+                    sample.characteristics.push(IsaAnnotation { category, value: value.to_string(), unit: None });
+                    last = Some(false);
+                }
+            } else if let Some(category) = bracket_name(column, "Factor Value") {
+                if !value.is_empty() {
+                    sample.factor_values.push(IsaAnnotation { category, value: value.to_string(), unit: None });
+                    last = Some(true);
+                }
+            }
+        }
+
+        if sample.sample_name.is_empty() {
+            continue;
+        }
+        match samples.iter_mut().find(|s| s.sample_name == sample.sample_name) {
+            Some(existing) => merge_sample(existing, sample),
+            None => samples.push(sample),
+        }
+    }
+    samples
+}
+
+/// Дополняет образец данными повторной строки без дублирования аннотаций
+fn merge_sample(into: &mut IsaSample, from: IsaSample) {
+    if into.source_name.is_none() {
+        into.source_name = from.source_name;
+    }
+    for protocol in from.protocols {
+        if !into.protocols.contains(&protocol) {
+            into.protocols.push(protocol);
+        }
+    }
+    let same = |a: &IsaAnnotation, b: &IsaAnnotation| a.category == b.category && a.value == b.value;
+    for annotation in from.characteristics {
+        if !into.characteristics.iter().any(|a| same(a, &annotation)) {
+            into.characteristics.push(annotation);
+        }
+    }
+    for annotation in from.factor_values {
+        if !into.factor_values.iter().any(|a| same(a, &annotation)) {
+            into.factor_values.push(annotation);
+        }
+    }
+}
+
+/// Число различных образцов (Sample Name) в таблице ассея
+fn count_samples(content: &str) -> i32 {
+    let mut lines = data_lines(content);
+    let Some(header) = lines.next().map(split_tsv) else { return 0 };
+    let Some(idx) = header.iter().position(|c| c == "Sample Name") else { return 0 };
+
+    let names: HashSet<String> = lines
+        .filter_map(|l| split_tsv(l).get(idx).filter(|v| !v.is_empty()).cloned())
+        .collect();
+    names.len() as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INVESTIGATION: &str = "\
+ONTOLOGY SOURCE REFERENCE
+Term Source Name\tNCBITAXON
+INVESTIGATION
+Investigation Identifier\tOSD-999
+Investigation Title\t\"Mice in \"\"space\"\"\"
+STUDY
+Study Identifier\tOSD-999
+Study Title\tStudy title
+STUDY PROTOCOLS
+Study Protocol Name\tsample collection\tRNA extraction
+Study Protocol Type\tsample collection\t
+Study Protocol Description\tDissection\tKit
+STUDY ASSAYS
+Study Assay File Name\ta_rna.txt
+Study Assay Measurement Type\ttranscription profiling
+Study Assay Technology Type\tRNA Sequencing (RNA-Seq)
+Study Assay Technology Platform\tIllumina
+";
+
+    const STUDY: &str = "\
+Source Name\tCharacteristics[Organism]\tTerm Source REF\tTerm Accession Number\tProtocol REF\tSample Name\tFactor Value[Spaceflight]\tFactor Value[Duration]\tUnit
+# comment line
+mouse1\tMus musculus\tNCBITAXON\t10090\tsample collection\tS1\tSpace Flight\t30\tday
+
+mouse1\tMus musculus\tNCBITAXON\t10090\tRNA extraction\tS1\tSpace Flight\t30\tday
+mouse2\tMus musculus\t\t\tsample collection\tS2\tGround Control\t\t
+\t\t\t\t\t\t\t\t
+";
+
+    const ASSAY: &str = "\
+Sample Name\tProtocol REF\tExtract Name
+S1\tRNA extraction\tS1_a
+S1\tRNA extraction\tS1_b
+S2\tRNA extraction\tS2_a
+\t\t
+";
+
+    #[test]
+    fn split_tsv_strips_quotes_and_unescapes() {
+        assert_eq!(split_tsv("a\t\"b c\"\t\"say \"\"hi\"\"\"\t\r\n"), vec!["a", "b c", "say \"hi\"", ""]);
+        assert_eq!(split_tsv(" x \t\"\""), vec!["x", ""]);
+    }
+
+    #[test]
+    fn parse_investigation_splits_sections() {
+        let sections = parse_investigation(INVESTIGATION);
+        let names: Vec<&str> = sections.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, ["ONTOLOGY SOURCE REFERENCE", "INVESTIGATION", "STUDY", "STUDY PROTOCOLS", "STUDY ASSAYS"]);
+        assert_eq!(first_value(&sections, "INVESTIGATION", "Investigation Title").as_deref(), Some("Mice in \"space\""));
+        let (_, protocols) = &sections[3];
+        assert_eq!(row_values(protocols, "Study Protocol Type"), vec!["sample collection", ""]);
+    }
+
+    #[test]
+    fn parse_study_table_merges_repeated_samples() {
+        let samples = parse_study_table("s_study.txt", STUDY);
+        assert_eq!(samples.len(), 2);
+
+        let s1 = &samples[0];
+        assert_eq!(s1.sample_name, "S1");
+        assert_eq!(s1.source_name.as_deref(), Some("mouse1"));
+        assert_eq!(s1.study_file, "s_study.txt");
+        assert_eq!(s1.protocols, vec!["sample collection", "RNA extraction"]);
+        assert_eq!(s1.characteristics.len(), 1);
+        assert_eq!(s1.characteristics[0].category, "Organism");
+        assert_eq!(s1.characteristics[0].unit, None);
+        assert_eq!(s1.factor_values.len(), 2);
+        assert_eq!(s1.factor_values[1].category, "Duration");
+        assert_eq!(s1.factor_values[1].value, "30");
+        assert_eq!(s1.factor_values[1].unit.as_deref(), Some("day"));
+
+        let s2 = &samples[1];
+        assert_eq!(s2.factor_values.len(), 1);
+        assert_eq!(s2.factor_values[0].value, "Ground Control");
+        assert_eq!(s2.factor_values[0].unit, None);
+    }
+
+    #[test]
+    fn count_samples_counts_distinct_names() {
+        assert_eq!(count_samples(ASSAY), 2);
+        assert_eq!(count_samples("Extract Name\nE1\n"), 0);
+        assert_eq!(count_samples(""), 0);
+    }
+
+    #[test]
+    fn parse_files_builds_archive() {
+        let files = vec![
+            ("i_Investigation.txt".to_string(), INVESTIGATION.to_string()),
+            ("s_study.txt".to_string(), STUDY.to_string()),
+            ("a_rna.txt".to_string(), ASSAY.to_string()),
+            ("a_extra.txt".to_string(), ASSAY.to_string()),
+        ];
+        let archive = parse_files("fixture", files).unwrap();
+        assert_eq!(archive.identifier.as_deref(), Some("OSD-999"));
+        assert_eq!(archive.protocols.len(), 2);
+        assert_eq!(archive.protocols[1].protocol_type, None);
+        assert_eq!(archive.samples.len(), 2);
+        assert_eq!(archive.assays.len(), 2);
+        assert_eq!(archive.assays[0].technology_platform.as_deref(), Some("Illumina"));
+        assert_eq!(archive.assays[0].sample_count, 2);
+        assert_eq!(archive.assays[1].file_name, "a_extra.txt");
+    }
+}
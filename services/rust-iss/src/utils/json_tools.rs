@@ -1,5 +1,5 @@
 use serde_json::Value;
-use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
 
 // Выделение логики num, s_pick, t_pick
 pub fn num(v: &Value) -> Option<f64> {
@@ -22,7 +22,16 @@ pub fn t_pick(v: &Value, keys: &[&str]) -> Option<DateTime<Utc>> {
         if let Some(x) = v.get(*k) {
             if let Some(s) = x.as_str() {
                 if let Ok(dt) = s.parse::<DateTime<Utc>>() { return Some(dt); }
-                if let Ok(ndt) = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S") {
+                // "2024-05-10 00:00:00" и NOAA SWPC "2024-05-10 00:00:00.000"
+                if let Ok(ndt) = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f") {
+                    return Some(Utc.from_utc_datetime(&ndt));
+                }
+                // NOAA SWPC (объектные продукты): "2024-05-10T03:00:00" без зоны — UTC
+                if let Ok(ndt) = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f") {
+                    return Some(Utc.from_utc_datetime(&ndt));
+                }
+                // DONKI: "2024-05-10T06:27Z" (без секунд)
+                if let Ok(ndt) = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%MZ") {
                     return Some(Utc.from_utc_datetime(&ndt));
                 }
             } else if let Some(n) = x.as_i64() {
@@ -31,4 +40,59 @@ pub fn t_pick(v: &Value, keys: &[&str]) -> Option<DateTime<Utc>> {
         }
     }
     None
-}
\ No newline at end of file
+}
+
+// Значение по ключу; ключ, начинающийся с '/', трактуется как JSON Pointer (вложенные поля)
+fn lookup<'a>(v: &'a Value, key: &str) -> Option<&'a Value> {
+    if key.starts_with('/') { v.pointer(key) } else { v.get(key) }
+}
+
+fn scalar_str(x: &Value) -> Option<String> {
+    if let Some(s) = x.as_str() { let s = s.trim(); if !s.is_empty() { return Some(s.to_string()); } }
+    else if x.is_number() { return Some(x.to_string()); }
+    None
+}
+
+// Как s_pick, но понимает вложенные пути и массивы (берется первый непустой элемент)
+pub fn s_pick_any(v: &Value, keys: &[&str]) -> Option<String> {
+    for k in keys {
+        if let Some(x) = lookup(v, k) {
+            if let Some(arr) = x.as_array() {
+                if let Some(s) = arr.iter().find_map(scalar_str) { return Some(s); }
+            } else if let Some(s) = scalar_str(x) { return Some(s); }
+        }
+    }
+    None
+}
+
+// Список строк: массив (строки или объекты с name) либо строка с разделителями , ; |
+pub fn list_pick(v: &Value, keys: &[&str]) -> Vec<String> {
+    for k in keys {
+        let Some(x) = lookup(v, k) else { continue };
+        let mut out: Vec<String> = match x {
+            Value::Array(arr) => arr.iter()
+                .filter_map(|e| scalar_str(e).or_else(|| s_pick(e, &["name", "label", "value"])))
+                .collect(),
+            Value::String(s) => s.split([',', ';', '|'])
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty())
+                .collect(),
+            _ => vec![],
+        };
+        out.dedup();
+        if !out.is_empty() { return out; }
+    }
+    vec![]
+}
+
+pub fn d_pick(v: &Value, keys: &[&str]) -> Option<NaiveDate> {
+    for k in keys {
+        if let Some(s) = lookup(v, k).and_then(scalar_str) {
+            for fmt in ["%Y-%m-%d", "%m/%d/%Y", "%d-%b-%Y", "%Y/%m/%d"] {
+                if let Ok(d) = NaiveDate::parse_from_str(&s, fmt) { return Some(d); }
+            }
+            if let Ok(dt) = s.parse::<DateTime<Utc>>() { return Some(dt.date_naive()); }
+        }
+    }
+    t_pick(v, keys).map(|dt| dt.date_naive())
+}
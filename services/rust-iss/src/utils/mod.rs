@@ -1,4 +1,9 @@
 pub mod haversine;
+pub mod hash;
+pub mod ical;
+pub mod imaging;
+pub mod isatab;
 pub mod json_tools;
+pub mod orbit;
 pub mod retry; // Пустой модуль
 pub mod trace; // Пустой модуль
\ No newline at end of file
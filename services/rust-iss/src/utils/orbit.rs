@@ -0,0 +1,360 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::domain::models::{IssElements, IssPass};
+
+// Прогноз пролетов МКС по средним элементам орбиты (CelesTrak GP).
+// Движение — кеплерово с вековыми поправками J2 (узел, перигей) и торможением (ṅ/2);
+// короткопериодические члены SGP4 не учитываются: ошибка — секунды по времени пролета
+// на горизонте в несколько суток от эпохи элементов, для календаря этого достаточно.
+
+const MU_KM3_S2: f64 = 398_600.441_8;
+const EARTH_RADIUS_KM: f64 = 6378.137;
+const EARTH_FLATTENING: f64 = 1.0 / 298.257_223_563;
+const J2: f64 = 1.082_626_68e-3;
+const AU_KM: f64 = 149_597_870.7;
+const TWO_PI: f64 = std::f64::consts::TAU;
+
+/// Шаг поиска пролетов (секунды): пролет над горизонтом длится минуты
+const SCAN_STEP_SECS: i64 = 20;
+/// Солнце ниже этой высоты — у наблюдателя достаточно темно (гражданские сумерки)
+const SUN_MAX_ELEVATION_DEG: f64 = -6.0;
+
+type Vec3 = [f64; 3];
+
+fn dot(a: Vec3, b: Vec3) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn julian_date(t: DateTime<Utc>) -> f64 {
+    t.timestamp_millis() as f64 / 86_400_000.0 + 2_440_587.5
+}
+
+/// Гринвичское среднее звездное время (радианы)
+fn gmst(t: DateTime<Utc>) -> f64 {
+    let d = julian_date(t) - 2_451_545.0;
+    (280.460_618_37 + 360.985_647_366_29 * d).rem_euclid(360.0).to_radians()
+}
+
+/// Инерциальные координаты -> земные (поворот на звездное время)
+fn eci_to_ecef(r: Vec3, t: DateTime<Utc>) -> Vec3 {
+    let (s, c) = gmst(t).sin_cos();
+    [c * r[0] + s * r[1], -s * r[0] + c * r[1], r[2]]
+}
+
+/// Положение МКС в инерциальной системе (км) на момент t
+fn position_eci(el: &IssElements, t: DateTime<Utc>) -> Vec3 {
+    let dt = (t - el.epoch).num_milliseconds() as f64 / 1000.0;
+    let days = dt / 86_400.0;
+    let n = el.mean_motion * TWO_PI / 86_400.0;
+    let a = (MU_KM3_S2 / (n * n)).cbrt();
+    let e = el.eccentricity;
+    let i = el.inclination_deg.to_radians();
+    let p = a * (1.0 - e * e);
+
+    // Вековой дрейф узла и перигея от сжатия Земли
+    let k = 1.5 * J2 * (EARTH_RADIUS_KM / p).powi(2) * n;
+    let raan = el.raan_deg.to_radians() - k * i.cos() * dt;
+    let argp = el.arg_perigee_deg.to_radians() + 0.5 * k * (5.0 * i.cos().powi(2) - 1.0) * dt;
+    let m = el.mean_anomaly_deg.to_radians() + n * dt + TWO_PI * el.mean_motion_dot * days * days;
+
+    // Уравнение Кеплера (Ньютон)
+    let mut ecc_anomaly = m;
+    for _ in 0..10 {
+        ecc_anomaly -= (ecc_anomaly - e * ecc_anomaly.sin() - m) / (1.0 - e * ecc_anomaly.cos());
+    }
+    let xp = a * (ecc_anomaly.cos() - e);
+    let yp = a * (1.0 - e * e).sqrt() * ecc_anomaly.sin();
+
+    let (so, co) = argp.sin_cos();
+    let (sn, cn) = raan.sin_cos();
+    let (si, ci) = i.sin_cos();
+    [
+        (cn * co - sn * so * ci) * xp + (-cn * so - sn * co * ci) * yp,
+        (sn * co + cn * so * ci) * xp + (-sn * so + cn * co * ci) * yp,
+        (so * si) * xp + (co * si) * yp,
+    ]
+}
+
+/// Номер витка на момент t (виток начинается в восходящем узле)
+fn revolution(el: &IssElements, t: DateTime<Utc>) -> i64 {
+    let dt = (t - el.epoch).num_milliseconds() as f64 / 1000.0;
+    let days = dt / 86_400.0;
+    let n = el.mean_motion * TWO_PI / 86_400.0;
+    let u0 = (el.arg_perigee_deg + el.mean_anomaly_deg).to_radians().rem_euclid(TWO_PI);
+    let du = n * dt + TWO_PI * el.mean_motion_dot * days * days;
+    el.rev_at_epoch + ((u0 + du) / TWO_PI).floor() as i64
+}
+
+/// Направление на Солнце в инерциальной системе (км), низкоточная формула Астрономического ежегодника
+fn sun_eci(t: DateTime<Utc>) -> Vec3 {
+    let d = julian_date(t) - 2_451_545.0;
+    let l = (280.460 + 0.985_647_4 * d).to_radians();
+    let g = (357.528 + 0.985_600_3 * d).to_radians();
+    let lambda = l + (1.915 * g.sin() + 0.020 * (2.0 * g).sin()).to_radians();
+    let eps = (23.439 - 0.000_000_4 * d).to_radians();
+    [AU_KM * lambda.cos(), AU_KM * eps.cos() * lambda.sin(), AU_KM * eps.sin() * lambda.sin()]
+}
+
+/// МКС освещена Солнцем (цилиндрическая тень Земли)
+fn is_sunlit(r: Vec3, sun: Vec3) -> bool {
+    let sun_norm = dot(sun, sun).sqrt();
+    let s = [sun[0] / sun_norm, sun[1] / sun_norm, sun[2] / sun_norm];
+    let proj = dot(r, s);
+    if proj >= 0.0 {
+        return true;
+    }
+    let perp = [r[0] - proj * s[0], r[1] - proj * s[1], r[2] - proj * s[2]];
+    dot(perp, perp).sqrt() > EARTH_RADIUS_KM
+}
+
+/// Точка наблюдения (WGS84, высота над эллипсоидом в км) в земных координатах
+struct Observer {
+    lat: f64,
+    lon: f64,
+    ecef: Vec3,
+}
+
+impl Observer {
+    fn new(lat_deg: f64, lon_deg: f64, alt_km: f64) -> Self {
+        let (lat, lon) = (lat_deg.to_radians(), lon_deg.to_radians());
+        let e2 = EARTH_FLATTENING * (2.0 - EARTH_FLATTENING);
+        let n = EARTH_RADIUS_KM / (1.0 - e2 * lat.sin().powi(2)).sqrt();
+        let ecef = [
+            (n + alt_km) * lat.cos() * lon.cos(),
+            (n + alt_km) * lat.cos() * lon.sin(),
+            (n * (1.0 - e2) + alt_km) * lat.sin(),
+        ];
+        Self { lat, lon, ecef }
+    }
+
+    /// Высота и азимут (градусы) точки, заданной в земных координатах
+    fn look_at(&self, target: Vec3) -> (f64, f64) {
+        let d = [target[0] - self.ecef[0], target[1] - self.ecef[1], target[2] - self.ecef[2]];
+        let (slat, clat) = self.lat.sin_cos();
+        let (slon, clon) = self.lon.sin_cos();
+        let east = -slon * d[0] + clon * d[1];
+        let north = -slat * clon * d[0] - slat * slon * d[1] + clat * d[2];
+        let up = clat * clon * d[0] + clat * slon * d[1] + slat * d[2];
+        let elevation = up.atan2((east * east + north * north).sqrt()).to_degrees();
+        let azimuth = east.atan2(north).to_degrees().rem_euclid(360.0);
+        (elevation, azimuth)
+    }
+}
+
+/// Состояние МКС для наблюдателя на момент t
+struct Sample {
+    t: DateTime<Utc>,
+    elevation: f64,
+    azimuth: f64,
+    visible: bool,
+}
+
+fn sample(el: &IssElements, obs: &Observer, t: DateTime<Utc>, min_elevation: f64) -> Sample {
+    let r = position_eci(el, t);
+    let sun = sun_eci(t);
+    let (elevation, azimuth) = obs.look_at(eci_to_ecef(r, t));
+    let visible = elevation >= min_elevation
+        && is_sunlit(r, sun)
+        && obs.look_at(eci_to_ecef(sun, t)).0 < SUN_MAX_ELEVATION_DEG;
+    Sample { t, elevation, azimuth, visible }
+}
+
+/// Момент смены видимости между lo и hi (бисекция до секунды)
+fn refine_edge(el: &IssElements, obs: &Observer, min_elevation: f64, mut lo: DateTime<Utc>, mut hi: DateTime<Utc>) -> Sample {
+    let visible_at_lo = sample(el, obs, lo, min_elevation).visible;
+    while hi - lo > Duration::seconds(1) {
+        let mid = lo + (hi - lo) / 2;
+        if sample(el, obs, mid, min_elevation).visible == visible_at_lo {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    // Возвращаем видимую сторону границы
+    if visible_at_lo { sample(el, obs, lo, min_elevation) } else { sample(el, obs, hi, min_elevation) }
+}
+
+/// Видимые пролеты МКС над точкой (lat, lon) в окне [from, to]
+pub fn visible_passes(
+    el: &IssElements,
+    lat: f64,
+    lon: f64,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    min_elevation: f64,
+) -> Vec<IssPass> {
+    let obs = Observer::new(lat, lon, 0.0);
+    let step = Duration::seconds(SCAN_STEP_SECS);
+    let mut passes = Vec::new();
+
+    let mut prev = sample(el, &obs, from, min_elevation);
+    let mut start: Option<Sample> = None;
+    let mut peak: Option<Sample> = None;
+    let mut t = from;
+    while t < to {
+        t += step;
+        let cur = sample(el, &obs, t, min_elevation);
+
+        if cur.visible && !prev.visible {
+            start = Some(refine_edge(el, &obs, min_elevation, prev.t, cur.t));
+        } else if cur.visible && start.is_none() {
+            start = Some(sample(el, &obs, prev.t, min_elevation));
+        }
+        if cur.visible && peak.as_ref().is_none_or(|p| cur.elevation > p.elevation) {
+            peak = Some(sample(el, &obs, cur.t, min_elevation));
+        }
+
+        if !cur.visible && prev.visible {
+            if let (Some(s), Some(p)) = (start.take(), peak.take()) {
+                let end = refine_edge(el, &obs, min_elevation, prev.t, cur.t);
+                // Уточняем максимум посекундно в окрестности шага; если МКС ушла в тень
+                // на подъеме (или вышла из нее на спуске), максимум приходится на границу
+                let best = (-SCAN_STEP_SECS..=SCAN_STEP_SECS)
+                    .map(|dt| sample(el, &obs, p.t + Duration::seconds(dt), min_elevation))
+                    .filter(|x| x.visible && x.t >= s.t && x.t <= end.t)
+                    .chain([sample(el, &obs, s.t, min_elevation), sample(el, &obs, end.t, min_elevation)])
+                    .max_by(|a, b| a.elevation.total_cmp(&b.elevation))
+                    .unwrap_or(p);
+                passes.push(IssPass {
+                    rev: revolution(el, best.t),
+                    start_at: s.t,
+                    max_at: best.t,
+                    end_at: end.t,
+                    start_elevation: s.elevation,
+                    max_elevation: best.elevation,
+                    end_elevation: end.elevation,
+                    start_azimuth: s.azimuth,
+                    max_azimuth: best.azimuth,
+                    end_azimuth: end.azimuth,
+                    revision: 0,
+                });
+            }
+        }
+        prev = cur;
+    }
+    passes
+}
+
+/// Румб по азимуту: N, NNE, NE, ...
+pub fn compass_point(azimuth: f64) -> &'static str {
+    const POINTS: [&str; 16] = [
+        "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSW", "SW", "WSW", "W", "WNW", "NW", "NNW",
+    ];
+    POINTS[((azimuth.rem_euclid(360.0) / 22.5).round() as usize) % 16]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    // TLE МКС из описания формата (CelesTrak/Wikipedia):
+    // 1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927
+    // 2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537
+    fn iss_2008() -> IssElements {
+        IssElements::from_omm(&serde_json::json!({
+            "EPOCH": "2008-09-20T12:25:40.104192",
+            "MEAN_MOTION": 15.72125391,
+            "ECCENTRICITY": 0.0006703,
+            "INCLINATION": 51.6416,
+            "RA_OF_ASC_NODE": 247.4627,
+            "ARG_OF_PERICENTER": 130.536,
+            "MEAN_ANOMALY": 325.0288,
+            "MEAN_MOTION_DOT": -0.00002182,
+            "REV_AT_EPOCH": 56353,
+        }))
+        .expect("valid OMM")
+    }
+
+    fn norm(r: Vec3) -> f64 {
+        dot(r, r).sqrt()
+    }
+
+    #[test]
+    fn elements_from_omm() {
+        let el = iss_2008();
+        assert_eq!(el.epoch, Utc.with_ymd_and_hms(2008, 9, 20, 12, 25, 40).unwrap() + Duration::microseconds(104_192));
+        assert_eq!(el.rev_at_epoch, 56353);
+    }
+
+    #[test]
+    fn position_at_epoch_matches_elements() {
+        let el = iss_2008();
+        let r = position_eci(&el, el.epoch);
+        // Высота ~350 км (ISS в сентябре 2008), широта = asin(sin i · sin u), u = ω + M ≈ 95.56°
+        let altitude = norm(r) - EARTH_RADIUS_KM;
+        assert!((330.0..380.0).contains(&altitude), "altitude {}", altitude);
+        let lat = (r[2] / norm(r)).asin().to_degrees();
+        assert!((lat - 51.27).abs() < 0.1, "latitude {}", lat);
+    }
+
+    #[test]
+    fn latitude_never_exceeds_inclination() {
+        let el = iss_2008();
+        for minutes in (0..24 * 60).step_by(3) {
+            let r = position_eci(&el, el.epoch + Duration::minutes(minutes));
+            let lat = (r[2] / norm(r)).asin().to_degrees();
+            assert!(lat.abs() <= el.inclination_deg + 0.01, "latitude {} at +{} min", lat, minutes);
+        }
+    }
+
+    #[test]
+    fn revolution_counts_from_ascending_node() {
+        let el = iss_2008();
+        // u0 ≈ 95.56°: до следующего восходящего узла (u = 360°) ≈ 264.44° из 360° периода 91.596 мин
+        let period_secs = 86_400.0 / el.mean_motion;
+        let to_node = period_secs * (360.0 - 95.5648) / 360.0;
+        assert_eq!(revolution(&el, el.epoch), 56353);
+        assert_eq!(revolution(&el, el.epoch + Duration::seconds((to_node - 30.0) as i64)), 56353);
+        assert_eq!(revolution(&el, el.epoch + Duration::seconds((to_node + 30.0) as i64)), 56354);
+        assert_eq!(revolution(&el, el.epoch + Duration::seconds((to_node + 30.0 + 10.0 * period_secs) as i64)), 56364);
+    }
+
+    #[test]
+    fn sun_direction_at_solstice() {
+        // Летнее солнцестояние 2008: 20 июня 23:59 UTC, склонение Солнца +23.44°
+        let sun = sun_eci(Utc.with_ymd_and_hms(2008, 6, 20, 23, 59, 0).unwrap());
+        let declination = (sun[2] / norm(sun)).asin().to_degrees();
+        assert!((declination - 23.44).abs() < 0.1, "declination {}", declination);
+    }
+
+    #[test]
+    fn passes_are_consistent() {
+        let el = iss_2008();
+        let (lat, lon) = (55.75, 37.62);
+        let passes = visible_passes(&el, lat, lon, el.epoch, el.epoch + Duration::days(3), 10.0);
+        assert!(!passes.is_empty(), "ISS is visible from Moscow in late September evenings");
+
+        let obs = Observer::new(lat, lon, 0.0);
+        for p in &passes {
+            assert!(p.start_at <= p.max_at && p.max_at <= p.end_at, "pass {:?}", p);
+            assert!(p.end_at - p.start_at < Duration::minutes(12), "pass {:?}", p);
+            assert!(p.max_elevation >= 10.0 && p.max_elevation <= 90.0);
+            assert!(p.max_elevation >= p.start_elevation && p.max_elevation >= p.end_elevation, "pass {:?}", p);
+            // Сохраненные углы совпадают с пересчетом на тот же момент
+            let s = sample(&el, &obs, p.max_at, 10.0);
+            assert!(s.visible);
+            assert!((s.elevation - p.max_elevation).abs() < 1e-9 && (s.azimuth - p.max_azimuth).abs() < 1e-9);
+        }
+        for w in passes.windows(2) {
+            assert!(w[0].end_at < w[1].start_at && w[0].rev < w[1].rev);
+        }
+    }
+
+    #[test]
+    fn no_passes_far_beyond_inclination() {
+        let el = iss_2008();
+        let passes = visible_passes(&el, 85.0, 0.0, el.epoch, el.epoch + Duration::days(2), 10.0);
+        assert!(passes.is_empty());
+    }
+
+    #[test]
+    fn compass_points() {
+        assert_eq!(compass_point(0.0), "N");
+        assert_eq!(compass_point(359.0), "N");
+        assert_eq!(compass_point(22.5), "NNE");
+        assert_eq!(compass_point(90.0), "E");
+        assert_eq!(compass_point(200.0), "SSW");
+        assert_eq!(compass_point(-45.0), "NW");
+    }
+}